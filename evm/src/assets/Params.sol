@@ -31,6 +31,8 @@ uint constant TRADERJOE_BINSTEP_SIZE = 2;
 uint constant SHARED_POOL_ID_SIZE = 3;
 uint constant SHARED_PATH_ELEMENT_SIZE = ADDRESS_SIZE + SHARED_POOL_ID_SIZE;
 
+uint constant ORCA_WHIRLPOOL_POOL_SIZE = UNIVERSAL_ADDRESS_SIZE + BOOL_SIZE;
+
 uint constant RELAY_GAS_DROPOFF_SIZE     = 4;
 uint constant RELAY_MAX_RELAYER_FEE_SIZE = 6;
 uint constant RELAY_PARAM_SIZE = RELAY_GAS_DROPOFF_SIZE + RELAY_MAX_RELAYER_FEE_SIZE;
@@ -42,6 +44,7 @@ uint8 constant SWAP_TYPE_UNISWAPV3 = 1;
 uint8 constant SWAP_TYPE_TRADERJOE = 2;
 //group solana swap types starting at 16
 uint8 constant SWAP_TYPE_JUPITERV6 = 16;
+uint8 constant SWAP_TYPE_ORCA_WHIRLPOOL = 18;
 
 enum IoToken {
   Usdc,
@@ -70,6 +73,9 @@ error InvalidAddress(bytes32 addr);
 //   1 byte   isSome
 //    0: -
 //    1: 32 bytes jupiter dex
+// * orca whirlpool (solana):
+//   1 byte   poolsLength (1 or 2)
+//   n bytes  pools (n = poolsLength * (32+1) (whirlpool address + aToB))
 
 function parseEvmSwapParams(
   address inputToken,
@@ -131,6 +137,13 @@ function parseSwapTypeAndCountAndSkipParams(
     //So we return 1 here to indicate that a Solana swap is requested.
     swapCount = 1;
   }
+  else if (swapType == SWAP_TYPE_ORCA_WHIRLPOOL) {
+    uint poolsLength;
+    (poolsLength, offset) = params.asUint8Unchecked(offset);
+    offset += poolsLength * ORCA_WHIRLPOOL_POOL_SIZE;
+    //see jupiterv6 above
+    swapCount = 1;
+  }
   else
     (swapType, swapCount, offset) = (SWAP_TYPE_INVALID, 0, params.length);
 
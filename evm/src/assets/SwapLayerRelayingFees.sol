@@ -15,6 +15,7 @@ import {
   SWAP_TYPE_UNISWAPV3,
   SWAP_TYPE_TRADERJOE,
   SWAP_TYPE_JUPITERV6,
+  SWAP_TYPE_ORCA_WHIRLPOOL,
   IoToken
 } from "./Params.sol";
 
@@ -152,7 +153,11 @@ abstract contract SwapLayerRelayingFees is SwapLayerBase {
 
     if (targetChain == SOLANA_CHAIN_ID) {
       //TODO figure out what other (dynamic) fees might go into Solana fee calculations
-      if (swapCount != 0 && swapType != SWAP_TYPE_JUPITERV6)
+      if (
+        swapCount != 0 &&
+        swapType != SWAP_TYPE_JUPITERV6 &&
+        swapType != SWAP_TYPE_ORCA_WHIRLPOOL
+      )
         revert InvalidSwapTypeForChain(targetChain, swapType);
 
       //add the cost of ATA rent (since any swap can fail, we might have to spawn a usdc ATA)
@@ -261,17 +261,37 @@ contract InitiateTest is SLTSwapBase, SwapLayerIntegrationBase {
       else {
         vars.outputSwapCount = uint8(nextRn(rngSeed) % 3);
         if (vars.outputToken != IoToken.Usdc) {
-          vars.outputSwapType = SWAP_TYPE_JUPITERV6;
           uint32 deadline = uint32(nextRn(rngSeed));
           uint128 minOutputAmount = uint128(nextRn(rngSeed));
-          bool withDex = xPercentOfTheTime(50, rngSeed);
-          vars.outputParams = abi.encodePacked(
-            deadline,
-            minOutputAmount,
-            vars.outputSwapType,
-            withDex,
-            withDex ? abi.encodePacked(bytes32(nextRn(rngSeed))) : new bytes(0)
-          );
+          if (xPercentOfTheTime(50, rngSeed)) {
+            vars.outputSwapType = SWAP_TYPE_JUPITERV6;
+            bool withDex = xPercentOfTheTime(50, rngSeed);
+            vars.outputParams = abi.encodePacked(
+              deadline,
+              minOutputAmount,
+              vars.outputSwapType,
+              withDex,
+              withDex ? abi.encodePacked(bytes32(nextRn(rngSeed))) : new bytes(0)
+            );
+          }
+          else {
+            vars.outputSwapType = SWAP_TYPE_ORCA_WHIRLPOOL;
+            uint8 poolsLength = uint8(1 + nextRn(rngSeed) % 2);
+            bytes memory pools;
+            for (uint i = 0; i < poolsLength; ++i)
+              pools = abi.encodePacked(
+                pools,
+                bytes32(nextRn(rngSeed)),
+                xPercentOfTheTime(50, rngSeed)
+              );
+            vars.outputParams = abi.encodePacked(
+              deadline,
+              minOutputAmount,
+              vars.outputSwapType,
+              poolsLength,
+              pools
+            );
+          }
           if (vars.outputToken == IoToken.Other) {
             vars.outputParams = abi.encodePacked(
               address(mockToken).toUniversalAddress(),
@@ -493,11 +513,12 @@ contract InitiateTest is SLTSwapBase, SwapLayerIntegrationBase {
       return;
     }
 
+    bool isSolanaSwapType =
+      vars.outputSwapType == SWAP_TYPE_JUPITERV6 ||
+      vars.outputSwapType == SWAP_TYPE_ORCA_WHIRLPOOL;
     if (vars.redeemMode == RedeemMode.Relay && vars.outputSwapType > 0 && (
-        (vars.targetChain == SOLANA_CHAIN_ID &&
-          vars.outputSwapType != SWAP_TYPE_JUPITERV6) ||
-        (vars.targetChain != SOLANA_CHAIN_ID &&
-          vars.outputSwapType == SWAP_TYPE_JUPITERV6)
+        (vars.targetChain == SOLANA_CHAIN_ID && !isSolanaSwapType) ||
+        (vars.targetChain != SOLANA_CHAIN_ID &&  isSolanaSwapType)
     )) {
       assertFalse(success, "outputSwapType and chain mismatch");
       assertEq(maybeErrorSelector, InvalidSwapTypeForChain.selector);
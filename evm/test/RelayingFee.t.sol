@@ -14,6 +14,7 @@ import {
   TRADERJOE_GAS_OVERHEAD,
   TRADERJOE_GAS_PER_SWAP
 } from "swap-layer/assets/SwapLayerRelayingFees.sol";
+import { SWAP_TYPE_ORCA_WHIRLPOOL } from "swap-layer/assets/Params.sol";
 
 import "swap-layer/SwapLayerIntegrationBase.sol";
 
@@ -113,7 +114,7 @@ contract RelayingFeeTest is SLTBase, SwapLayerIntegrationBase {
    ) {
     targetChain = xPercentOfTheTime(75, rngSeed) ? FOREIGN_CHAIN_ID : SOLANA_CHAIN_ID;
     swapType = targetChain == SOLANA_CHAIN_ID
-      ? SWAP_TYPE_JUPITERV6
+      ? xPercentOfTheTime(50, rngSeed) ? SWAP_TYPE_JUPITERV6 : SWAP_TYPE_ORCA_WHIRLPOOL
       : xPercentOfTheTime(50, rngSeed) ? SWAP_TYPE_UNISWAPV3 : SWAP_TYPE_TRADERJOE;
     swapCount = uint8(nextRn(rngSeed) % 3);
   }
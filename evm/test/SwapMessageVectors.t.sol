@@ -0,0 +1,306 @@
+// SPDX-License-Identifier: Apache 2
+
+pragma solidity ^0.8.24;
+
+import "forge-std/Test.sol";
+import { stdJson } from "forge-std/StdJson.sol";
+
+import "wormhole-sdk/libraries/BytesParsing.sol";
+import { fromUniversalAddress } from "wormhole-sdk/Utils.sol";
+
+import { GasDropoff } from "swap-layer/assets/GasDropoff.sol";
+import "swap-layer/assets/Params.sol";
+import {
+  VERSION,
+  InvalidVersion,
+  SwapMessageStructure,
+  encodeSwapMessage,
+  encodeSwapMessageRelayParams,
+  encodeSwapMessagePayloadParams,
+  parseSwapMessageStructure
+} from "swap-layer/assets/Message.sol";
+
+//Golden vectors shared with the Rust swap-layer-messages crate. Each vector is a structured swap
+//  message (in the crate's serde json representation) and its expected encoding.
+contract SwapMessageVectorsTest is Test {
+  using stdJson for string;
+  using BytesParsing for bytes;
+  using { fromUniversalAddress } for bytes32;
+
+  string private constant VECTORS_PATH = "../universal/vectors/swap_messages.json";
+
+  function parseSwapMessageStructureExt(
+    bytes calldata message
+  ) external pure returns (SwapMessageStructure memory) {
+    return parseSwapMessageStructure(message);
+  }
+
+  function testSwapMessageVectors() public {
+    string memory json = vm.readFile(VECTORS_PATH);
+
+    uint count;
+    for (; vm.keyExistsJson(json, _vectorKey(count)); ++count) {
+      string memory key = _vectorKey(count);
+      string memory description = json.readString(string.concat(key, ".description"));
+      string memory msgKey = string.concat(key, ".message");
+      bytes memory encoded = json.readBytes(string.concat(key, ".encoded"));
+
+      if (_eq(json.readString(string.concat(msgKey, ".version")), "V1")) {
+        _checkEncode(json, msgKey, encoded, description);
+        _checkParse(json, msgKey, encoded, description);
+      }
+      else {
+        //only the first version of the swap message is supported on EVM
+        vm.expectRevert(
+          abi.encodeWithSelector(InvalidVersion.selector, uint8(encoded[0]), VERSION)
+        );
+        this.parseSwapMessageStructureExt(encoded);
+      }
+    }
+
+    assertGt(count, 0, "no vectors");
+  }
+
+  function _checkEncode(
+    string memory json,
+    string memory key,
+    bytes memory encoded,
+    string memory description
+  ) private view {
+    (RedeemMode redeemMode, bytes memory redeemParams) =
+      _encodeRedeemParams(json, string.concat(key, ".redeemMode"));
+
+    bytes memory swapMessage = encodeSwapMessage(
+      json.readBytes32(string.concat(key, ".recipient")),
+      redeemMode,
+      redeemParams,
+      _encodeOutputToken(json, string.concat(key, ".outputToken"))
+    );
+    assertEq(swapMessage, encoded, string.concat("encode: ", description));
+  }
+
+  function _checkParse(
+    string memory json,
+    string memory key,
+    bytes memory encoded,
+    string memory description
+  ) private view {
+    bytes32 recipient = json.readBytes32(string.concat(key, ".recipient"));
+    //messages to other chains (i.e. Solana) are never redeemed on EVM
+    if (bytes12(recipient) != 0)
+      return;
+
+    SwapMessageStructure memory sms = parseSwapMessageStructure(encoded);
+    assertEq(sms.recipient, recipient.fromUniversalAddress(), description);
+
+    string memory redeemKey = string.concat(key, ".redeemMode");
+    (RedeemMode redeemMode, ) = _encodeRedeemParams(json, redeemKey);
+    assertEq(uint8(sms.redeemMode), uint8(redeemMode), description);
+    if (redeemMode == RedeemMode.Payload) {
+      assertEq(sms.sender, json.readBytes32(string.concat(redeemKey, ".sender")), description);
+      assertEq(sms.payload, json.readBytes(string.concat(redeemKey, ".buf")), description);
+    }
+    else if (redeemMode == RedeemMode.Relay) {
+      (GasDropoff gasDropoff, uint relayingFee, ) = parseRelayParams(encoded, sms.redeemOffset);
+      assertEq(
+        GasDropoff.unwrap(gasDropoff),
+        json.readUint(string.concat(redeemKey, ".gasDropoff")),
+        description
+      );
+      assertEq(relayingFee, json.readUint(string.concat(redeemKey, ".relayingFee")), description);
+    }
+
+    string memory swapKey = string.concat(key, ".outputToken");
+    (IoToken outputToken, uint offset) = parseIoToken(encoded, sms.swapOffset);
+    assertEq(
+      uint8(outputToken),
+      uint8(_ioToken(json.readString(string.concat(swapKey, ".type")))),
+      description
+    );
+
+    if (outputToken == IoToken.Other) {
+      bytes32 tokenAddress;
+      (tokenAddress, offset) = encoded.asBytes32Unchecked(offset);
+      assertEq(tokenAddress, json.readBytes32(string.concat(swapKey, ".address")), description);
+      swapKey = string.concat(swapKey, ".swap");
+    }
+
+    if (outputToken != IoToken.Usdc) {
+      uint swapType = _swapType(json.readString(string.concat(swapKey, ".swapType.type")));
+      if (swapType == SWAP_TYPE_UNISWAPV3 || swapType == SWAP_TYPE_TRADERJOE) {
+        (uint deadline, uint limitAmount, uint evmSwapType, , ) =
+          parseEvmSwapParams(address(0), address(0), encoded, offset);
+        assertEq(deadline, json.readUint(string.concat(swapKey, ".deadline")), description);
+        assertEq(
+          limitAmount,
+          vm.parseUint(json.readString(string.concat(swapKey, ".limitAmount"))),
+          description
+        );
+        assertEq(evmSwapType, swapType, description);
+      }
+
+      uint parsedSwapType;
+      (parsedSwapType, , offset) = parseSwapTypeAndCountAndSkipParams(encoded, offset);
+      assertEq(parsedSwapType, swapType, description);
+    }
+
+    assertEq(offset, encoded.length, string.concat("parse: ", description));
+  }
+
+  function _encodeRedeemParams(
+    string memory json,
+    string memory key
+  ) private pure returns (RedeemMode, bytes memory) {
+    string memory mode = json.readString(string.concat(key, ".mode"));
+    if (_eq(mode, "Direct"))
+      return (RedeemMode.Direct, new bytes(0));
+
+    if (_eq(mode, "Relay"))
+      return (
+        RedeemMode.Relay,
+        encodeSwapMessageRelayParams(
+          GasDropoff.wrap(uint32(json.readUint(string.concat(key, ".gasDropoff")))),
+          json.readUint(string.concat(key, ".relayingFee"))
+        )
+      );
+
+    if (_eq(mode, "Payload")) {
+      bytes memory payload = json.readBytes(string.concat(key, ".buf"));
+      return (
+        RedeemMode.Payload,
+        encodeSwapMessagePayloadParams(
+          json.readBytes32(string.concat(key, ".sender")).fromUniversalAddress(),
+          abi.encodePacked(uint16(payload.length), payload)
+        )
+      );
+    }
+
+    revert(string.concat("unknown redeem mode: ", mode));
+  }
+
+  function _encodeOutputToken(
+    string memory json,
+    string memory key
+  ) private view returns (bytes memory) {
+    IoToken outputToken = _ioToken(json.readString(string.concat(key, ".type")));
+    if (outputToken == IoToken.Usdc)
+      return abi.encodePacked(outputToken);
+
+    if (outputToken == IoToken.Gas)
+      return abi.encodePacked(outputToken, _encodeOutputSwap(json, key));
+
+    return abi.encodePacked(
+      outputToken,
+      json.readBytes32(string.concat(key, ".address")),
+      _encodeOutputSwap(json, string.concat(key, ".swap"))
+    );
+  }
+
+  function _encodeOutputSwap(
+    string memory json,
+    string memory key
+  ) private view returns (bytes memory) {
+    string memory swapKey = string.concat(key, ".swapType");
+    uint8 swapType = _swapType(json.readString(string.concat(swapKey, ".type")));
+    bytes memory swap = abi.encodePacked(
+      uint32(json.readUint(string.concat(key, ".deadline"))),
+      uint128(vm.parseUint(json.readString(string.concat(key, ".limitAmount")))),
+      swapType
+    );
+
+    if (swapType == SWAP_TYPE_UNISWAPV3 || swapType == SWAP_TYPE_TRADERJOE) {
+      bool isUniswap = swapType == SWAP_TYPE_UNISWAPV3;
+      swap = abi.encodePacked(
+        swap,
+        isUniswap
+          ? abi.encodePacked(uint24(json.readUint(string.concat(swapKey, ".firstLegFee"))))
+          : _encodeTraderJoePoolId(json, string.concat(swapKey, ".firstPoolId"))
+      );
+
+      bytes memory path;
+      uint pathLength;
+      for (; vm.keyExistsJson(json, _hopKey(swapKey, pathLength)); ++pathLength) {
+        string memory hopKey = _hopKey(swapKey, pathLength);
+        path = abi.encodePacked(
+          path,
+          json.readAddress(string.concat(hopKey, ".evmAddress")),
+          isUniswap
+            ? abi.encodePacked(uint24(json.readUint(string.concat(hopKey, ".fee"))))
+            : _encodeTraderJoePoolId(json, string.concat(hopKey, ".poolId"))
+        );
+      }
+
+      return abi.encodePacked(swap, uint8(pathLength), path);
+    }
+
+    if (swapType == SWAP_TYPE_ORCA_WHIRLPOOL) {
+      bytes memory pools;
+      uint poolsLength;
+      for (; vm.keyExistsJson(json, _poolKey(swapKey, poolsLength)); ++poolsLength) {
+        string memory poolKey = _poolKey(swapKey, poolsLength);
+        pools = abi.encodePacked(
+          pools,
+          json.readBytes32(string.concat(poolKey, ".whirlpool")),
+          json.readBool(string.concat(poolKey, ".aToB"))
+        );
+      }
+
+      return abi.encodePacked(swap, uint8(poolsLength), pools);
+    }
+
+    string memory dexKey = string.concat(swapKey, ".dexProgramId");
+    return vm.keyExistsJson(json, dexKey)
+      ? abi.encodePacked(swap, true, json.readBytes32(dexKey))
+      : abi.encodePacked(swap, false);
+  }
+
+  function _encodeTraderJoePoolId(
+    string memory json,
+    string memory key
+  ) private pure returns (bytes memory) {
+    return abi.encodePacked(
+      uint8(json.readUint(string.concat(key, ".version"))),
+      uint16(json.readUint(string.concat(key, ".binSize")))
+    );
+  }
+
+  function _ioToken(string memory tokenType) private pure returns (IoToken) {
+    if (_eq(tokenType, "Usdc"))
+      return IoToken.Usdc;
+    if (_eq(tokenType, "Gas"))
+      return IoToken.Gas;
+    if (_eq(tokenType, "Other"))
+      return IoToken.Other;
+
+    revert(string.concat("unknown output token: ", tokenType));
+  }
+
+  function _swapType(string memory swapType) private pure returns (uint8) {
+    if (_eq(swapType, "UniswapV3"))
+      return SWAP_TYPE_UNISWAPV3;
+    if (_eq(swapType, "TraderJoe"))
+      return SWAP_TYPE_TRADERJOE;
+    if (_eq(swapType, "JupiterV6"))
+      return SWAP_TYPE_JUPITERV6;
+    if (_eq(swapType, "OrcaWhirlpool"))
+      return SWAP_TYPE_ORCA_WHIRLPOOL;
+
+    revert(string.concat("unknown swap type: ", swapType));
+  }
+
+  function _vectorKey(uint index) private pure returns (string memory) {
+    return string.concat(".vectors[", vm.toString(index), "]");
+  }
+
+  function _hopKey(string memory swapKey, uint index) private pure returns (string memory) {
+    return string.concat(swapKey, ".path[", vm.toString(index), "]");
+  }
+
+  function _poolKey(string memory swapKey, uint index) private pure returns (string memory) {
+    return string.concat(swapKey, ".pools[", vm.toString(index), "]");
+  }
+
+  function _eq(string memory a, string memory b) private pure returns (bool) {
+    return keccak256(bytes(a)) == keccak256(bytes(b));
+  }
+}
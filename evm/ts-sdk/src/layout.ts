@@ -236,13 +236,21 @@ const jupiterV6SwapParametersLayout = [
   },
 ] as const satisfies Layout;
 
+const orcaWhirlpoolSwapParametersLayout = [
+  { name: "pools", binary: "array", lengthSize: 1, layout: [
+    { name: "whirlpool", ...layoutItems.universalAddressItem },
+    { name: "aToB", ...boolItem },
+  ]}
+] as const satisfies Layout;
+
 const swapTypesEvm = [
   [1, "UniswapV3"],
   [2, "TraderJoe"],
 ] as const;
 
 const swapTypesSolana = [
-  [16, "GenericSolana"]
+  [16, "GenericSolana"],
+  [18, "OrcaWhirlpool"],
 ] as const;
 
 const [swapTypes, swapItemLayouts] = [[
@@ -252,6 +260,7 @@ const [swapTypes, swapItemLayouts] = [[
     sharedUniswapTraderJoeLayout,
     sharedUniswapTraderJoeLayout,
     jupiterV6SwapParametersLayout,
+    orcaWhirlpoolSwapParametersLayout,
   ]
 ] as const;
 
@@ -2,10 +2,18 @@ use std::ops::Deref;
 
 use crate::{
     error::SwapLayerError,
-    state::{Custodian, Peer},
+    state::{Custodian, Peer, StagedSplit, StagedSplitOutput},
     utils::{
         self,
-        jupiter_v6::{self, cpi::SharedAccountsRouteArgs, JUPITER_V6_PROGRAM_ID},
+        jupiter_v6::{
+            self,
+            cpi::{SharedAccountsExactOutRouteArgs, SharedAccountsRouteArgs},
+            JUPITER_V6_PROGRAM_ID,
+        },
+        orca_whirlpool::{
+            self,
+            cpi::{SwapArgs, TwoHopSwapArgs},
+        },
         AnchorInstructionData,
     },
 };
@@ -20,8 +28,11 @@ use common::{
     USDC_MINT,
 };
 use swap_layer_messages::{
-    messages::SwapMessageV1,
-    types::{OutputSwap, OutputToken, RedeemMode, SwapType},
+    messages::{SwapMessage, SwapMessageV2},
+    raw::{OutputSwapRef, OutputTokenRef, RedeemModeRef, SwapMessageRef, SwapTypeRef},
+    types::{
+        JupiterV6SwapParameters, OrcaWhirlpoolPool, OutputSwap, OutputToken, SwapMode, SwapType,
+    },
 };
 use token_router::state::PreparedFill;
 
@@ -170,7 +181,8 @@ pub struct ConsumeSwapLayerFill<'info> {
     #[account(
         mut,
         constraint = {
-            let swap_msg = SwapMessageV1::read_slice(&fill.redeemer_message)
+            // Validate the swap message once without copying it out of the prepared fill.
+            SwapMessageRef::parse(&fill.redeemer_message)
                 .map_err(|_| SwapLayerError::InvalidSwapMessage)?;
 
             require_eq!(
@@ -202,22 +214,149 @@ pub struct ConsumeSwapLayerFill<'info> {
     #[account(mut)]
     beneficiary: UncheckedAccount<'info>,
 
+    /// USDC token account receiving the integrator fee. This account is only required if the swap
+    /// message specifies an integrator fee and the source peer allows one. Its owner must be the
+    /// encoded integrator fee recipient.
+    #[account(
+        mut,
+        token::mint = USDC_MINT,
+    )]
+    integrator_fee_token: Option<Box<Account<'info, token::TokenAccount>>>,
+
     token_router_program: Program<'info, token_router::program::TokenRouter>,
 }
 
 impl<'info> ConsumeSwapLayerFill<'info> {
-    pub fn read_message_unchecked(&self) -> SwapMessageV1 {
-        SwapMessageV1::read_slice(&self.fill.redeemer_message).unwrap()
+    /// Read the swap message from the prepared fill. Any supported version of the swap message is
+    /// normalized into the latest version.
+    pub fn read_message_unchecked(&self) -> SwapMessageV2 {
+        SwapMessage::read_slice(&self.fill.redeemer_message)
+            .unwrap()
+            .into()
+    }
+
+    /// Borrow the swap message from the prepared fill without decoding it into owned types. Prefer
+    /// this when only a few fields need to be inspected.
+    pub fn read_message_ref_unchecked(&self) -> SwapMessageRef<'_> {
+        SwapMessageRef::parse(&self.fill.redeemer_message).unwrap()
+    }
+
+    /// Owner of the token account receiving the USDC if the swap encoded in the message is not
+    /// performed. This is only specified if the message encodes a swap.
+    pub fn fallback_recipient(&self) -> Option<Pubkey> {
+        let swap_msg = self.read_message_ref_unchecked();
+
+        match swap_msg.output_token() {
+            OutputTokenRef::Usdc => None,
+            OutputTokenRef::Gas(_) | OutputTokenRef::Other { .. } => swap_msg
+                .fallback_recipient()
+                .map(|fallback_recipient| Pubkey::from(*fallback_recipient)),
+        }
+    }
+
+    /// Owner of the token account receiving the USDC when the fill is redeemed without a swap. If
+    /// a relayer redeems USDC instead of the encoded swap (meaning that the swap failed or was not
+    /// performed in time), the USDC goes to the fallback recipient if one is specified. The
+    /// recipient redeeming USDC itself always receives it.
+    pub fn usdc_recipient(&self, redeemer: &Pubkey) -> Pubkey {
+        let recipient = Pubkey::from(*self.read_message_ref_unchecked().recipient());
+
+        if *redeemer == recipient {
+            recipient
+        } else {
+            self.fallback_recipient().unwrap_or(recipient)
+        }
     }
 
     pub fn prepared_fill_key(&self) -> Pubkey {
         self.fill.key()
     }
 
+    /// Integrator fee owed on the USDC amount, which is capped at the maximum integrator fee
+    /// configured for the source peer.
+    pub fn integrator_fee(&self, amount: u64) -> u64 {
+        self.read_message_ref_unchecked()
+            .integrator_fee()
+            .map(|integrator_fee| {
+                integrator_fee
+                    .amount
+                    .compute(amount)
+                    .min(self.source_peer.max_integrator_fee)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Pay the integrator fee out of the USDC in the source token account. The fee is paid before
+    /// the relaying fee is deducted and before any swap. Returns the amount of USDC remaining.
+    pub fn pay_integrator_fee(
+        &self,
+        amount: u64,
+        src_token: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<u64> {
+        let integrator_fee = self.integrator_fee(amount);
+        if integrator_fee == 0 {
+            return Ok(amount);
+        }
+
+        let integrator_fee_token = self
+            .integrator_fee_token
+            .as_ref()
+            .ok_or_else(|| SwapLayerError::IntegratorFeeTokenRequired)?;
+
+        // This unwrap is safe because a non-zero fee means the swap message encodes one.
+        let recipient = self
+            .read_message_ref_unchecked()
+            .integrator_fee()
+            .unwrap()
+            .recipient;
+        require_keys_eq!(
+            integrator_fee_token.owner,
+            Pubkey::from(*recipient),
+            SwapLayerError::InvalidIntegratorFeeToken
+        );
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                token::Transfer {
+                    from: src_token.to_account_info(),
+                    to: integrator_fee_token.to_account_info(),
+                    authority: authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            integrator_fee,
+        )?;
+
+        // The fee never exceeds the amount.
+        Ok(amount.saturating_sub(integrator_fee))
+    }
+
+    /// Consume the prepared fill into the destination token account. A fill whose swap message
+    /// encodes split outputs must be staged with the split instructions instead, which deliver each
+    /// recipient's share.
     pub fn consume_prepared_fill(
         &self,
         dst_token: &AccountInfo<'info>,
         token_program: &AccountInfo<'info>,
+    ) -> Result<u64> {
+        require!(
+            self.read_message_ref_unchecked().split_outputs().is_empty(),
+            SwapLayerError::SplitOutputsNotSupported
+        );
+
+        self.consume_prepared_fill_unchecked(dst_token, token_program)
+    }
+
+    /// Consume the prepared fill into the destination token account without checking whether the
+    /// swap message encodes split outputs.
+    pub fn consume_prepared_fill_unchecked(
+        &self,
+        dst_token: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
     ) -> Result<u64> {
         token_router::cpi::consume_prepared_fill(CpiContext::new_with_signer(
             self.token_router_program.to_account_info(),
@@ -250,56 +389,75 @@ impl<'info> ConsumeSwapLayerFill<'info> {
         &self,
         dst_mint: &InterfaceAccount<'info, token_interface::Mint>,
     ) -> Result<bool> {
-        let swap_msg = self.read_message_unchecked();
-
-        let (expected_dst_mint, swap) = match swap_msg.output_token {
-            OutputToken::Usdc => {
-                require!(
-                    matches!(swap_msg.redeem_mode, RedeemMode::Direct),
-                    SwapLayerError::InvalidRedeemMode,
-                );
-
-                (Default::default(), None)
-            }
-            OutputToken::Gas(swap) => (token::spl_token::native_mint::id(), swap.into()),
-            OutputToken::Other { address, swap } => (address.into(), swap.into()),
-        };
-
-        if let Some(swap) = swap {
-            // Verify the address matches the destination mint.
-            require_keys_eq!(
-                dst_mint.key(),
-                expected_dst_mint,
-                SwapLayerError::InvalidDestinationMint
-            );
-
-            let OutputSwap {
-                limit_amount,
-                deadline,
-                swap_type,
-            } = swap;
+        let swap_msg = self.read_message_ref_unchecked();
 
+        if !verify_output_swap(swap_msg.output_token(), &dst_mint.key())? {
             require!(
-                matches!(swap_type, SwapType::JupiterV6(_)),
-                SwapLayerError::InvalidSwapType,
+                matches!(swap_msg.redeem_mode(), RedeemModeRef::Direct),
+                SwapLayerError::InvalidRedeemMode,
             );
-
-            // Check the deadline for the swap. There may not be a deadline check with the
-            // dex that this instruction composes with, so we will check it here.
-            require!(
-                deadline == 0 || Clock::get().unwrap().unix_timestamp <= i64::from(deadline),
-                SwapLayerError::SwapPastDeadline,
-            );
-
-            // Just in case the encoded limit amount exceeds u64, we have nothing to do if
-            // this message were misconfigured.
-            u64::try_from(limit_amount).map_err(|_| SwapLayerError::InvalidLimitAmount)?;
         }
 
         Ok(true)
     }
 }
 
+/// Ensure that the swap encoded in the output token can be performed by this program into the
+/// destination mint. Returns false if the output token is USDC, which does not encode a swap.
+pub fn verify_output_swap(output_token: OutputTokenRef, dst_mint: &Pubkey) -> Result<bool> {
+    let (expected_dst_mint, swap) = match output_token {
+        OutputTokenRef::Usdc => return Ok(false),
+        OutputTokenRef::Gas(swap) => (token::spl_token::native_mint::id(), swap),
+        OutputTokenRef::Other { address, swap } => (Pubkey::from(*address), swap),
+    };
+
+    // Verify the address matches the destination mint.
+    require_keys_eq!(
+        *dst_mint,
+        expected_dst_mint,
+        SwapLayerError::InvalidDestinationMint
+    );
+
+    let OutputSwapRef {
+        limit_amount,
+        deadline,
+        swap_type,
+        mode: _,
+    } = swap;
+
+    require!(
+        matches!(
+            swap_type,
+            SwapTypeRef::JupiterV6 { .. } | SwapTypeRef::OrcaWhirlpool { .. }
+        ),
+        SwapLayerError::InvalidSwapType,
+    );
+
+    // Check the deadline for the swap. There may not be a deadline check with the dex that this
+    // instruction composes with, so we will check it here.
+    require!(
+        deadline == 0 || Clock::get().unwrap().unix_timestamp <= i64::from(deadline),
+        SwapLayerError::SwapPastDeadline,
+    );
+
+    // Just in case the encoded limit amount exceeds u64, we have nothing to do if this message
+    // were misconfigured.
+    let limit_amount =
+        u64::try_from(limit_amount).map_err(|_| SwapLayerError::InvalidLimitAmount)?;
+
+    // Only Jupiter V6 can swap exact out, and the swap must produce some amount of the
+    // destination mint.
+    if swap.is_exact_out() {
+        require!(
+            matches!(swap_type, SwapTypeRef::JupiterV6 { .. }),
+            SwapLayerError::InvalidSwapType
+        );
+        require!(limit_amount != 0, SwapLayerError::InvalidLimitAmount);
+    }
+
+    Ok(true)
+}
+
 impl<'info> Deref for ConsumeSwapLayerFill<'info> {
     type Target = Account<'info, PreparedFill>;
 
@@ -358,6 +516,12 @@ pub struct CompleteSwap<'info> {
     )]
     pub fee_recipient_token: UncheckedAccount<'info>,
 
+    /// CHECK: In case the exact out swap does not use all USDC, we send residual to this token
+    /// account. This account is only required for exact out swaps and must be a USDC token account
+    /// owned by the encoded recipient.
+    #[account(mut)]
+    pub recipient_usdc_token: Option<UncheckedAccount<'info>>,
+
     /// This account must be verified as the source mint for the swap.
     pub usdc: Usdc<'info>,
 
@@ -372,11 +536,12 @@ pub struct CompleteSwap<'info> {
 
 pub struct HandleCompleteSwap<'ctx, 'info> {
     pub payer: &'ctx Signer<'info>,
-    pub consume_swap_layer_fill: &'ctx ConsumeSwapLayerFill<'info>,
+    pub prepared_fill_key: Pubkey,
     pub swap_authority: &'ctx AccountInfo<'info>,
     pub src_swap_token: &'ctx Account<'info, token::TokenAccount>,
     pub dst_swap_token: &'ctx InterfaceAccount<'info, token_interface::TokenAccount>,
     pub fee_recipient_token: &'ctx UncheckedAccount<'info>,
+    pub recipient_usdc_token: Option<&'ctx UncheckedAccount<'info>>,
     pub dst_mint: &'ctx InterfaceAccount<'info, token_interface::Mint>,
     pub token_program: &'ctx Program<'info, token::Token>,
     pub dst_token_program: &'ctx Interface<'info, token_interface::TokenInterface>,
@@ -388,6 +553,23 @@ impl<'info> CompleteSwap<'info> {
         self.consume_swap_layer_fill
             .consume_prepared_fill(self.src_swap_token.as_ref().as_ref(), &self.token_program)
     }
+
+    /// Pay the integrator fee out of the USDC in the source swap token account, which the swap
+    /// authority signs for. Returns the amount of USDC left to swap.
+    pub fn pay_integrator_fee(&self, amount: u64, authority_bump: u8) -> Result<u64> {
+        let prepared_fill = self.consume_swap_layer_fill.key();
+        self.consume_swap_layer_fill.pay_integrator_fee(
+            amount,
+            self.src_swap_token.as_ref().as_ref(),
+            &self.authority,
+            &self.token_program,
+            &[&[
+                crate::SWAP_AUTHORITY_SEED_PREFIX,
+                prepared_fill.as_ref(),
+                &[authority_bump],
+            ]],
+        )
+    }
 }
 
 impl<'info> Deref for CompleteSwap<'info> {
@@ -398,6 +580,123 @@ impl<'info> Deref for CompleteSwap<'info> {
     }
 }
 
+/// Staged split fill, whose outputs are delivered one at a time. The staged split and its custody
+/// token account are closed once every output is delivered.
+#[derive(Accounts)]
+pub struct ConsumeStagedSplit<'info> {
+    pub custodian: CheckedCustodian<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            StagedSplit::SEED_PREFIX,
+            staged_split.seeds.prepared_fill.as_ref(),
+        ],
+        bump = staged_split.seeds.bump,
+    )]
+    pub staged_split: Box<Account<'info, StagedSplit>>,
+
+    #[account(
+        mut,
+        seeds = [
+            crate::STAGED_CUSTODY_TOKEN_SEED_PREFIX,
+            staged_split.key().as_ref(),
+        ],
+        bump = staged_split.custody_token_bump,
+    )]
+    pub staged_custody_token: Box<Account<'info, token::TokenAccount>>,
+
+    /// CHECK: Recipient of lamports from closing the staged split and its custody token account.
+    #[account(
+        mut,
+        address = staged_split.staged_by,
+    )]
+    staged_by: UncheckedAccount<'info>,
+
+    /// CHECK: Any USDC left in the staged custody token account after every output is delivered
+    /// is dust, which is sent to this token account.
+    #[account(
+        mut,
+        address = custodian.fee_recipient_token,
+    )]
+    pub fee_recipient_token: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+impl<'info> ConsumeStagedSplit<'info> {
+    /// Mark the output at this index as delivered. Returns the output to deliver.
+    pub fn complete_output(&mut self, index: u8) -> Result<StagedSplitOutput> {
+        let output = self
+            .staged_split
+            .outputs
+            .get_mut(usize::from(index))
+            .ok_or_else(|| SwapLayerError::InvalidSplitOutputIndex)?;
+        require!(
+            !output.is_completed,
+            SwapLayerError::SplitOutputAlreadyCompleted
+        );
+
+        output.is_completed = true;
+
+        Ok(output.clone())
+    }
+
+    /// Transfer USDC out of the staged custody token account.
+    pub fn transfer(&self, to: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        let staged_split = &self.staged_split;
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                token::Transfer {
+                    from: self.staged_custody_token.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: staged_split.to_account_info(),
+                },
+                &[&[
+                    StagedSplit::SEED_PREFIX,
+                    staged_split.seeds.prepared_fill.as_ref(),
+                    &[staged_split.seeds.bump],
+                ]],
+            ),
+            amount,
+        )
+    }
+
+    /// Close the staged custody token account and the staged split once every output is delivered.
+    pub fn close_if_completed(&mut self) -> Result<()> {
+        if !self.staged_split.is_completed() {
+            return Ok(());
+        }
+
+        // Someone may have sent USDC to the custody token account, so whatever is left is dust.
+        self.staged_custody_token.reload()?;
+        let dust = self.staged_custody_token.amount;
+        if dust > 0 {
+            msg!("USDC dust: {}", dust);
+            self.transfer(&self.fee_recipient_token, dust)?;
+        }
+
+        let staged_split = &self.staged_split;
+        token::close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            token::CloseAccount {
+                account: self.staged_custody_token.to_account_info(),
+                destination: self.staged_by.to_account_info(),
+                authority: staged_split.to_account_info(),
+            },
+            &[&[
+                StagedSplit::SEED_PREFIX,
+                staged_split.seeds.prepared_fill.as_ref(),
+                &[staged_split.seeds.bump],
+            ]],
+        ))?;
+
+        self.staged_split.close(self.staged_by.to_account_info())
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn complete_swap_jup_v6<'info>(
     complete_swap: &CompleteSwap<'info>,
@@ -405,7 +704,7 @@ pub(crate) fn complete_swap_jup_v6<'info>(
     remaining_accounts: &'info [AccountInfo<'info>],
     ix_data: Vec<u8>,
     in_amount: u64,
-    swap_message: SwapMessageV1,
+    swap_message: SwapMessageV2,
     recipient: &AccountInfo<'info>,
     recipient_token: &AccountInfo<'info>,
     gas_dropoff: Option<u64>,
@@ -417,6 +716,7 @@ pub(crate) fn complete_swap_jup_v6<'info>(
         src_swap_token,
         dst_swap_token,
         fee_recipient_token,
+        recipient_usdc_token,
         dst_mint,
         token_program,
         dst_token_program,
@@ -427,11 +727,12 @@ pub(crate) fn complete_swap_jup_v6<'info>(
     handle_complete_swap_jup_v6(
         HandleCompleteSwap {
             payer,
-            consume_swap_layer_fill,
+            prepared_fill_key: consume_swap_layer_fill.prepared_fill_key(),
             swap_authority: authority,
             src_swap_token,
             dst_swap_token,
             fee_recipient_token,
+            recipient_usdc_token: recipient_usdc_token.as_ref(),
             dst_mint,
             token_program,
             dst_token_program,
@@ -465,27 +766,31 @@ pub(crate) fn handle_complete_swap_jup_v6<'ctx, 'info>(
     remaining_accounts: &'info [AccountInfo<'info>],
     ix_data: Vec<u8>,
     in_amount: u64,
-    swap_message: SwapMessageV1,
+    swap_message: SwapMessageV2,
     recipient: Option<RecipientAccounts<'ctx, 'info>>,
     gas_dropoff: Option<u64>,
 ) -> Result<()> {
     let HandleCompleteSwap {
         payer,
-        consume_swap_layer_fill,
+        prepared_fill_key,
         swap_authority,
         src_swap_token,
         dst_swap_token,
         fee_recipient_token,
+        recipient_usdc_token,
         dst_mint,
         token_program,
         dst_token_program,
         system_program,
     } = accounts;
 
-    let SwapMessageV1 {
+    let SwapMessageV2 {
         recipient: expected_recipient,
         output_token,
         redeem_mode: _,
+        fallback_recipient: _,
+        integrator_fee: _,
+        split_outputs: _,
     } = swap_message;
 
     let recipient_key = recipient
@@ -499,7 +804,7 @@ pub(crate) fn handle_complete_swap_jup_v6<'ctx, 'info>(
         );
     }
 
-    let (limit_and_params, is_native) = match output_token {
+    let (swap, is_native) = match output_token {
         OutputToken::Usdc => match recipient_key {
             Some(recipient_key) => {
                 // In this case, we require that the signer of the instruction (the payer) is the
@@ -510,128 +815,223 @@ pub(crate) fn handle_complete_swap_jup_v6<'ctx, 'info>(
             }
             None => return err!(SwapLayerError::InvalidOutputToken),
         },
-        OutputToken::Gas(OutputSwap {
+        OutputToken::Gas(swap) => (swap.into(), true),
+        OutputToken::Other { address: _, swap } => (swap.into(), false),
+    };
+
+    // The limit amount is either the minimum amount out (exact in) or the exact amount out (exact
+    // out). Swaps through Orca Whirlpools are always exact in and do not use Jupiter V6.
+    let (limit_and_params, orca_whirlpool_params) = match swap {
+        Some(OutputSwap {
             deadline: _,
             limit_amount,
-            swap_type: SwapType::JupiterV6(swap_params),
-        }) => ((limit_amount.try_into().unwrap(), swap_params).into(), true),
-        OutputToken::Other {
-            address: _,
-            swap:
-                OutputSwap {
-                    deadline: _,
-                    limit_amount,
-                    swap_type: SwapType::JupiterV6(swap_params),
-                },
-        } => (
-            (limit_amount.try_into().unwrap(), swap_params).into(),
-            false,
-        ),
-        _ => return err!(SwapLayerError::InvalidOutputToken),
+            swap_type,
+            mode,
+        }) => match swap_type {
+            SwapType::JupiterV6(swap_params) => (
+                (
+                    limit_amount.try_into().unwrap(),
+                    swap_params,
+                    mode == SwapMode::ExactOut,
+                )
+                    .into(),
+                None,
+            ),
+            SwapType::OrcaWhirlpool(swap_params) => {
+                (None, (limit_amount.try_into().unwrap(), swap_params).into())
+            }
+            _ => return err!(SwapLayerError::InvalidOutputToken),
+        },
+        None => (None, None),
     };
+    let is_exact_out = matches!(limit_and_params, Some((_, _, true)));
 
-    let prepared_fill_key = consume_swap_layer_fill.prepared_fill_key();
     let swap_authority_seeds = &[
         swap_authority_seed_prefix,
         prepared_fill_key.as_ref(),
         &[swap_authority_bump_seed],
     ];
 
-    let (shared_accounts_route, mut swap_args, first_dex_program_id) =
-        JupiterV6SharedAccountsRoute::set_up(remaining_accounts, &ix_data[..])?;
-
     // Verify remaining accounts.
-    {
-        require_keys_eq!(
-            shared_accounts_route.transfer_authority.key(),
-            swap_authority.key(),
-            SwapLayerError::InvalidSwapAuthority
-        );
-        require_keys_eq!(
-            shared_accounts_route.src_custody_token.key(),
-            src_swap_token.key(),
-            SwapLayerError::InvalidSourceSwapToken
-        );
-        require_keys_eq!(
-            shared_accounts_route.dst_custody_token.key(),
-            dst_swap_token.key(),
-            SwapLayerError::InvalidDestinationSwapToken
-        );
-        require_keys_eq!(
-            shared_accounts_route.src_mint.key(),
-            common::USDC_MINT,
-            SwapLayerError::InvalidSourceMint
-        );
-        require_keys_eq!(
-            shared_accounts_route.dst_mint.key(),
-            dst_mint.key(),
-            SwapLayerError::InvalidDestinationMint
-        );
-    }
+    let verify_shared_accounts_route =
+        |shared_accounts_route: &JupiterV6SharedAccountsRoute<'info>| {
+            shared_accounts_route.verify_swap_accounts(
+                swap_authority.key(),
+                src_swap_token.key(),
+                dst_swap_token.key(),
+                common::USDC_MINT,
+                dst_mint.key(),
+            )
+        };
+
+    // Peek into the head of remaining accounts. This account will be the dex program that Jupiter
+    // V6 interacts with. If the swap params specify a specific dex program, we need to ensure that
+    // the one passed into this instruction handler is that.
+    let verify_dex_program_id = |swap_params: JupiterV6SwapParameters,
+                                 route_plan_len: usize,
+                                 first_dex_program_id: Pubkey|
+     -> Result<()> {
+        if let Some(dex_program_id) = swap_params.dex_program_id {
+            require_eq!(route_plan_len, 1, SwapLayerError::NotJupiterV6DirectRoute);
+            require_keys_eq!(
+                first_dex_program_id,
+                Pubkey::from(dex_program_id),
+                SwapLayerError::JupiterV6DexProgramMismatch
+            );
+        }
+
+        Ok(())
+    };
+
+    // Execute swap.
+    let (amount_out, usdc_dust) = match (limit_and_params, orca_whirlpool_params) {
+        // The Whirlpool program swaps the whole fill amount with the limit amount as the minimum
+        // amount out.
+        (_, Some((limit_amount, swap_params))) => {
+            let whirlpool_route = OrcaWhirlpoolRoute::set_up(
+                remaining_accounts,
+                &swap_params
+                    .pools
+                    .iter()
+                    .map(|pool| pool.a_to_b)
+                    .collect::<Vec<_>>(),
+            )?;
+            whirlpool_route.require_whirlpools(&swap_params.pools)?;
+            whirlpool_route.verify_swap_accounts(
+                swap_authority.key(),
+                src_swap_token.key(),
+                dst_swap_token.key(),
+            )?;
+
+            whirlpool_route.swap_exact_in(
+                in_amount,
+                limit_amount,
+                swap_authority_seeds,
+                remaining_accounts,
+            )?
+        }
+        // If the output swap is exact out, we will override the instruction arguments with the
+        // limit amount as the out amount and the whole fill amount as the quoted in amount with
+        // slippage == 0 bps. This way, at most the fill amount will be swapped.
+        (Some((limit_amount, swap_params, true)), None) => {
+            let (shared_accounts_route, mut swap_args, first_dex_program_id) =
+                JupiterV6SharedAccountsRoute::set_up::<SharedAccountsExactOutRouteArgs>(
+                    remaining_accounts,
+                    &ix_data[..],
+                )?;
+            verify_shared_accounts_route(&shared_accounts_route)?;
 
-    let limit_amount = match limit_and_params {
-        // If the limit amount is some value (meaning that the OutputToken is Gas or Other), we
-        // will override the instruction arguments with the limit amount and slippage == 0 bps.
-        // Otherwise we will compute the limit amount using the given swap args.
-        Some((limit_amount, swap_params)) => {
             msg!(
-                "Override in_amount: {}, quoted_out_amount: {}, slippage_bps: {}",
-                swap_args.in_amount,
-                swap_args.quoted_out_amount,
+                "Override out_amount: {}, quoted_in_amount: {}, slippage_bps: {}",
+                swap_args.out_amount,
+                swap_args.quoted_in_amount,
                 swap_args.slippage_bps
             );
-            swap_args.in_amount = in_amount;
-            swap_args.quoted_out_amount = limit_amount;
+            swap_args.out_amount = limit_amount;
+            swap_args.quoted_in_amount = in_amount;
             swap_args.slippage_bps = 0;
 
-            // Peek into the head of remaining accounts. This account will be the dex program that Jupiter
-            // V6 interacts with. If the swap params specify a specific dex program, we need to ensure that
-            // the one passed into this instruction handler is that.
-            if let Some(dex_program_id) = swap_params.dex_program_id {
-                require_eq!(
-                    swap_args.route_plan.len(),
-                    1,
-                    SwapLayerError::NotJupiterV6DirectRoute
-                );
-                require_keys_eq!(
-                    first_dex_program_id,
-                    Pubkey::from(dex_program_id),
-                    SwapLayerError::JupiterV6DexProgramMismatch
-                );
-            }
+            verify_dex_program_id(
+                swap_params,
+                swap_args.route_plan.len(),
+                first_dex_program_id,
+            )?;
 
-            limit_amount.into()
+            shared_accounts_route.swap_exact_out(
+                swap_args,
+                swap_authority_seeds,
+                remaining_accounts,
+            )?
         }
-        None => {
-            // Fetched swap args should have the same in amount as the prepared (fast) fill.
-            require_eq!(
-                swap_args.in_amount,
-                in_amount,
-                SwapLayerError::InvalidSwapInAmount
-            );
-
-            None
+        (limit_and_params, None) => {
+            let (shared_accounts_route, mut swap_args, first_dex_program_id) =
+                JupiterV6SharedAccountsRoute::set_up::<SharedAccountsRouteArgs>(
+                    remaining_accounts,
+                    &ix_data[..],
+                )?;
+            verify_shared_accounts_route(&shared_accounts_route)?;
+
+            let limit_amount = match limit_and_params {
+                // If the limit amount is some value (meaning that the OutputToken is Gas or
+                // Other), we will override the instruction arguments with the limit amount and
+                // slippage == 0 bps. Otherwise we will compute the limit amount using the given
+                // swap args.
+                Some((limit_amount, swap_params, _)) => {
+                    msg!(
+                        "Override in_amount: {}, quoted_out_amount: {}, slippage_bps: {}",
+                        swap_args.in_amount,
+                        swap_args.quoted_out_amount,
+                        swap_args.slippage_bps
+                    );
+                    swap_args.in_amount = in_amount;
+                    swap_args.quoted_out_amount = limit_amount;
+                    swap_args.slippage_bps = 0;
+
+                    verify_dex_program_id(
+                        swap_params,
+                        swap_args.route_plan.len(),
+                        first_dex_program_id,
+                    )?;
+
+                    limit_amount.into()
+                }
+                None => {
+                    // Fetched swap args should have the same in amount as the prepared (fast) fill.
+                    require_eq!(
+                        swap_args.in_amount,
+                        in_amount,
+                        SwapLayerError::InvalidSwapInAmount
+                    );
+
+                    None
+                }
+            };
+
+            shared_accounts_route.swap_exact_in(
+                swap_args,
+                swap_authority_seeds,
+                remaining_accounts,
+                limit_amount,
+            )?
         }
     };
 
-    // Execute swap.
-    let (amount_out, usdc_dust) = shared_accounts_route.swap_exact_in(
-        swap_args,
-        swap_authority_seeds,
-        remaining_accounts,
-        limit_amount,
-    )?;
-
-    // Transfer residual to the fee recipient token if there is any.
+    // Transfer residual if there is any. If the swap is exact out, the residual belongs to the
+    // recipient. Otherwise it is considered dust and is sent to the fee recipient token.
     if usdc_dust > 0 {
-        msg!("USDC dust: {}", usdc_dust);
+        let residual_token = if is_exact_out {
+            msg!("USDC residual: {}", usdc_dust);
+
+            let recipient_usdc_token =
+                recipient_usdc_token.ok_or_else(|| SwapLayerError::RecipientUsdcTokenRequired)?;
+
+            // Verify that the encoded recipient is the owner of this USDC token account.
+            let token =
+                token::TokenAccount::try_deserialize(&mut &recipient_usdc_token.data.borrow()[..])?;
+            require_keys_eq!(
+                token.mint,
+                common::USDC_MINT,
+                ErrorCode::ConstraintTokenMint
+            );
+            require_keys_eq!(
+                token.owner,
+                Pubkey::from(expected_recipient),
+                ErrorCode::ConstraintTokenOwner
+            );
+
+            recipient_usdc_token.to_account_info()
+        } else {
+            msg!("USDC dust: {}", usdc_dust);
+
+            fee_recipient_token.to_account_info()
+        };
 
         token::transfer(
             CpiContext::new_with_signer(
                 token_program.to_account_info(),
                 token::Transfer {
                     from: src_swap_token.to_account_info(),
-                    to: fee_recipient_token.to_account_info(),
+                    to: residual_token,
                     authority: swap_authority.to_account_info(),
                 },
                 &[swap_authority_seeds],
@@ -834,12 +1234,15 @@ pub struct JupiterV6SharedAccountsRoute<'info> {
 }
 
 impl<'info> JupiterV6SharedAccountsRoute<'info> {
-    pub fn set_up(
+    /// Set up either the shared accounts route or the shared accounts exact out route. Both of
+    /// these instructions share the same accounts, and the authority ID is the first argument for
+    /// both of them, so the accounts are deserialized the same way.
+    pub fn set_up<T: AnchorInstructionData>(
         mut cpi_account_infos: &'info [AccountInfo<'info>],
         ix_data: &[u8],
-    ) -> Result<(Self, SharedAccountsRouteArgs, Pubkey)> {
+    ) -> Result<(Self, T, Pubkey)> {
         // Deserialize Jupiter V6 shared accounts route args.
-        let args = AnchorInstructionData::deserialize_checked(ix_data)?;
+        let args = T::deserialize_checked(ix_data)?;
 
         // Now try account infos.
         let accounts = JupiterV6SharedAccountsRoute::try_accounts(
@@ -855,6 +1258,45 @@ impl<'info> JupiterV6SharedAccountsRoute<'info> {
         Ok((accounts, args, cpi_account_infos[0].key()))
     }
 
+    /// Verify that the swap authority swaps the source mint from the source swap token account into
+    /// the destination mint in the destination swap token account.
+    pub fn verify_swap_accounts(
+        &self,
+        swap_authority: Pubkey,
+        src_swap_token: Pubkey,
+        dst_swap_token: Pubkey,
+        src_mint: Pubkey,
+        dst_mint: Pubkey,
+    ) -> Result<()> {
+        require_keys_eq!(
+            self.transfer_authority.key(),
+            swap_authority,
+            SwapLayerError::InvalidSwapAuthority
+        );
+        require_keys_eq!(
+            self.src_custody_token.key(),
+            src_swap_token,
+            SwapLayerError::InvalidSourceSwapToken
+        );
+        require_keys_eq!(
+            self.dst_custody_token.key(),
+            dst_swap_token,
+            SwapLayerError::InvalidDestinationSwapToken
+        );
+        require_keys_eq!(
+            self.src_mint.key(),
+            src_mint,
+            SwapLayerError::InvalidSourceMint
+        );
+        require_keys_eq!(
+            self.dst_mint.key(),
+            dst_mint,
+            SwapLayerError::InvalidDestinationMint
+        );
+
+        Ok(())
+    }
+
     pub fn swap_exact_in(
         &self,
         args: SharedAccountsRouteArgs,
@@ -865,6 +1307,43 @@ impl<'info> JupiterV6SharedAccountsRoute<'info> {
         let limit_amount =
             limit_amount.unwrap_or_else(|| utils::jupiter_v6::compute_min_amount_out(&args));
 
+        self.invoke_shared_accounts_route(
+            (jupiter_v6::SHARED_ACCOUNTS_ROUTE_SELECTOR, args)
+                .try_to_vec()
+                .unwrap(),
+            signer_seeds,
+            account_infos,
+            limit_amount,
+        )
+    }
+
+    pub fn swap_exact_out(
+        &self,
+        args: SharedAccountsExactOutRouteArgs,
+        signer_seeds: &[&[u8]],
+        account_infos: &'info [AccountInfo<'info>],
+    ) -> Result<(u64, u64)> {
+        // Jupiter V6 will revert if the amount in exceeds the quoted in amount after slippage. But
+        // we still verify that the out amount was actually delivered.
+        let limit_amount = args.out_amount;
+
+        self.invoke_shared_accounts_route(
+            (jupiter_v6::SHARED_ACCOUNTS_EXACT_OUT_ROUTE_SELECTOR, args)
+                .try_to_vec()
+                .unwrap(),
+            signer_seeds,
+            account_infos,
+            limit_amount,
+        )
+    }
+
+    fn invoke_shared_accounts_route(
+        &self,
+        data: Vec<u8>,
+        signer_seeds: &[&[u8]],
+        account_infos: &'info [AccountInfo<'info>],
+        limit_amount: u64,
+    ) -> Result<(u64, u64)> {
         let mut accounts = account_infos
             .iter()
             .map(|acc| match acc.is_writable {
@@ -878,9 +1357,7 @@ impl<'info> JupiterV6SharedAccountsRoute<'info> {
             &solana_program::instruction::Instruction {
                 program_id: jupiter_v6::JUPITER_V6_PROGRAM_ID,
                 accounts,
-                data: (jupiter_v6::SHARED_ACCOUNTS_ROUTE_SELECTOR, args)
-                    .try_to_vec()
-                    .unwrap(),
+                data,
             },
             account_infos,
             &[signer_seeds],
@@ -906,3 +1383,435 @@ impl<'info> JupiterV6SharedAccountsRoute<'info> {
         ))
     }
 }
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+//
+//  Orca Whirlpool handling.
+//
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Accounts of the Whirlpool swap instruction, followed by the Whirlpool program. The Whirlpool
+/// program verifies the vaults, tick arrays and oracle against the whirlpool.
+#[derive(Accounts)]
+pub struct OrcaWhirlpoolSwap<'info> {
+    pub token_program: Program<'info, token::Token>,
+
+    /// CHECK: This account will be the Swap Layer's swap authority.
+    pub token_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Must be owned by the Whirlpool program.
+    #[account(
+        mut,
+        owner = orca_whirlpool::ORCA_WHIRLPOOL_PROGRAM_ID,
+    )]
+    pub whirlpool: UncheckedAccount<'info>,
+
+    /// CHECK: This account will be one of the Swap Layer's swap token accounts.
+    #[account(mut)]
+    pub token_owner_account_a: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub token_vault_a: UncheckedAccount<'info>,
+
+    /// CHECK: This account will be one of the Swap Layer's swap token accounts.
+    #[account(mut)]
+    pub token_owner_account_b: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub token_vault_b: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub tick_array_0: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub tick_array_1: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub tick_array_2: UncheckedAccount<'info>,
+
+    /// CHECK: Seeds must be \["oracle", whirlpool.key()\] (Whirlpool Program).
+    pub oracle: UncheckedAccount<'info>,
+
+    /// CHECK: Must equal Whirlpool Program ID.
+    #[account(address = orca_whirlpool::ORCA_WHIRLPOOL_PROGRAM_ID)]
+    pub orca_whirlpool_program: UncheckedAccount<'info>,
+}
+
+/// Accounts of the Whirlpool two hop swap instruction, followed by the Whirlpool program. The
+/// output token account of the first swap is the input token account of the second swap.
+#[derive(Accounts)]
+pub struct OrcaWhirlpoolTwoHopSwap<'info> {
+    pub token_program: Program<'info, token::Token>,
+
+    /// CHECK: This account will be the Swap Layer's swap authority.
+    pub token_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Must be owned by the Whirlpool program.
+    #[account(
+        mut,
+        owner = orca_whirlpool::ORCA_WHIRLPOOL_PROGRAM_ID,
+    )]
+    pub whirlpool_one: UncheckedAccount<'info>,
+
+    /// CHECK: Must be owned by the Whirlpool program.
+    #[account(
+        mut,
+        owner = orca_whirlpool::ORCA_WHIRLPOOL_PROGRAM_ID,
+    )]
+    pub whirlpool_two: UncheckedAccount<'info>,
+
+    /// CHECK: This account will be one of the Swap Layer's swap token accounts.
+    #[account(mut)]
+    pub token_owner_account_one_a: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub token_vault_one_a: UncheckedAccount<'info>,
+
+    /// CHECK: This account will be one of the Swap Layer's swap token accounts.
+    #[account(mut)]
+    pub token_owner_account_one_b: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub token_vault_one_b: UncheckedAccount<'info>,
+
+    /// CHECK: This account will be one of the Swap Layer's swap token accounts.
+    #[account(mut)]
+    pub token_owner_account_two_a: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub token_vault_two_a: UncheckedAccount<'info>,
+
+    /// CHECK: This account will be one of the Swap Layer's swap token accounts.
+    #[account(mut)]
+    pub token_owner_account_two_b: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub token_vault_two_b: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub tick_array_one_0: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub tick_array_one_1: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub tick_array_one_2: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub tick_array_two_0: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub tick_array_two_1: UncheckedAccount<'info>,
+
+    /// CHECK: Verified by the Whirlpool program.
+    #[account(mut)]
+    pub tick_array_two_2: UncheckedAccount<'info>,
+
+    /// CHECK: Seeds must be \["oracle", whirlpool_one.key()\] (Whirlpool Program).
+    pub oracle_one: UncheckedAccount<'info>,
+
+    /// CHECK: Seeds must be \["oracle", whirlpool_two.key()\] (Whirlpool Program).
+    pub oracle_two: UncheckedAccount<'info>,
+
+    /// CHECK: Must equal Whirlpool Program ID.
+    #[account(address = orca_whirlpool::ORCA_WHIRLPOOL_PROGRAM_ID)]
+    pub orca_whirlpool_program: UncheckedAccount<'info>,
+}
+
+/// Swap through one whirlpool (swap instruction) or two whirlpools (two hop swap instruction),
+/// with the direction of each swap.
+pub enum OrcaWhirlpoolRoute<'info> {
+    Swap {
+        accounts: Box<OrcaWhirlpoolSwap<'info>>,
+        a_to_b: bool,
+    },
+    TwoHopSwap {
+        accounts: Box<OrcaWhirlpoolTwoHopSwap<'info>>,
+        a_to_b_one: bool,
+        a_to_b_two: bool,
+    },
+}
+
+impl<'info> OrcaWhirlpoolRoute<'info> {
+    /// Set up the swap with the given direction for each whirlpool. The number of directions
+    /// determines whether the accounts are deserialized for the swap or two hop swap instruction.
+    pub fn set_up(
+        mut cpi_account_infos: &'info [AccountInfo<'info>],
+        a_to_b: &[bool],
+    ) -> Result<Self> {
+        match *a_to_b {
+            [a_to_b] => {
+                let accounts = OrcaWhirlpoolSwap::try_accounts(
+                    &orca_whirlpool::ORCA_WHIRLPOOL_PROGRAM_ID,
+                    &mut cpi_account_infos,
+                    &[],
+                    &mut OrcaWhirlpoolSwapBumps {},
+                    &mut Default::default(),
+                )?;
+
+                Ok(Self::Swap {
+                    accounts: Box::new(accounts),
+                    a_to_b,
+                })
+            }
+            [a_to_b_one, a_to_b_two] => {
+                let accounts = OrcaWhirlpoolTwoHopSwap::try_accounts(
+                    &orca_whirlpool::ORCA_WHIRLPOOL_PROGRAM_ID,
+                    &mut cpi_account_infos,
+                    &[],
+                    &mut OrcaWhirlpoolTwoHopSwapBumps {},
+                    &mut Default::default(),
+                )?;
+
+                Ok(Self::TwoHopSwap {
+                    accounts: Box::new(accounts),
+                    a_to_b_one,
+                    a_to_b_two,
+                })
+            }
+            _ => err!(SwapLayerError::InvalidSwapType),
+        }
+    }
+
+    /// Require that the whirlpools and directions are the ones encoded in the swap message.
+    pub fn require_whirlpools(&self, pools: &[OrcaWhirlpoolPool]) -> Result<()> {
+        let whirlpools = match self {
+            Self::Swap { accounts, a_to_b } => vec![(accounts.whirlpool.key(), *a_to_b)],
+            Self::TwoHopSwap {
+                accounts,
+                a_to_b_one,
+                a_to_b_two,
+            } => vec![
+                (accounts.whirlpool_one.key(), *a_to_b_one),
+                (accounts.whirlpool_two.key(), *a_to_b_two),
+            ],
+        };
+
+        require!(
+            whirlpools.len() == pools.len()
+                && whirlpools.iter().zip(pools).all(|((key, a_to_b), pool)| {
+                    *key == Pubkey::from(pool.whirlpool) && *a_to_b == pool.a_to_b
+                }),
+            SwapLayerError::OrcaWhirlpoolMismatch
+        );
+
+        Ok(())
+    }
+
+    /// Verify that the swap authority swaps from the source swap token account into the
+    /// destination swap token account. The Whirlpool program requires that the mints of these
+    /// token accounts match the whirlpool vaults.
+    pub fn verify_swap_accounts(
+        &self,
+        swap_authority: Pubkey,
+        src_swap_token: Pubkey,
+        dst_swap_token: Pubkey,
+    ) -> Result<()> {
+        let token_authority = match self {
+            Self::Swap { accounts, .. } => &accounts.token_authority,
+            Self::TwoHopSwap { accounts, .. } => &accounts.token_authority,
+        };
+        require_keys_eq!(
+            token_authority.key(),
+            swap_authority,
+            SwapLayerError::InvalidSwapAuthority
+        );
+        require_keys_eq!(
+            self.src_token().key(),
+            src_swap_token,
+            SwapLayerError::InvalidSourceSwapToken
+        );
+        require_keys_eq!(
+            self.dst_token().key(),
+            dst_swap_token,
+            SwapLayerError::InvalidDestinationSwapToken
+        );
+
+        // The output of the first swap is the input of the second swap, so this token account must
+        // be the same for both swaps and belong to the swap authority.
+        if let Self::TwoHopSwap {
+            accounts,
+            a_to_b_one,
+            a_to_b_two,
+        } = self
+        {
+            let (intermediate_out, intermediate_in) = match (a_to_b_one, a_to_b_two) {
+                (true, true) => (
+                    &accounts.token_owner_account_one_b,
+                    &accounts.token_owner_account_two_a,
+                ),
+                (true, false) => (
+                    &accounts.token_owner_account_one_b,
+                    &accounts.token_owner_account_two_b,
+                ),
+                (false, true) => (
+                    &accounts.token_owner_account_one_a,
+                    &accounts.token_owner_account_two_a,
+                ),
+                (false, false) => (
+                    &accounts.token_owner_account_one_a,
+                    &accounts.token_owner_account_two_b,
+                ),
+            };
+            require_keys_eq!(
+                intermediate_out.key(),
+                intermediate_in.key(),
+                SwapLayerError::InvalidIntermediateSwapToken
+            );
+
+            let intermediate_owner = token_interface::TokenAccount::try_deserialize(
+                &mut &intermediate_in.data.borrow()[..],
+            )
+            .map(|token| token.owner)?;
+            require_keys_eq!(
+                intermediate_owner,
+                swap_authority,
+                SwapLayerError::InvalidIntermediateSwapToken
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Swap the exact amount in, requiring at least the other amount threshold out. Returns the
+    /// amount out and the residual in the source token account.
+    pub fn swap_exact_in(
+        &self,
+        amount: u64,
+        other_amount_threshold: u64,
+        signer_seeds: &[&[u8]],
+        account_infos: &'info [AccountInfo<'info>],
+    ) -> Result<(u64, u64)> {
+        let data = match self {
+            Self::Swap { a_to_b, .. } => (
+                orca_whirlpool::SWAP_SELECTOR,
+                SwapArgs {
+                    amount,
+                    other_amount_threshold,
+                    sqrt_price_limit: orca_whirlpool::sqrt_price_limit(*a_to_b),
+                    amount_specified_is_input: true,
+                    a_to_b: *a_to_b,
+                },
+            )
+                .try_to_vec()
+                .unwrap(),
+            Self::TwoHopSwap {
+                a_to_b_one,
+                a_to_b_two,
+                ..
+            } => (
+                orca_whirlpool::TWO_HOP_SWAP_SELECTOR,
+                TwoHopSwapArgs {
+                    amount,
+                    other_amount_threshold,
+                    amount_specified_is_input: true,
+                    a_to_b_one: *a_to_b_one,
+                    a_to_b_two: *a_to_b_two,
+                    sqrt_price_limit_one: orca_whirlpool::sqrt_price_limit(*a_to_b_one),
+                    sqrt_price_limit_two: orca_whirlpool::sqrt_price_limit(*a_to_b_two),
+                },
+            )
+                .try_to_vec()
+                .unwrap(),
+        };
+
+        let mut accounts = account_infos
+            .iter()
+            .map(|acc| match acc.is_writable {
+                false => AccountMeta::new_readonly(*acc.key, acc.is_signer),
+                true => AccountMeta::new(*acc.key, acc.is_signer),
+            })
+            .collect::<Vec<_>>();
+        accounts[1].is_signer = true;
+
+        solana_program::program::invoke_signed(
+            &solana_program::instruction::Instruction {
+                program_id: orca_whirlpool::ORCA_WHIRLPOOL_PROGRAM_ID,
+                accounts,
+                data,
+            },
+            account_infos,
+            &[signer_seeds],
+        )?;
+
+        // Like Jupiter V6 swaps, reload both token accounts to find the swap result and any
+        // residual.
+        let amount_out = token_interface::TokenAccount::try_deserialize_unchecked(
+            &mut &self.dst_token().data.borrow()[..],
+        )
+        .map(|token| token.amount)?;
+        require_gte!(
+            amount_out,
+            other_amount_threshold,
+            SwapLayerError::SwapFailed
+        );
+
+        Ok((
+            amount_out,
+            token_interface::TokenAccount::try_deserialize_unchecked(
+                &mut &self.src_token().data.borrow()[..],
+            )
+            .map(|token| token.amount)?,
+        ))
+    }
+
+    fn src_token(&self) -> &UncheckedAccount<'info> {
+        match self {
+            Self::Swap {
+                accounts,
+                a_to_b: true,
+            } => &accounts.token_owner_account_a,
+            Self::Swap {
+                accounts,
+                a_to_b: false,
+            } => &accounts.token_owner_account_b,
+            Self::TwoHopSwap {
+                accounts,
+                a_to_b_one: true,
+                ..
+            } => &accounts.token_owner_account_one_a,
+            Self::TwoHopSwap {
+                accounts,
+                a_to_b_one: false,
+                ..
+            } => &accounts.token_owner_account_one_b,
+        }
+    }
+
+    fn dst_token(&self) -> &UncheckedAccount<'info> {
+        match self {
+            Self::Swap {
+                accounts,
+                a_to_b: true,
+            } => &accounts.token_owner_account_b,
+            Self::Swap {
+                accounts,
+                a_to_b: false,
+            } => &accounts.token_owner_account_a,
+            Self::TwoHopSwap {
+                accounts,
+                a_to_b_two: true,
+                ..
+            } => &accounts.token_owner_account_two_b,
+            Self::TwoHopSwap {
+                accounts,
+                a_to_b_two: false,
+                ..
+            } => &accounts.token_owner_account_two_a,
+        }
+    }
+}
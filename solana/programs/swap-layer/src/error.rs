@@ -33,6 +33,14 @@ pub enum SwapLayerError {
     SwapTimeLimitNotExceeded = 0x116,
     ImmutableProgram = 0x118,
     InvalidRedeemer = 0x11a,
+    RecipientRequired = 0x11c,
+    FeeRecipientTokenRequired = 0x11d,
+    IntegratorFeeTokenRequired = 0x11e,
+    InvalidIntegratorFeeToken = 0x11f,
+    SplitOutputsNotSupported = 0x120,
+    SplitOutputsRequired = 0x121,
+    InvalidSplitOutputIndex = 0x122,
+    SplitOutputAlreadyCompleted = 0x123,
 
     // EVM Execution Param errors
     InvalidBaseFee = 0x200,
@@ -52,7 +60,10 @@ pub enum SwapLayerError {
     ZeroMinAmountOut = 0x262,
     DelegatedAmountMismatch = 0x264,
     NotProgramTransferAuthority = 0x265,
+    /// Deprecated: stage_outbound accepts exact out swaps. This error is no longer returned and is
+    /// kept so that the other error codes are not renumbered.
     ExactInRequired = 0x266,
+    ExactOutRequired = 0x267,
     InsufficientAmountIn = 0x268,
 
     // Swap
@@ -63,6 +74,7 @@ pub enum SwapLayerError {
     MinAmountOutRequired = 0x308,
     InvalidSourceResidual = 0x310,
     SourceResidualMismatch = 0x312,
+    RecipientUsdcTokenRequired = 0x314,
 
     // Jupiter V6
     #[msg("Jupiter V6 Authority ID must be >= 0 and < 8")]
@@ -79,6 +91,10 @@ pub enum SwapLayerError {
     SwapFailed = 0x346,
     InvalidSwapInAmount = 0x348,
 
+    // Orca Whirlpool
+    OrcaWhirlpoolMismatch = 0x360,
+    InvalidIntermediateSwapToken = 0x362,
+
     // Ownership
     NoTransferOwnershipRequest = 0x400,
     NotPendingOwner = 0x401,
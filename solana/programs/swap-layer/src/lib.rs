@@ -14,15 +14,14 @@ pub mod utils;
 declare_id!("SwapLayer1111111111111111111111111111111111");
 
 const CUSTODIAN_BUMP: u8 = 254;
-const COMPLETE_TOKEN_SEED_PREFIX: &[u8] = b"complete";
 
-const SWAP_AUTHORITY_SEED_PREFIX: &[u8] = b"swap-authority";
-const TRANSFER_AUTHORITY_SEED_PREFIX: &[u8] = b"transfer-authority";
-
-const PREPARED_ORDER_SEED_PREFIX: &[u8] = b"prepared-order";
-const STAGED_CUSTODY_TOKEN_SEED_PREFIX: &[u8] = b"staged-custody";
-
-const MAX_BPS: u32 = 1_000_000; // 10,000.00 bps (100%)
+use swap_layer_messages::{
+    relayer_fees::MAX_BPS,
+    seeds::{
+        COMPLETE_TOKEN_SEED_PREFIX, PREPARED_ORDER_SEED_PREFIX, STAGED_CUSTODY_TOKEN_SEED_PREFIX,
+        SWAP_AUTHORITY_SEED_PREFIX, TRANSFER_AUTHORITY_SEED_PREFIX,
+    },
+};
 
 #[program]
 pub mod swap_layer {
@@ -65,6 +64,18 @@ pub mod swap_layer {
         processor::update_peer(ctx, args)
     }
 
+    /// Reallocates a peer created before the `max_integrator_fee` field was added to `Peer`, so it
+    /// can be deserialized again. Integrator fees stay disabled for the peer until the `owner`
+    /// updates it.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context for migrating the peer.
+    /// * `chain` - The chain ID of the peer.
+    pub fn migrate_peer(ctx: Context<MigratePeer>, chain: u16) -> Result<()> {
+        processor::migrate_peer(ctx, chain)
+    }
+
     /// Submits an ownership transfer request.
     ///
     /// This instruction sets the `pending_owner` field in the `Custodian` account. This instruction
@@ -244,6 +255,24 @@ pub mod swap_layer {
         processor::initiate_swap_exact_in(ctx, instruction_data)
     }
 
+    /// Initiates a swap with exact output in the swap layer. The swap will produce exactly the
+    /// USDC amount specified when the outbound transfer was staged (including the relaying fee),
+    /// and any unspent source tokens will be refunded to the sender.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context for initiating the swap.
+    /// * `instruction_data` - The instruction data for initiating the swap.
+    pub fn initiate_swap_exact_out<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, InitiateSwapExactOut<'info>>,
+        instruction_data: Vec<u8>,
+    ) -> Result<()>
+    where
+        'c: 'info,
+    {
+        processor::initiate_swap_exact_out(ctx, instruction_data)
+    }
+
     /// Completes a direct swap in the swap layer.
     ///
     /// # Arguments
@@ -294,4 +323,47 @@ pub mod swap_layer {
     {
         processor::complete_swap_payload(ctx, instruction_data)
     }
+
+    /// Stages a fill whose swap message encodes split outputs. This instruction
+    /// records each recipient's share of the fill in the `staged_split` account
+    /// and moves the USDC into a custody token account. If the redeem mode is
+    /// relay, the relaying fee is deducted before the fill is split.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context for staging the split.
+    pub fn stage_split(ctx: Context<StageSplit>) -> Result<()> {
+        processor::stage_split(ctx)
+    }
+
+    /// Completes one output of a staged split by transferring its share as
+    /// USDC. The staged split is closed once every output is completed.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context for completing the split output.
+    /// * `index` - The index of the output, where the message recipient is 0.
+    pub fn complete_split_transfer(ctx: Context<CompleteSplitTransfer>, index: u8) -> Result<()> {
+        processor::complete_split_transfer(ctx, index)
+    }
+
+    /// Completes one output of a staged split by swapping its share into the
+    /// encoded output token. The staged split is closed once every output is
+    /// completed.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context for completing the split output.
+    /// * `index` - The index of the output, where the message recipient is 0.
+    /// * `instruction_data` - The instruction data for completing the swap.
+    pub fn complete_split_swap<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CompleteSplitSwap<'info>>,
+        index: u8,
+        instruction_data: Vec<u8>,
+    ) -> Result<()>
+    where
+        'c: 'info,
+    {
+        processor::complete_split_swap(ctx, index, instruction_data)
+    }
 }
@@ -32,6 +32,7 @@ pub struct AddPeerArgs {
     pub chain: u16,
     pub address: [u8; 32],
     pub relay_params: RelayParams,
+    pub max_integrator_fee: u64,
 }
 
 pub fn add_peer(ctx: Context<AddPeer>, args: AddPeerArgs) -> Result<()> {
@@ -0,0 +1,38 @@
+use crate::{composite::*, error::SwapLayerError, state::Peer};
+use anchor_lang::{prelude::*, Discriminator};
+
+#[derive(Accounts)]
+#[instruction(chain: u16)]
+pub struct MigratePeer<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    admin: Admin<'info>,
+
+    /// CHECK: Peer account, which may have been created before [Peer] gained
+    /// [max_integrator_fee](Peer::max_integrator_fee). Such an account is too small to deserialize
+    /// as [Peer], so it is reallocated here. The appended bytes are zeroed, which disables
+    /// integrator fees for this peer until it is updated.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [
+            Peer::SEED_PREFIX,
+            &chain.to_be_bytes()
+        ],
+        bump,
+        constraint = peer.try_borrow_data()?.starts_with(&Peer::DISCRIMINATOR)
+            @ SwapLayerError::InvalidPeer,
+        realloc = 8 + Peer::INIT_SPACE,
+        realloc::payer = payer,
+        realloc::zero = true,
+    )]
+    peer: UncheckedAccount<'info>,
+
+    system_program: Program<'info, System>,
+}
+
+pub fn migrate_peer(_ctx: Context<MigratePeer>, _chain: u16) -> Result<()> {
+    // The account is reallocated by the account constraints.
+    Ok(())
+}
@@ -1,6 +1,9 @@
 mod add;
 pub use add::*;
 
+mod migrate;
+pub use migrate::*;
+
 mod update;
 pub use update::*;
 
@@ -30,6 +33,7 @@ pub fn handle_add_peer(
         chain,
         address,
         relay_params,
+        max_integrator_fee,
     } = args;
 
     let seeds = PeerSeeds {
@@ -47,6 +51,7 @@ pub fn handle_add_peer(
         seeds,
         address,
         relay_params,
+        max_integrator_fee,
     });
 
     Ok(())
@@ -1,3 +1,6 @@
+mod split;
+pub use split::*;
+
 mod swap;
 pub use swap::*;
 
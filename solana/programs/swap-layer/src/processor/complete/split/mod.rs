@@ -0,0 +1,8 @@
+mod stage;
+pub use stage::*;
+
+mod swap;
+pub use swap::*;
+
+mod transfer;
+pub use transfer::*;
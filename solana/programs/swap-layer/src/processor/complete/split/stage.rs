@@ -0,0 +1,283 @@
+use crate::{
+    composite::*,
+    error::SwapLayerError,
+    state::{StagedSplit, StagedSplitInfo, StagedSplitOutput, StagedSplitSeeds},
+    utils,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token;
+use swap_layer_messages::{
+    messages::SwapMessageV2,
+    raw::RedeemModeRef,
+    types::{split_amounts, RedeemMode},
+};
+use token_router::state::FillType;
+
+#[derive(Accounts)]
+pub struct StageSplit<'info> {
+    /// The payer of the transaction. This could either be the recipient or a relayer. If the payer
+    /// is the recipient, no relaying fee is charged.
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    #[account(
+        constraint = {
+            let swap_msg = consume_swap_layer_fill.read_message_ref_unchecked();
+
+            require!(
+                !swap_msg.split_outputs().is_empty(),
+                SwapLayerError::SplitOutputsRequired
+            );
+            require!(
+                matches!(
+                    swap_msg.redeem_mode(),
+                    RedeemModeRef::Direct | RedeemModeRef::Relay { .. }
+                ),
+                SwapLayerError::InvalidRedeemMode
+            );
+
+            true
+        }
+    )]
+    consume_swap_layer_fill: ConsumeSwapLayerFill<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = StagedSplit::try_compute_size(
+            &consume_swap_layer_fill.read_message_unchecked()
+        )?,
+        seeds = [
+            StagedSplit::SEED_PREFIX,
+            consume_swap_layer_fill.prepared_fill_key().as_ref(),
+        ],
+        bump
+    )]
+    /// The staged split account that records each recipient's share of the fill. This account also
+    /// warehouses the seeds necessary to derive the staged custody token account.
+    staged_split: Box<Account<'info, StagedSplit>>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = usdc,
+        token::authority = staged_split,
+        seeds = [
+            crate::STAGED_CUSTODY_TOKEN_SEED_PREFIX,
+            staged_split.key().as_ref(),
+        ],
+        bump,
+    )]
+    /// The staged custody token account that will be created to hold the USDC until every output
+    /// is delivered. This account is derived from the staged split account.
+    staged_custody_token: Box<Account<'info, token::TokenAccount>>,
+
+    /// CHECK: Recipient of the gas dropoff. This account is only required if a relayer stages a
+    /// fill with [Relay](swap_layer_messages::types::RedeemMode::Relay) and must be the encoded
+    /// recipient.
+    #[account(
+        mut,
+        address = Pubkey::from(
+            *consume_swap_layer_fill.read_message_ref_unchecked().recipient()
+        ) @ SwapLayerError::InvalidRecipient,
+    )]
+    recipient: Option<UncheckedAccount<'info>>,
+
+    /// Token account receiving the relaying fee. This account is only required if a relayer
+    /// stages a fill with [Relay](swap_layer_messages::types::RedeemMode::Relay).
+    #[account(
+        mut,
+        address = consume_swap_layer_fill.custodian.fee_recipient_token,
+    )]
+    fee_recipient_token: Option<Box<Account<'info, token::TokenAccount>>>,
+
+    usdc: Usdc<'info>,
+
+    token_program: Program<'info, token::Token>,
+    system_program: Program<'info, System>,
+}
+
+pub fn stage_split(ctx: Context<StageSplit>) -> Result<()> {
+    // Consume the prepared fill, and send the tokens to the staged custody account.
+    let fill_amount = ctx
+        .accounts
+        .consume_swap_layer_fill
+        .consume_prepared_fill_unchecked(
+            ctx.accounts.staged_custody_token.as_ref().as_ref(),
+            &ctx.accounts.token_program,
+        )?;
+
+    let prepared_fill = ctx.accounts.consume_swap_layer_fill.prepared_fill_key();
+    let staged_split_signer_seeds = &[
+        StagedSplit::SEED_PREFIX,
+        prepared_fill.as_ref(),
+        &[ctx.bumps.staged_split],
+    ];
+
+    // The integrator is paid before the relaying fee is deducted.
+    let fill_amount = ctx.accounts.consume_swap_layer_fill.pay_integrator_fee(
+        fill_amount,
+        ctx.accounts.staged_custody_token.as_ref().as_ref(),
+        ctx.accounts.staged_split.as_ref().as_ref(),
+        &ctx.accounts.token_program,
+        &[staged_split_signer_seeds],
+    )?;
+
+    let SwapMessageV2 {
+        recipient,
+        redeem_mode,
+        output_token,
+        fallback_recipient: _,
+        integrator_fee: _,
+        split_outputs,
+    } = ctx
+        .accounts
+        .consume_swap_layer_fill
+        .read_message_unchecked();
+    let recipient = Pubkey::from(recipient);
+
+    // If a relayer is staging on behalf of the recipient, the relayer is paid from the staged USDC
+    // and drops off gas to the recipient. Override the relaying fee to zero if the payer is the
+    // recipient (self redemption).
+    let (split_amount, swap_time_limit_end) = match redeem_mode {
+        RedeemMode::Relay {
+            gas_dropoff,
+            relaying_fee,
+        } => {
+            let split_amount = if ctx.accounts.payer.key() != recipient {
+                pay_relayer(
+                    &ctx,
+                    fill_amount,
+                    utils::gas_dropoff::denormalize_gas_dropoff(gas_dropoff),
+                    relaying_fee.into(),
+                    staged_split_signer_seeds,
+                )?
+            } else {
+                fill_amount
+            };
+
+            (split_amount, Some(swap_time_limit_end(&ctx)?))
+        }
+        _ => (fill_amount, None),
+    };
+
+    // The message recipient receives whatever is left after every split output is paid.
+    let (recipient_amount, amounts) = split_amounts(&split_outputs, split_amount);
+
+    let outputs = std::iter::once(StagedSplitOutput {
+        recipient,
+        usdc_recipient: ctx
+            .accounts
+            .consume_swap_layer_fill
+            .fallback_recipient()
+            .unwrap_or(recipient),
+        amount: recipient_amount,
+        output_token,
+        is_completed: false,
+    })
+    .chain(
+        split_outputs
+            .into_iter()
+            .zip(amounts)
+            .map(|(split_output, amount)| StagedSplitOutput {
+                recipient: Pubkey::from(split_output.recipient),
+                usdc_recipient: Pubkey::from(split_output.recipient),
+                amount,
+                output_token: split_output.output_token,
+                is_completed: false,
+            }),
+    )
+    .collect();
+
+    ctx.accounts.staged_split.set_inner(StagedSplit {
+        seeds: StagedSplitSeeds {
+            prepared_fill,
+            bump: ctx.bumps.staged_split,
+        },
+        info: StagedSplitInfo {
+            custody_token_bump: ctx.bumps.staged_custody_token,
+            staged_by: ctx.accounts.payer.key(),
+            source_chain: ctx.accounts.consume_swap_layer_fill.fill.source_chain,
+            swap_time_limit_end,
+        },
+        outputs,
+    });
+
+    // Done.
+    Ok(())
+}
+
+/// Time after which a relayer may deliver USDC instead of performing the swap for an output.
+fn swap_time_limit_end(ctx: &Context<StageSplit>) -> Result<i64> {
+    let fill = &ctx.accounts.consume_swap_layer_fill.fill;
+    let swap_time_limit = &ctx
+        .accounts
+        .consume_swap_layer_fill
+        .source_peer
+        .relay_params
+        .swap_time_limit;
+
+    let time_limit = match fill.fill_type {
+        FillType::FastFill => swap_time_limit.fast_limit,
+        FillType::WormholeCctpDeposit => swap_time_limit.finalized_limit,
+        FillType::Unset => return err!(SwapLayerError::UnsupportedFillType),
+    };
+
+    Ok(fill.timestamp.saturating_add(time_limit.into()))
+}
+
+/// Pay the relaying fee to the fee recipient and drop off gas to the recipient. Returns the amount
+/// of USDC left to split.
+fn pay_relayer(
+    ctx: &Context<StageSplit>,
+    fill_amount: u64,
+    gas_dropoff: u64,
+    relaying_fee: u64,
+    staged_split_signer_seeds: &[&[u8]],
+) -> Result<u64> {
+    let split_amount = fill_amount
+        .checked_sub(relaying_fee)
+        .ok_or_else(|| SwapLayerError::InvalidRelayerFee)?;
+
+    if gas_dropoff > 0 {
+        let recipient = ctx
+            .accounts
+            .recipient
+            .as_ref()
+            .ok_or_else(|| SwapLayerError::RecipientRequired)?;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: recipient.to_account_info(),
+                },
+            ),
+            gas_dropoff,
+        )?;
+    }
+
+    if relaying_fee > 0 {
+        let fee_recipient_token = ctx
+            .accounts
+            .fee_recipient_token
+            .as_ref()
+            .ok_or_else(|| SwapLayerError::FeeRecipientTokenRequired)?;
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.staged_custody_token.to_account_info(),
+                    to: fee_recipient_token.to_account_info(),
+                    authority: ctx.accounts.staged_split.to_account_info(),
+                },
+                &[staged_split_signer_seeds],
+            ),
+            relaying_fee,
+        )?;
+    }
+
+    Ok(split_amount)
+}
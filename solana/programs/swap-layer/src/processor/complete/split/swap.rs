@@ -0,0 +1,157 @@
+use crate::{composite::*, error::SwapLayerError};
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token, token_interface};
+use swap_layer_messages::{messages::SwapMessageV2, raw::OutputTokenRef};
+
+#[derive(Accounts)]
+pub struct CompleteSplitSwap<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    consume_staged_split: ConsumeStagedSplit<'info>,
+
+    /// CHECK: Seeds must be \["swap-authority", prepared_fill.key()\]. The prepared fill was
+    /// consumed when the split was staged, so this authority only swaps split outputs.
+    #[account(
+        seeds = [
+            crate::SWAP_AUTHORITY_SEED_PREFIX,
+            consume_staged_split.staged_split.seeds.prepared_fill.as_ref(),
+        ],
+        bump,
+    )]
+    authority: UncheckedAccount<'info>,
+
+    /// Temporary swap token account to receive the output's USDC from the staged custody token
+    /// account. This account will be closed at the end of this instruction.
+    ///
+    /// NOTE: This ATA must already be created.
+    #[account(
+        mut,
+        associated_token::mint = usdc,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program
+    )]
+    src_swap_token: Box<Account<'info, token::TokenAccount>>,
+
+    /// Temporary swap token account to receive destination mint after the swap. This account will
+    /// be closed at the end of this instruction.
+    ///
+    /// NOTE: This ATA must already be created.
+    #[account(
+        mut,
+        associated_token::mint = dst_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = dst_token_program
+    )]
+    dst_swap_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// CHECK: In case the exact out swap does not use all USDC, we send residual to this token
+    /// account. This account is only required for exact out swaps and must be a USDC token account
+    /// owned by the output's recipient.
+    #[account(mut)]
+    recipient_usdc_token: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        address = associated_token::get_associated_token_address_with_program_id(
+            &recipient.key(),
+            &dst_mint.key(),
+            &dst_token_program.key()
+        )
+    )]
+    /// Recipient associated token account. The recipient authority check is necessary to ensure
+    /// that the recipient is the intended recipient of the output.
+    ///
+    /// If OutputToken::Other, this account will be deserialized to ensure that the recipient is
+    /// the owner of this token account.
+    ///
+    /// CHECK: Mutable ATA whose owner is the recipient and mint is the destination mint.
+    recipient_token: UncheckedAccount<'info>,
+
+    /// CHECK: This account must be the recipient of the output being completed.
+    #[account(mut)]
+    recipient: UncheckedAccount<'info>,
+
+    /// This account must be verified as the source mint for the swap.
+    usdc: Usdc<'info>,
+
+    /// CHECK: This account must be verified as the destination mint for the swap.
+    #[account(constraint = usdc.key() != dst_mint.key() @ SwapLayerError::SameMint)]
+    dst_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    dst_token_program: Interface<'info, token_interface::TokenInterface>,
+    system_program: Program<'info, System>,
+}
+
+pub fn complete_split_swap<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CompleteSplitSwap<'info>>,
+    index: u8,
+    instruction_data: Vec<u8>,
+) -> Result<()>
+where
+    'c: 'info,
+{
+    let output = ctx.accounts.consume_staged_split.complete_output(index)?;
+
+    require_keys_eq!(
+        ctx.accounts.recipient.key(),
+        output.recipient,
+        SwapLayerError::InvalidRecipient
+    );
+
+    // Only an output token that encodes a swap can be completed with this instruction. Split
+    // outputs are encoded as in swap message V2, which includes the swap mode.
+    let mut encoded_output_token = Vec::with_capacity(output.output_token.written_size_v2());
+    output
+        .output_token
+        .write_v2(&mut encoded_output_token)
+        .map_err(|_| SwapLayerError::InvalidOutputToken)?;
+    require!(
+        verify_output_swap(
+            OutputTokenRef::parse_v2(&encoded_output_token)
+                .map_err(|_| SwapLayerError::InvalidOutputToken)?,
+            &ctx.accounts.dst_mint.key(),
+        )?,
+        SwapLayerError::InvalidOutputToken
+    );
+
+    let consume_staged_split = &ctx.accounts.consume_staged_split;
+    consume_staged_split.transfer(ctx.accounts.src_swap_token.as_ref().as_ref(), output.amount)?;
+
+    handle_complete_swap_jup_v6(
+        HandleCompleteSwap {
+            payer: &ctx.accounts.payer,
+            prepared_fill_key: consume_staged_split.staged_split.seeds.prepared_fill,
+            swap_authority: &ctx.accounts.authority,
+            src_swap_token: &ctx.accounts.src_swap_token,
+            dst_swap_token: &ctx.accounts.dst_swap_token,
+            fee_recipient_token: &consume_staged_split.fee_recipient_token,
+            recipient_usdc_token: ctx.accounts.recipient_usdc_token.as_ref(),
+            dst_mint: &ctx.accounts.dst_mint,
+            token_program: &consume_staged_split.token_program,
+            dst_token_program: &ctx.accounts.dst_token_program,
+            system_program: &ctx.accounts.system_program,
+        },
+        crate::SWAP_AUTHORITY_SEED_PREFIX,
+        ctx.bumps.authority,
+        ctx.remaining_accounts,
+        instruction_data,
+        output.amount,
+        SwapMessageV2 {
+            recipient: output.recipient.to_bytes(),
+            redeem_mode: Default::default(), // RedeemMode is not handled in this method.
+            output_token: output.output_token,
+            fallback_recipient: None,
+            integrator_fee: None,
+            split_outputs: Default::default(),
+        },
+        RecipientAccounts {
+            recipient: &ctx.accounts.recipient,
+            recipient_token: &ctx.accounts.recipient_token,
+        }
+        .into(),
+        Default::default(),
+    )?;
+
+    ctx.accounts.consume_staged_split.close_if_completed()
+}
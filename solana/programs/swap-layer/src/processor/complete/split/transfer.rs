@@ -0,0 +1,65 @@
+use crate::{composite::*, error::SwapLayerError};
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token, token};
+use swap_layer_messages::types::OutputToken;
+
+#[derive(Accounts)]
+pub struct CompleteSplitTransfer<'info> {
+    /// This redeemer is used to check against the output's recipient. If the redeemer is the same
+    /// as the recipient, he is free to redeem his share directly as USDC even if a swap is encoded.
+    /// A relayer may only do so for a relayed fill once the swap time limit is exceeded.
+    redeemer: Signer<'info>,
+
+    consume_staged_split: ConsumeStagedSplit<'info>,
+
+    /// Recipient associated token account, which must belong to the output's recipient.
+    ///
+    /// If a relayer redeems USDC instead of the message recipient's encoded swap and the message
+    /// specifies a fallback recipient, this must be the fallback recipient's associated token
+    /// account instead.
+    #[account(mut)]
+    recipient_token_account: Box<Account<'info, token::TokenAccount>>,
+}
+
+pub fn complete_split_transfer(ctx: Context<CompleteSplitTransfer>, index: u8) -> Result<()> {
+    let output = ctx.accounts.consume_staged_split.complete_output(index)?;
+
+    // The recipient redeeming USDC itself always receives it.
+    let usdc_recipient = if ctx.accounts.redeemer.key() == output.recipient {
+        output.recipient
+    } else {
+        output.usdc_recipient
+    };
+    require_keys_eq!(
+        ctx.accounts.recipient_token_account.key(),
+        associated_token::get_associated_token_address(&usdc_recipient, &common::USDC_MINT),
+        SwapLayerError::InvalidRecipient
+    );
+
+    // Ensure that the swap time limit has been exceeded if a relayer is attempting to deliver USDC
+    // for an output token that is not USDC.
+    match output.output_token {
+        OutputToken::Usdc => {}
+        OutputToken::Gas(_) | OutputToken::Other { .. } => {
+            if ctx.accounts.redeemer.key() != output.recipient {
+                let swap_time_limit_end = ctx
+                    .accounts
+                    .consume_staged_split
+                    .staged_split
+                    .swap_time_limit_end
+                    .ok_or_else(|| SwapLayerError::InvalidRedeemer)?;
+                require!(
+                    Clock::get()?.unix_timestamp >= swap_time_limit_end,
+                    SwapLayerError::SwapTimeLimitNotExceeded
+                );
+            }
+        }
+    }
+
+    ctx.accounts.consume_staged_split.transfer(
+        ctx.accounts.recipient_token_account.as_ref().as_ref(),
+        output.amount,
+    )?;
+
+    ctx.accounts.consume_staged_split.close_if_completed()
+}
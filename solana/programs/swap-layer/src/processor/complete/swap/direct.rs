@@ -41,7 +41,9 @@ where
 
     // Read message to determine redeem mode and consume prepared fill.
     let swap_msg = complete_swap_accounts.read_message_unchecked();
-    let in_amount = complete_swap_accounts.consume_prepared_fill()?;
+    let fill_amount = complete_swap_accounts.consume_prepared_fill()?;
+    let in_amount = complete_swap_accounts
+        .pay_integrator_fee(fill_amount, ctx.bumps.complete_swap.authority)?;
 
     match &swap_msg.redeem_mode {
         RedeemMode::Direct => complete_swap_jup_v6(
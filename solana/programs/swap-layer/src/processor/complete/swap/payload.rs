@@ -2,11 +2,12 @@ use crate::{
     composite::*,
     error::SwapLayerError,
     state::{StagedInbound, StagedInboundInfo, StagedInboundSeeds},
+    utils,
 };
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, system_program};
 use anchor_spl::{token, token_interface};
 use swap_layer_messages::{
-    messages::SwapMessageV1,
+    messages::SwapMessageV2,
     types::{OutputToken, RedeemMode},
 };
 
@@ -23,7 +24,7 @@ pub struct CompleteSwapPayload<'info> {
         payer = payer,
         space = StagedInbound::try_compute_size_if_needed(
             staged_inbound,
-            consume_swap_layer_fill.read_message_unchecked()
+            consume_swap_layer_fill.read_message_ref_unchecked().redeem_mode()
         )?,
         seeds = [
             StagedInbound::SEED_PREFIX,
@@ -65,6 +66,23 @@ pub struct CompleteSwapPayload<'info> {
     )]
     fee_recipient_token: UncheckedAccount<'info>,
 
+    /// CHECK: In case the exact out swap does not use all USDC, we send residual to this token
+    /// account. This account is only required for exact out swaps and must be a USDC token account
+    /// owned by the encoded recipient.
+    #[account(mut)]
+    recipient_usdc_token: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Recipient of the gas dropoff. This account is only required if a relayer redeems a
+    /// fill with [RedeemMode::RelayWithPayload] and must be the encoded recipient. If the recipient
+    /// signs, it is redeeming the fill itself (self redemption) and no relaying fee is paid.
+    #[account(
+        mut,
+        address = Pubkey::from(
+            *consume_swap_layer_fill.read_message_ref_unchecked().recipient()
+        ) @ SwapLayerError::InvalidRecipient,
+    )]
+    recipient: Option<UncheckedAccount<'info>>,
+
     /// This account must be verified as the source mint for the swap.
     usdc: Usdc<'info>,
 
@@ -86,47 +104,91 @@ where
 {
     let staged_inbound = &mut ctx.accounts.staged_inbound;
 
-    let in_amount = ctx.accounts.consume_swap_layer_fill.consume_prepared_fill(
+    let fill_amount = ctx.accounts.consume_swap_layer_fill.consume_prepared_fill(
         ctx.accounts.src_swap_token.as_ref().as_ref(),
         &ctx.accounts.token_program,
     )?;
 
-    let SwapMessageV1 {
+    let SwapMessageV2 {
         recipient,
         redeem_mode,
         output_token,
+        fallback_recipient,
+        integrator_fee,
+        split_outputs,
     } = ctx
         .accounts
         .consume_swap_layer_fill
         .read_message_unchecked();
 
-    match redeem_mode {
-        RedeemMode::Payload { sender, buf } => staged_inbound.set_inner(StagedInbound {
-            seeds: StagedInboundSeeds {
-                prepared_fill: ctx.accounts.consume_swap_layer_fill.prepared_fill_key(),
-                bump: ctx.bumps.staged_inbound,
-            },
-            info: StagedInboundInfo {
-                custody_token: ctx.accounts.dst_swap_token.key(),
-                staged_by: ctx.accounts.payer.key(),
-                source_chain: ctx.accounts.consume_swap_layer_fill.fill.source_chain,
-                sender,
-                recipient: Pubkey::from(recipient),
-                is_native: matches!(&output_token, OutputToken::Gas(_)),
-            },
-            recipient_payload: buf.into(),
-        }),
+    let (sender, buf, relay) = match redeem_mode {
+        RedeemMode::Payload { sender, buf } => (sender, buf, None),
+        RedeemMode::RelayWithPayload {
+            gas_dropoff,
+            relaying_fee,
+            sender,
+            buf,
+        } => (sender, buf, Some((gas_dropoff, relaying_fee))),
         _ => return err!(SwapLayerError::InvalidRedeemMode),
     };
 
+    staged_inbound.set_inner(StagedInbound {
+        seeds: StagedInboundSeeds {
+            prepared_fill: ctx.accounts.consume_swap_layer_fill.prepared_fill_key(),
+            bump: ctx.bumps.staged_inbound,
+        },
+        info: StagedInboundInfo {
+            custody_token: ctx.accounts.dst_swap_token.key(),
+            staged_by: ctx.accounts.payer.key(),
+            source_chain: ctx.accounts.consume_swap_layer_fill.fill.source_chain,
+            sender,
+            recipient: Pubkey::from(recipient),
+            is_native: matches!(&output_token, OutputToken::Gas(_)),
+        },
+        recipient_payload: buf.into(),
+    });
+
+    // The integrator is paid before the relaying fee is deducted.
+    let staged_inbound = &ctx.accounts.staged_inbound;
+    let fill_amount = ctx.accounts.consume_swap_layer_fill.pay_integrator_fee(
+        fill_amount,
+        ctx.accounts.src_swap_token.as_ref().as_ref(),
+        staged_inbound.as_ref().as_ref(),
+        &ctx.accounts.token_program,
+        &[&[
+            StagedInbound::SEED_PREFIX,
+            staged_inbound.seeds.prepared_fill.as_ref(),
+            &[staged_inbound.seeds.bump],
+        ]],
+    )?;
+
+    // If a relayer is redeeming on behalf of the recipient, the relayer is paid from the USDC
+    // before the swap and drops off gas to the recipient. Override the relaying fee to zero if the
+    // recipient signs (self redemption).
+    let is_self_redemption = ctx
+        .accounts
+        .recipient
+        .as_ref()
+        .is_some_and(|recipient| recipient.is_signer);
+    let in_amount = match relay {
+        Some((gas_dropoff, relaying_fee)) if !is_self_redemption => pay_relayer(
+            &ctx,
+            fill_amount,
+            utils::gas_dropoff::denormalize_gas_dropoff(gas_dropoff),
+            relaying_fee.into(),
+        )?,
+        _ => fill_amount,
+    };
+
     handle_complete_swap_jup_v6(
         HandleCompleteSwap {
             payer: &ctx.accounts.payer,
-            consume_swap_layer_fill: &ctx.accounts.consume_swap_layer_fill,
+            prepared_fill_key: ctx.accounts.consume_swap_layer_fill.prepared_fill_key(),
             swap_authority: ctx.accounts.staged_inbound.as_ref().as_ref(),
             src_swap_token: &ctx.accounts.src_swap_token,
             dst_swap_token: &ctx.accounts.dst_swap_token,
             fee_recipient_token: &ctx.accounts.fee_recipient_token,
+            recipient_usdc_token: ctx.accounts.recipient_usdc_token.as_ref(),
             dst_mint: &ctx.accounts.dst_mint,
             token_program: &ctx.accounts.token_program,
             system_program: &ctx.accounts.system_program,
@@ -137,12 +199,69 @@ where
         ctx.remaining_accounts,
         instruction_data,
         in_amount,
-        SwapMessageV1 {
+        SwapMessageV2 {
             recipient,
             redeem_mode: Default::default(), // RedeemMode is not handled in this method.
             output_token,
+            fallback_recipient,
+            integrator_fee,
+            split_outputs,
         },
         Default::default(),
         Default::default(),
     )
 }
+
+/// Pay the relaying fee to the fee recipient and drop off gas to the recipient. Returns the amount
+/// of USDC left to swap.
+fn pay_relayer(
+    ctx: &Context<CompleteSwapPayload>,
+    fill_amount: u64,
+    gas_dropoff: u64,
+    relaying_fee: u64,
+) -> Result<u64> {
+    let in_amount = fill_amount
+        .checked_sub(relaying_fee)
+        .ok_or_else(|| SwapLayerError::InvalidRelayerFee)?;
+
+    if gas_dropoff > 0 {
+        let recipient = ctx
+            .accounts
+            .recipient
+            .as_ref()
+            .ok_or_else(|| SwapLayerError::RecipientRequired)?;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: recipient.to_account_info(),
+                },
+            ),
+            gas_dropoff,
+        )?;
+    }
+
+    if relaying_fee > 0 {
+        let staged_inbound = &ctx.accounts.staged_inbound;
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.src_swap_token.to_account_info(),
+                    to: ctx.accounts.fee_recipient_token.to_account_info(),
+                    authority: staged_inbound.to_account_info(),
+                },
+                &[&[
+                    StagedInbound::SEED_PREFIX,
+                    staged_inbound.seeds.prepared_fill.as_ref(),
+                    &[staged_inbound.seeds.bump],
+                ]],
+            ),
+            relaying_fee,
+        )?;
+    }
+
+    Ok(in_amount)
+}
@@ -2,7 +2,7 @@ use crate::utils::gas_dropoff;
 use crate::{composite::*, error::SwapLayerError};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token;
-use swap_layer_messages::{messages::SwapMessageV1, types::RedeemMode};
+use swap_layer_messages::{messages::SwapMessageV2, types::RedeemMode};
 
 #[derive(Accounts)]
 pub struct CompleteSwapRelay<'info> {
@@ -58,7 +58,7 @@ where
 pub fn handle_complete_swap_relay<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, CompleteSwapRelay<'info>>,
     instruction_data: Vec<u8>,
-    swap_msg: SwapMessageV1,
+    swap_msg: SwapMessageV2,
     gas_dropoff: u64,
     relaying_fee: u64,
 ) -> Result<()>
@@ -67,6 +67,12 @@ where
 {
     // Consume prepared fill.
     let fill_amount = ctx.accounts.complete_swap.consume_prepared_fill()?;
+
+    // The integrator is paid before the relaying fee is deducted.
+    let fill_amount = ctx
+        .accounts
+        .complete_swap
+        .pay_integrator_fee(fill_amount, ctx.bumps.complete_swap.authority)?;
     let payer = &ctx.accounts.complete_swap.payer;
     let recipient = &ctx.accounts.recipient;
 
@@ -1,28 +1,31 @@
 use crate::{composite::*, error::SwapLayerError};
 use anchor_lang::prelude::*;
 use anchor_spl::token;
-use swap_layer_messages::types::{OutputToken, RedeemMode};
+use swap_layer_messages::raw::{OutputTokenRef, RedeemModeRef};
 
 #[derive(Accounts)]
 pub struct CompleteTransferDirect<'info> {
     /// This redeemer is used to check against the recipient. If the redeemer is the same as the
     /// recipient, he is free to redeem his tokens directly as USDC even if swap instructions are
     /// encoded.
+    ///
+    /// If an integrator fee is owed, the redeemer must own the recipient token account because the
+    /// fee is paid out of it.
     redeemer: Signer<'info>,
 
     #[account(
         constraint = {
-            let swap_msg = consume_swap_layer_fill.read_message_unchecked();
+            let swap_msg = consume_swap_layer_fill.read_message_ref_unchecked();
 
             require_keys_eq!(
                 recipient.key(),
-                Pubkey::from(swap_msg.recipient),
+                Pubkey::from(*swap_msg.recipient()),
                 SwapLayerError::InvalidRecipient
             );
 
-            match &swap_msg.output_token {
-                OutputToken::Usdc => {}
-                OutputToken::Gas(_) | OutputToken::Other { .. } => {
+            match swap_msg.output_token() {
+                OutputTokenRef::Usdc => {}
+                OutputTokenRef::Gas(_) | OutputTokenRef::Other { .. } => {
                     require_eq!(
                         redeemer.key(),
                         recipient.key(),
@@ -44,6 +47,9 @@ pub struct CompleteTransferDirect<'info> {
     /// Recipient associated token account. The recipient authority check
     /// is necessary to ensure that the recipient is the intended recipient
     /// of the bridged tokens. Mutable.
+    ///
+    /// Only the recipient may redeem USDC instead of an encoded swap, so the
+    /// fallback recipient never applies to direct transfers.
     recipient_token_account: Box<Account<'info, token::TokenAccount>>,
 
     /// CHECK: This account must be the owner of the recipient token account. The
@@ -58,17 +64,43 @@ pub fn complete_transfer_direct(ctx: Context<CompleteTransferDirect>) -> Result<
     match ctx
         .accounts
         .consume_swap_layer_fill
-        .read_message_unchecked()
-        .redeem_mode
+        .read_message_ref_unchecked()
+        .redeem_mode()
     {
-        RedeemMode::Direct => ctx
-            .accounts
-            .consume_swap_layer_fill
-            .consume_prepared_fill(
-                ctx.accounts.recipient_token_account.as_ref().as_ref(),
-                &ctx.accounts.token_program,
-            )
-            .map(|_| ()),
+        RedeemModeRef::Direct => handle_complete_transfer_direct(ctx),
         _ => err!(SwapLayerError::InvalidRedeemMode),
     }
 }
+
+fn handle_complete_transfer_direct(ctx: Context<CompleteTransferDirect>) -> Result<()> {
+    let consume_swap_layer_fill = &ctx.accounts.consume_swap_layer_fill;
+    let recipient_token = &ctx.accounts.recipient_token_account;
+
+    // The recipient token account may already hold USDC, so only the balance change is filled.
+    let fill_amount = consume_swap_layer_fill
+        .consume_prepared_fill(
+            recipient_token.as_ref().as_ref(),
+            &ctx.accounts.token_program,
+        )?
+        .saturating_sub(recipient_token.amount);
+
+    // The integrator fee is paid out of the recipient token account, so its owner must redeem the
+    // fill if an integrator fee is owed.
+    if consume_swap_layer_fill.integrator_fee(fill_amount) != 0 {
+        require_keys_eq!(
+            ctx.accounts.redeemer.key(),
+            recipient_token.owner,
+            SwapLayerError::InvalidRedeemer
+        );
+    }
+
+    consume_swap_layer_fill
+        .pay_integrator_fee(
+            fill_amount,
+            recipient_token.as_ref().as_ref(),
+            &ctx.accounts.redeemer,
+            &ctx.accounts.token_program,
+            &[],
+        )
+        .map(|_| ())
+}
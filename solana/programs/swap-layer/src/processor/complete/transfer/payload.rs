@@ -2,10 +2,11 @@ use crate::{
     composite::*,
     error::SwapLayerError,
     state::{StagedInbound, StagedInboundInfo, StagedInboundSeeds},
+    utils,
 };
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, system_program};
 use anchor_spl::token;
-use swap_layer_messages::types::{OutputToken, RedeemMode};
+use swap_layer_messages::raw::{OutputTokenRef, RedeemModeRef};
 
 #[derive(Accounts)]
 pub struct CompleteTransferPayload<'info> {
@@ -19,14 +20,14 @@ pub struct CompleteTransferPayload<'info> {
 
     #[account(
         constraint = {
-            let swap_msg = consume_swap_layer_fill.read_message_unchecked();
+            let swap_msg = consume_swap_layer_fill.read_message_ref_unchecked();
 
-            match &swap_msg.output_token {
-                OutputToken::Usdc => {}
-                OutputToken::Gas(_) | OutputToken::Other { .. } => {
+            match swap_msg.output_token() {
+                OutputTokenRef::Usdc => {}
+                OutputTokenRef::Gas(_) | OutputTokenRef::Other { .. } => {
                     require_eq!(
                         redeemer.key(),
-                        Pubkey::from(swap_msg.recipient),
+                        Pubkey::from(*swap_msg.recipient()),
                         SwapLayerError::InvalidRedeemer
                     );
                 }
@@ -42,7 +43,7 @@ pub struct CompleteTransferPayload<'info> {
         payer = payer,
         space = StagedInbound::try_compute_size_if_needed(
             staged_inbound,
-            consume_swap_layer_fill.read_message_unchecked()
+            consume_swap_layer_fill.read_message_ref_unchecked().redeem_mode()
         )?,
         seeds = [
             StagedInbound::SEED_PREFIX,
@@ -71,6 +72,27 @@ pub struct CompleteTransferPayload<'info> {
     /// inbound account.
     staged_custody_token: Box<Account<'info, token::TokenAccount>>,
 
+    /// CHECK: Recipient of the gas dropoff. This account is only required if a relayer redeems a
+    /// fill with [RelayWithPayload](swap_layer_messages::types::RedeemMode::RelayWithPayload) and
+    /// must be the encoded recipient. If the recipient signs, it is redeeming the fill itself (self
+    /// redemption) and no relaying fee is paid.
+    #[account(
+        mut,
+        address = Pubkey::from(
+            *consume_swap_layer_fill.read_message_ref_unchecked().recipient()
+        ) @ SwapLayerError::InvalidRecipient,
+    )]
+    recipient: Option<UncheckedAccount<'info>>,
+
+    /// Token account receiving the relaying fee. This account is only required if a relayer
+    /// redeems a fill with
+    /// [RelayWithPayload](swap_layer_messages::types::RedeemMode::RelayWithPayload).
+    #[account(
+        mut,
+        address = consume_swap_layer_fill.custodian.fee_recipient_token,
+    )]
+    fee_recipient_token: Option<Box<Account<'info, token::TokenAccount>>>,
+
     usdc: Usdc<'info>,
 
     token_program: Program<'info, token::Token>,
@@ -81,7 +103,7 @@ pub fn complete_transfer_payload(ctx: Context<CompleteTransferPayload>) -> Resul
     let staged_inbound = &mut ctx.accounts.staged_inbound;
 
     // Consume the prepared fill, and send the tokens to the staged custody account.
-    ctx.accounts.consume_swap_layer_fill.consume_prepared_fill(
+    let fill_amount = ctx.accounts.consume_swap_layer_fill.consume_prepared_fill(
         ctx.accounts.staged_custody_token.as_ref().as_ref(),
         &ctx.accounts.token_program,
     )?;
@@ -89,29 +111,125 @@ pub fn complete_transfer_payload(ctx: Context<CompleteTransferPayload>) -> Resul
     let swap_msg = ctx
         .accounts
         .consume_swap_layer_fill
-        .read_message_unchecked();
-
-    match swap_msg.redeem_mode {
-        RedeemMode::Payload { sender, buf } => {
-            staged_inbound.set_inner(StagedInbound {
-                seeds: StagedInboundSeeds {
-                    prepared_fill: ctx.accounts.consume_swap_layer_fill.prepared_fill_key(),
-                    bump: ctx.bumps.staged_inbound,
-                },
-                info: StagedInboundInfo {
-                    custody_token: ctx.accounts.staged_custody_token.key(),
-                    staged_by: ctx.accounts.payer.key(),
-                    source_chain: ctx.accounts.consume_swap_layer_fill.fill.source_chain,
-                    sender,
-                    recipient: Pubkey::from(swap_msg.recipient),
-                    is_native: false,
+        .read_message_ref_unchecked();
+    let recipient = Pubkey::from(*swap_msg.recipient());
+
+    let (sender, buf, relay) = match swap_msg.redeem_mode() {
+        RedeemModeRef::Payload { sender, buf } => (sender, buf, None),
+        RedeemModeRef::RelayWithPayload {
+            gas_dropoff,
+            relaying_fee,
+            sender,
+            buf,
+        } => (sender, buf, Some((gas_dropoff, relaying_fee))),
+        _ => return err!(SwapLayerError::InvalidRedeemMode),
+    };
+
+    staged_inbound.set_inner(StagedInbound {
+        seeds: StagedInboundSeeds {
+            prepared_fill: ctx.accounts.consume_swap_layer_fill.prepared_fill_key(),
+            bump: ctx.bumps.staged_inbound,
+        },
+        info: StagedInboundInfo {
+            custody_token: ctx.accounts.staged_custody_token.key(),
+            staged_by: ctx.accounts.payer.key(),
+            source_chain: ctx.accounts.consume_swap_layer_fill.fill.source_chain,
+            sender: *sender,
+            recipient,
+            is_native: false,
+        },
+        recipient_payload: buf.to_vec(),
+    });
+
+    // The integrator is paid before the relaying fee is deducted.
+    let staged_inbound = &ctx.accounts.staged_inbound;
+    let fill_amount = ctx.accounts.consume_swap_layer_fill.pay_integrator_fee(
+        fill_amount,
+        ctx.accounts.staged_custody_token.as_ref().as_ref(),
+        staged_inbound.as_ref(),
+        &ctx.accounts.token_program,
+        &[&[
+            StagedInbound::SEED_PREFIX,
+            staged_inbound.seeds.prepared_fill.as_ref(),
+            &[staged_inbound.seeds.bump],
+        ]],
+    )?;
+
+    // If a relayer is redeeming on behalf of the recipient, the relayer is paid from the staged
+    // USDC and drops off gas to the recipient. Override the relaying fee to zero if the recipient
+    // signs (self redemption).
+    let is_self_redemption = ctx
+        .accounts
+        .recipient
+        .as_ref()
+        .is_some_and(|recipient| recipient.is_signer);
+    match relay {
+        Some((gas_dropoff, relaying_fee)) if !is_self_redemption => handle_relay_with_payload(
+            ctx,
+            fill_amount,
+            utils::gas_dropoff::denormalize_gas_dropoff(gas_dropoff),
+            relaying_fee.into(),
+        ),
+        _ => Ok(()),
+    }
+}
+
+fn handle_relay_with_payload(
+    ctx: Context<CompleteTransferPayload>,
+    fill_amount: u64,
+    gas_dropoff: u64,
+    relaying_fee: u64,
+) -> Result<()> {
+    require!(
+        relaying_fee <= fill_amount,
+        SwapLayerError::InvalidRelayerFee
+    );
+
+    if gas_dropoff > 0 {
+        let recipient = ctx
+            .accounts
+            .recipient
+            .as_ref()
+            .ok_or_else(|| SwapLayerError::RecipientRequired)?;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: recipient.to_account_info(),
                 },
-                recipient_payload: buf.into(),
-            });
+            ),
+            gas_dropoff,
+        )?;
+    }
 
-            // Done.
-            Ok(())
-        }
-        _ => err!(SwapLayerError::InvalidRedeemMode),
+    if relaying_fee > 0 {
+        let fee_recipient_token = ctx
+            .accounts
+            .fee_recipient_token
+            .as_ref()
+            .ok_or_else(|| SwapLayerError::FeeRecipientTokenRequired)?;
+
+        let staged_inbound = &ctx.accounts.staged_inbound;
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.staged_custody_token.to_account_info(),
+                    to: fee_recipient_token.to_account_info(),
+                    authority: staged_inbound.to_account_info(),
+                },
+                &[&[
+                    StagedInbound::SEED_PREFIX,
+                    staged_inbound.seeds.prepared_fill.as_ref(),
+                    &[staged_inbound.seeds.bump],
+                ]],
+            ),
+            relaying_fee,
+        )?;
     }
+
+    // Done.
+    Ok(())
 }
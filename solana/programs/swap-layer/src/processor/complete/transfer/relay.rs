@@ -5,8 +5,8 @@ use crate::{
     utils::{self},
 };
 use anchor_lang::{prelude::*, system_program};
-use anchor_spl::token;
-use swap_layer_messages::types::{OutputToken, RedeemMode};
+use anchor_spl::{associated_token, token};
+use swap_layer_messages::raw::{OutputTokenRef, RedeemModeRef};
 use token_router::state::FillType;
 
 #[derive(Accounts)]
@@ -22,19 +22,19 @@ pub struct CompleteTransferRelay<'info> {
 
     #[account(
         constraint = {
-            let swap_msg = consume_swap_layer_fill.read_message_unchecked();
+            let swap_msg = consume_swap_layer_fill.read_message_ref_unchecked();
 
             require_keys_eq!(
                 recipient.key(),
-                Pubkey::from(swap_msg.recipient),
+                Pubkey::from(*swap_msg.recipient()),
                 SwapLayerError::InvalidRecipient
             );
 
             // Ensure that the swap time limit has been exceeded if the
             // relayer is attempting to redeem an output token that is not USDC.
-            match swap_msg.output_token {
-                OutputToken::Usdc => {}
-                OutputToken::Gas(_) | OutputToken::Other { .. } => {
+            match swap_msg.output_token() {
+                OutputTokenRef::Usdc => {}
+                OutputTokenRef::Gas(_) | OutputTokenRef::Other { .. } => {
                     // If the redeemer is not the recipient, handle these output tokens very
                     // carefully by checking the time limits.
                     if redeemer.key() != recipient.key() {
@@ -86,12 +86,18 @@ pub struct CompleteTransferRelay<'info> {
 
     #[account(
         mut,
-        associated_token::mint = usdc,
-        associated_token::authority = recipient
+        address = associated_token::get_associated_token_address(
+            &consume_swap_layer_fill.usdc_recipient(&redeemer.key()),
+            &common::USDC_MINT,
+        )
     )]
     /// Recipient associated token account. The recipient authority check
     /// is necessary to ensure that the recipient is the intended recipient
     /// of the bridged tokens.
+    ///
+    /// If a relayer redeems USDC instead of an encoded swap and the message
+    /// specifies a fallback recipient, this must be the fallback recipient's
+    /// associated token account instead.
     recipient_token_account: Box<Account<'info, token::TokenAccount>>,
 
     /// CHECK: Recipient may differ from redeemer if a relayer paid for this
@@ -117,10 +123,10 @@ pub fn complete_transfer_relay(ctx: Context<CompleteTransferRelay>) -> Result<()
     match ctx
         .accounts
         .consume_swap_layer_fill
-        .read_message_unchecked()
-        .redeem_mode
+        .read_message_ref_unchecked()
+        .redeem_mode()
     {
-        RedeemMode::Relay {
+        RedeemModeRef::Relay {
             gas_dropoff,
             relaying_fee,
         } => handle_complete_transfer_relay(
@@ -147,6 +153,15 @@ fn handle_complete_transfer_relay(
         .consume_prepared_fill(complete_token.as_ref(), token_program)?;
 
     let custodian = &ctx.accounts.consume_swap_layer_fill.custodian;
+
+    // The integrator is paid before the relaying fee is deducted.
+    let fill_amount = ctx.accounts.consume_swap_layer_fill.pay_integrator_fee(
+        fill_amount,
+        complete_token.as_ref(),
+        custodian,
+        token_program,
+        &[Custodian::SIGNER_SEEDS],
+    )?;
     let payer = &ctx.accounts.payer;
     let recipient = &ctx.accounts.recipient;
 
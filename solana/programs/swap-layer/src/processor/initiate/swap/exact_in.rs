@@ -2,6 +2,7 @@ use crate::{
     composite::*,
     error::SwapLayerError,
     state::{Custodian, Peer, StagedOutbound},
+    utils::orca_whirlpool,
     PREPARED_ORDER_SEED_PREFIX,
 };
 use anchor_lang::prelude::*;
@@ -29,7 +30,8 @@ pub struct InitiateSwapExactIn<'info> {
     #[account(
         mut,
         constraint = {
-            require!(staged_outbound.info.is_exact_in, SwapLayerError::ExactInRequired);
+            // Exact out swaps must be initiated with initiate_swap_exact_out.
+            require!(staged_outbound.info.is_exact_in, SwapLayerError::InvalidSwapType);
 
             // Cannot send to zero address.
             //
@@ -166,44 +168,11 @@ where
         src_mint.decimals,
     )?;
 
-    let (shared_accounts_route, swap_args, _) =
-        JupiterV6SharedAccountsRoute::set_up(ctx.remaining_accounts, &instruction_data[..])?;
-
-    let swap_authority = &ctx.accounts.swap_authority;
-
-    // Verify remaining accounts.
-    {
-        require_keys_eq!(
-            shared_accounts_route.transfer_authority.key(),
-            swap_authority.key(),
-            SwapLayerError::InvalidSwapAuthority
-        );
-        require_keys_eq!(
-            shared_accounts_route.src_custody_token.key(),
-            ctx.accounts.src_swap_token.key(),
-            SwapLayerError::InvalidSourceSwapToken
-        );
-        require_keys_eq!(
-            shared_accounts_route.dst_custody_token.key(),
-            ctx.accounts.dst_swap_token.key(),
-            SwapLayerError::InvalidDestinationSwapToken
-        );
-        require_keys_eq!(
-            shared_accounts_route.src_mint.key(),
-            ctx.accounts.src_mint.key(),
-            SwapLayerError::InvalidSourceMint
-        );
-        require_keys_eq!(
-            shared_accounts_route.dst_mint.key(),
-            common::USDC_MINT,
-            SwapLayerError::InvalidDestinationMint
-        );
-    }
-
-    let swap_msg = ctx.accounts.staged_outbound.to_swap_message_v1()?;
+    let swap_msg = ctx.accounts.staged_outbound.to_swap_message()?;
 
     let staged_outbound = &ctx.accounts.staged_outbound;
     let prepared_order = &ctx.accounts.prepared_order;
+    let swap_authority = &ctx.accounts.swap_authority;
 
     let prepared_order_key = prepared_order.key();
     let swap_authority_seeds = &[
@@ -213,12 +182,48 @@ where
     ];
 
     // Execute swap. Keep in mind that exact in is not really exact in... so there may be residual.
-    let (usdc_amount_out, src_dust) = shared_accounts_route.swap_exact_in(
-        swap_args,
-        swap_authority_seeds,
-        ctx.remaining_accounts,
-        Default::default(),
-    )?;
+    let (usdc_amount_out, src_dust) = if orca_whirlpool::is_swap_instruction(&instruction_data) {
+        // The instruction data only determines the whirlpool route. The whole staged amount is
+        // swapped with the staged minimum amount out.
+        let whirlpool_route = OrcaWhirlpoolRoute::set_up(
+            ctx.remaining_accounts,
+            &orca_whirlpool::swap_directions(&instruction_data)?,
+        )?;
+
+        // Verify remaining accounts. The source and destination swap token accounts determine the
+        // source mint and USDC.
+        whirlpool_route.verify_swap_accounts(
+            swap_authority.key(),
+            ctx.accounts.src_swap_token.key(),
+            ctx.accounts.dst_swap_token.key(),
+        )?;
+
+        whirlpool_route.swap_exact_in(
+            custody_token.amount,
+            staged_outbound.info.min_amount_out.unwrap_or_default(),
+            swap_authority_seeds,
+            ctx.remaining_accounts,
+        )?
+    } else {
+        let (shared_accounts_route, swap_args, _) =
+            JupiterV6SharedAccountsRoute::set_up(ctx.remaining_accounts, &instruction_data[..])?;
+
+        // Verify remaining accounts.
+        shared_accounts_route.verify_swap_accounts(
+            swap_authority.key(),
+            ctx.accounts.src_swap_token.key(),
+            ctx.accounts.dst_swap_token.key(),
+            ctx.accounts.src_mint.key(),
+            common::USDC_MINT,
+        )?;
+
+        shared_accounts_route.swap_exact_in(
+            swap_args,
+            swap_authority_seeds,
+            ctx.remaining_accounts,
+            Default::default(),
+        )?
+    };
 
     // The `min_amount_out` should always be Some when swapping into USDC, this
     // is guaranteed by the stage_outbound instruction.
@@ -0,0 +1,386 @@
+use crate::{
+    composite::*,
+    error::SwapLayerError,
+    state::{Custodian, Peer, StagedOutbound},
+    utils::jupiter_v6::cpi::SharedAccountsExactOutRouteArgs,
+    PREPARED_ORDER_SEED_PREFIX,
+};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{token, token_interface};
+use common::wormhole_io::TypePrefixedPayload;
+
+#[derive(Accounts)]
+pub struct InitiateSwapExactOut<'info> {
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    custodian: CheckedCustodian<'info>,
+
+    /// CHECK: This account must be the one who paid to create the staged outbound account.
+    #[account(
+        mut,
+        address = staged_outbound.info.prepared_by,
+    )]
+    prepared_by: UncheckedAccount<'info>,
+
+    /// CHECK: This account must be the sender encoded in the staged outbound account. If the source
+    /// mint is the native mint and no sender token account is provided, unspent lamports will be
+    /// refunded to this account.
+    #[account(
+        mut,
+        address = staged_outbound.info.sender,
+    )]
+    sender: UncheckedAccount<'info>,
+
+    /// If provided, unspent source tokens will be refunded to this token account. This account
+    /// is required if the source mint is not the native mint.
+    #[account(
+        mut,
+        token::mint = src_mint,
+        token::authority = sender,
+        token::token_program = src_token_program,
+    )]
+    sender_token: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// Staging for outbound transfer. This account has all of the instructions needed to initiate
+    /// the transfer.
+    ///
+    /// This account will be closed by the end of the instruction.
+    #[account(
+        mut,
+        close = prepared_by,
+        constraint = {
+            require!(!staged_outbound.info.is_exact_in, SwapLayerError::ExactOutRequired);
+
+            // Cannot send to zero address.
+            require!(staged_outbound.info.recipient != [0; 32], SwapLayerError::InvalidRecipient);
+
+            true
+        }
+    )]
+    staged_outbound: Box<Account<'info, StagedOutbound>>,
+
+    /// This custody token account will be closed by the end of the instruction.
+    #[account(
+        mut,
+        token::mint = src_mint,
+        token::authority = target_peer,
+        token::token_program = src_token_program,
+        seeds = [
+            crate::STAGED_CUSTODY_TOKEN_SEED_PREFIX,
+            staged_outbound.key().as_ref(),
+        ],
+        bump = staged_outbound.info.custody_token_bump,
+    )]
+    staged_custody_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// CHECK: This account must equal the usdc refund token encoded in the staged outbound account.
+    #[account(address = staged_outbound.usdc_refund_token)]
+    usdc_refund_token: UncheckedAccount<'info>,
+
+    /// Peer used to determine whether assets are sent to a valid destination.
+    target_peer: RegisteredPeer<'info>,
+
+    /// CHECK: Mutable, seeds must be \["prepared-order", staged_outbound.key()\]
+    #[account(
+        mut,
+        seeds = [
+            PREPARED_ORDER_SEED_PREFIX,
+            staged_outbound.key().as_ref(),
+        ],
+        bump,
+    )]
+    prepared_order: UncheckedAccount<'info>,
+
+    /// CHECK: Seeds must be \["swap-authority", prepared_order.key()\].
+    #[account(
+        seeds = [
+            crate::SWAP_AUTHORITY_SEED_PREFIX,
+            prepared_order.key().as_ref(),
+        ],
+        bump,
+    )]
+    swap_authority: UncheckedAccount<'info>,
+
+    /// Temporary swap token account to receive source mint from the staged custody token. This
+    /// account will be closed at the end of this instruction.
+    ///
+    /// NOTE: This ATA must already be created.
+    #[account(
+        mut,
+        associated_token::mint = src_mint,
+        associated_token::authority = swap_authority,
+        associated_token::token_program = src_token_program
+    )]
+    src_swap_token: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// Temporary swap token account to receive destination mint after the swap. This account will
+    /// be closed at the end of this instruction.
+    ///
+    /// NOTE: This ATA must already be created.
+    #[account(
+        mut,
+        associated_token::mint = usdc,
+        associated_token::authority = swap_authority
+    )]
+    dst_swap_token: Box<Account<'info, token::TokenAccount>>,
+
+    /// This account must be verified as the source mint for the swap.
+    src_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// This account must be verified as the destination mint for the swap.
+    #[account(constraint = src_mint.key() != usdc.key() @ SwapLayerError::SameMint)]
+    usdc: Usdc<'info>,
+
+    /// CHECK: Seeds must be \["emitter"] (Token Router Program).
+    token_router_custodian: UncheckedAccount<'info>,
+
+    /// CHECK: Seeds must be \["endpoint"\, target_chain.to_be_bytes()] (Matching Engine Program).
+    target_router_endpoint: UncheckedAccount<'info>,
+
+    /// CHECK: Mutable. This account is verified by the Token Router Program.
+    #[account(mut)]
+    prepared_custody_token: UncheckedAccount<'info>,
+
+    token_router_program: Program<'info, token_router::program::TokenRouter>,
+    src_token_program: Interface<'info, token_interface::TokenInterface>,
+    token_program: Program<'info, token::Token>,
+    system_program: Program<'info, System>,
+}
+
+pub fn initiate_swap_exact_out<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, InitiateSwapExactOut<'info>>,
+    instruction_data: Vec<u8>,
+) -> Result<()>
+where
+    'c: 'info,
+{
+    let src_token_program = &ctx.accounts.src_token_program;
+    let custody_token = &ctx.accounts.staged_custody_token;
+
+    let peer = &ctx.accounts.target_peer;
+    let peer_signer_seeds = &[
+        Peer::SEED_PREFIX,
+        &peer.seeds.chain.to_be_bytes(),
+        &[peer.seeds.bump],
+    ];
+
+    let src_mint = &ctx.accounts.src_mint;
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            src_token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: custody_token.to_account_info(),
+                to: ctx.accounts.src_swap_token.to_account_info(),
+                authority: peer.to_account_info(),
+                mint: src_mint.to_account_info(),
+            },
+            &[peer_signer_seeds],
+        ),
+        custody_token.amount,
+        src_mint.decimals,
+    )?;
+
+    let (shared_accounts_route, mut swap_args, _) =
+        JupiterV6SharedAccountsRoute::set_up::<SharedAccountsExactOutRouteArgs>(
+            ctx.remaining_accounts,
+            &instruction_data[..],
+        )?;
+
+    let swap_authority = &ctx.accounts.swap_authority;
+
+    // Verify remaining accounts.
+    shared_accounts_route.verify_swap_accounts(
+        swap_authority.key(),
+        ctx.accounts.src_swap_token.key(),
+        ctx.accounts.dst_swap_token.key(),
+        ctx.accounts.src_mint.key(),
+        common::USDC_MINT,
+    )?;
+
+    let swap_msg = ctx.accounts.staged_outbound.to_swap_message()?;
+
+    let staged_outbound = &ctx.accounts.staged_outbound;
+    let prepared_order = &ctx.accounts.prepared_order;
+
+    // The stage_outbound instruction requires `min_amount_out` when swapping into USDC. For exact
+    // out swaps, this amount already includes the relaying fee.
+    let usdc_amount_out = staged_outbound
+        .info
+        .min_amount_out
+        .ok_or(SwapLayerError::MinAmountOutRequired)?;
+
+    msg!(
+        "Override out_amount: {}, quoted_in_amount: {}, slippage_bps: {}",
+        swap_args.out_amount,
+        swap_args.quoted_in_amount,
+        swap_args.slippage_bps
+    );
+    swap_args.out_amount = usdc_amount_out;
+
+    let prepared_order_key = prepared_order.key();
+    let swap_authority_seeds = &[
+        crate::SWAP_AUTHORITY_SEED_PREFIX,
+        prepared_order_key.as_ref(),
+        &[ctx.bumps.swap_authority],
+    ];
+
+    // Execute swap. Whatever source tokens were not used for the swap will be refunded to the
+    // sender.
+    let (usdc_amount_out, src_residual) = shared_accounts_route.swap_exact_out(
+        swap_args,
+        swap_authority_seeds,
+        ctx.remaining_accounts,
+    )?;
+
+    let payer = &ctx.accounts.payer;
+    let src_swap_token = &ctx.accounts.src_swap_token;
+
+    let token_program = &ctx.accounts.token_program;
+    let dst_swap_token = &ctx.accounts.dst_swap_token;
+    let custodian = &ctx.accounts.custodian;
+
+    token::approve(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            token::Approve {
+                to: dst_swap_token.to_account_info(),
+                delegate: custodian.to_account_info(),
+                authority: swap_authority.to_account_info(),
+            },
+            &[swap_authority_seeds],
+        ),
+        usdc_amount_out,
+    )?;
+
+    // Prepare market order as custodian.
+    token_router::cpi::prepare_market_order(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_router_program.to_account_info(),
+            token_router::cpi::accounts::PrepareMarketOrder {
+                payer: payer.to_account_info(),
+                custodian: token_router::cpi::accounts::CheckedCustodian {
+                    custodian: ctx.accounts.token_router_custodian.to_account_info(),
+                },
+                program_transfer_authority: Default::default(),
+                sender: custodian.to_account_info().into(),
+                prepared_order: ctx.accounts.prepared_order.to_account_info(),
+                sender_token: dst_swap_token.to_account_info(),
+                refund_token: ctx.accounts.usdc_refund_token.to_account_info(),
+                prepared_custody_token: ctx.accounts.prepared_custody_token.to_account_info(),
+                usdc: token_router::cpi::accounts::Usdc {
+                    mint: ctx.accounts.usdc.to_account_info(),
+                },
+                target_router_endpoint: token_router::cpi::accounts::RegisteredEndpoint {
+                    endpoint: ctx.accounts.target_router_endpoint.to_account_info(),
+                },
+                token_program: token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            &[
+                Custodian::SIGNER_SEEDS,
+                &[
+                    PREPARED_ORDER_SEED_PREFIX,
+                    staged_outbound.key().as_ref(),
+                    &[ctx.bumps.prepared_order],
+                ],
+            ],
+        ),
+        token_router::PrepareMarketOrderArgs {
+            amount_in: usdc_amount_out,
+            min_amount_out: Default::default(),
+            target_chain: staged_outbound.target_chain,
+            redeemer: ctx.accounts.target_peer.address,
+            redeemer_message: swap_msg.to_vec(),
+        },
+    )?;
+
+    //  Close the destination swap token account.
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token::CloseAccount {
+            account: dst_swap_token.to_account_info(),
+            destination: payer.to_account_info(),
+            authority: swap_authority.to_account_info(),
+        },
+        &[swap_authority_seeds],
+    ))?;
+
+    // Refund the unspent source tokens to the sender.
+    let native_residual = match (&ctx.accounts.sender_token, src_residual) {
+        (_, 0) => Default::default(),
+        (Some(sender_token), _) => {
+            msg!("Refund: {}", src_residual);
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    src_token_program.to_account_info(),
+                    token_interface::TransferChecked {
+                        from: src_swap_token.to_account_info(),
+                        to: sender_token.to_account_info(),
+                        authority: swap_authority.to_account_info(),
+                        mint: src_mint.to_account_info(),
+                    },
+                    &[swap_authority_seeds],
+                ),
+                src_residual,
+                src_mint.decimals,
+            )?;
+
+            Default::default()
+        }
+        (None, _) => {
+            // Without a sender token account, we can only refund lamports.
+            require_keys_eq!(
+                src_mint.key(),
+                token::spl_token::native_mint::ID,
+                SwapLayerError::SenderTokenRequired,
+            );
+
+            src_residual
+        }
+    };
+
+    // Close the source swap token account. If the source mint is the native mint and there is no
+    // sender token account, the residual lamports are sent to the payer first.
+    token_interface::close_account(CpiContext::new_with_signer(
+        src_token_program.to_account_info(),
+        token_interface::CloseAccount {
+            account: src_swap_token.to_account_info(),
+            destination: payer.to_account_info(),
+            authority: swap_authority.to_account_info(),
+        },
+        &[swap_authority_seeds],
+    ))?;
+
+    // Then transfer the residual lamports to the sender.
+    if native_residual > 0 {
+        msg!("Refund: {}", native_residual);
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: ctx.accounts.sender.to_account_info(),
+                },
+            ),
+            native_residual,
+        )?;
+    }
+
+    // Close the custody token account. The staged outbound account will be closed via the account
+    // context.
+    token_interface::close_account(CpiContext::new_with_signer(
+        src_token_program.to_account_info(),
+        token_interface::CloseAccount {
+            account: custody_token.to_account_info(),
+            destination: ctx.accounts.prepared_by.to_account_info(),
+            authority: peer.to_account_info(),
+        },
+        &[peer_signer_seeds],
+    ))?;
+
+    // Done.
+    Ok(())
+}
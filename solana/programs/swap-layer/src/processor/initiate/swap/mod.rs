@@ -1,2 +1,5 @@
 mod exact_in;
 pub use exact_in::*;
+
+mod exact_out;
+pub use exact_out::*;
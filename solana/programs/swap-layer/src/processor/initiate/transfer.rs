@@ -93,7 +93,7 @@ pub fn initiate_transfer(ctx: Context<InitiateTransfer>) -> Result<()> {
     let redeemer_message = ctx
         .accounts
         .staged_outbound
-        .to_swap_message_v1()
+        .to_swap_message()
         .map(|msg| msg.to_vec())?;
 
     let staged_outbound = &ctx.accounts.staged_outbound;
@@ -6,9 +6,8 @@ use crate::{
 };
 use anchor_lang::{prelude::*, system_program};
 use anchor_spl::{token, token_interface};
-use common::wormhole_io::{Readable, Writeable};
 use solana_program::keccak;
-use swap_layer_messages::types::OutputToken;
+use swap_layer_messages::types::{validate_recipient_for_target, OutputToken};
 
 #[derive(Accounts)]
 #[instruction(args: StageOutboundArgs)]
@@ -66,7 +65,7 @@ pub struct StageOutbound<'info> {
     #[account(
         init,
         payer = payer,
-        space = StagedOutbound::try_compute_size(&args.redeem_option, &args.encoded_output_token)?,
+        space = StagedOutbound::try_compute_size(&args.redeem_option, &args.output_token)?,
         constraint = {
             // Disallow amount in of zero.
             require!(args.amount_in != 0, SwapLayerError::ZeroAmountIn);
@@ -74,6 +73,15 @@ pub struct StageOutbound<'info> {
             // Cannot send to zero address.
             require!(args.recipient != [0; 32], SwapLayerError::InvalidRecipient);
 
+            // Reject anything the target network can never execute before any funds are bridged.
+            validate_recipient_for_target(&args.recipient, args.target_chain)
+                .map_err(|_| SwapLayerError::InvalidRecipient)?;
+            if let Some(output_token) = &args.output_token {
+                output_token
+                    .validate_for_target(args.target_chain)
+                    .map_err(|_| SwapLayerError::InvalidOutputToken)?;
+            }
+
             // Min amount out must be specified for swaps into USDC.
             require!(
                 args.min_amount_out.is_some() || src_mint.key() == common::USDC_MINT,
@@ -137,11 +145,14 @@ pub struct StageOutboundArgs {
     // Must be specified for swaps into USDC.
     pub min_amount_out: Option<u64>,
 
-    /// This argument only applies to relays. If exact in is specified, the relaying fee will be
-    /// removed from the amount in. Otherwise it will be added to the amount in to guarantee the
-    /// USDC amount specified above.
+    /// For USDC transfers, this argument only applies to relays. If exact in is specified, the
+    /// relaying fee will be removed from the amount in. Otherwise it will be added to the amount in
+    /// to guarantee the USDC amount specified above.
     ///
-    /// For swaps, this argument will determine whether which initiate swap instruction to use.
+    /// For swaps, this argument will determine which initiate swap instruction to use. If exact out
+    /// is specified, the amount in is the maximum amount of source tokens to swap and the min
+    /// amount out is the exact amount of USDC the swap must produce (the relaying fee will be added
+    /// to this amount). Unspent source tokens are refunded to the sender.
     pub is_exact_in: bool,
 
     /// The Wormhole chain ID of the network to transfer tokens to.
@@ -152,7 +163,9 @@ pub struct StageOutboundArgs {
 
     pub redeem_option: Option<RedeemOption>,
 
-    pub encoded_output_token: Option<Vec<u8>>,
+    /// The token to deliver to the recipient on the target network. If not specified, USDC will
+    /// be delivered.
+    pub output_token: Option<OutputToken>,
 }
 
 pub fn stage_outbound(ctx: Context<StageOutbound>, args: StageOutboundArgs) -> Result<()> {
@@ -169,22 +182,20 @@ pub fn stage_outbound(ctx: Context<StageOutbound>, args: StageOutboundArgs) -> R
         target_chain,
         recipient,
         redeem_option,
-        encoded_output_token,
+        output_token,
     } = args;
 
-    // Replace None with OutputToken::USDC encoded.
-    let encoded_output_token = encoded_output_token.unwrap_or_else(|| {
-        let mut buf = Vec::with_capacity(1);
-        OutputToken::Usdc.write(&mut buf).unwrap();
-        buf
-    });
-    let output_token = OutputToken::read(&mut &encoded_output_token[..]).unwrap();
+    // Replace None with OutputToken::USDC.
+    let output_token = output_token.unwrap_or(OutputToken::Usdc);
 
     let is_usdc = ctx.accounts.src_mint.key() == common::USDC_MINT;
 
-    // Swap layer does not support exact out for swaps (yet). We catch this before we reach the
-    // initiate swap instruction.
-    require!(is_usdc || is_exact_in, SwapLayerError::ExactInRequired);
+    // For exact out swaps, the min amount out is the exact amount of USDC the swap must produce, so
+    // it cannot be zero. A missing min amount out is already rejected by the account constraints.
+    require!(
+        is_usdc || is_exact_in || min_amount_out != Some(0),
+        SwapLayerError::ZeroMinAmountOut
+    );
 
     // We need to determine the relayer fee. This fee will either be paid for right now if
     // StagedInput::Usdc or will be deducted from the USDC after a resulting swap from the source
@@ -193,18 +204,19 @@ pub fn stage_outbound(ctx: Context<StageOutbound>, args: StageOutboundArgs) -> R
     // NOTE: The swap instruction will revert if the amount of destination tokens is less than the
     // calculated relaying fee. The amount of source tokens should be sufficient enough to cover the
     // relaying fee after the swap.
-    let (transfer_amount, staged_redeem) = match redeem_option {
+    let (transfer_amount, min_amount_out, staged_redeem) = match redeem_option {
         Some(redeem_option) => match redeem_option {
             RedeemOption::Relay {
                 gas_dropoff,
                 max_relayer_fee,
             } => {
                 // Relaying fee must be less than the user-specific maximum.
-                let relaying_fee = utils::relayer_fees::calculate_relayer_fee(
+                let staged_relaying_fee = utils::relayer_fees::calculate_relayer_fee(
                     &ctx.accounts.target_peer.relay_params,
                     gas_dropoff,
                     &output_token,
                 )?;
+                let relaying_fee = u64::from(staged_relaying_fee);
                 require!(
                     relaying_fee <= max_relayer_fee,
                     SwapLayerError::ExceedsMaxRelayingFee
@@ -225,6 +237,11 @@ pub fn stage_outbound(ctx: Context<StageOutbound>, args: StageOutboundArgs) -> R
                                 .ok_or_else(|| SwapLayerError::U64Overflow)?
                         }
                     } else {
+                        amount_in
+                    },
+                    if is_usdc {
+                        min_amount_out
+                    } else if is_exact_in {
                         // Min amount out must cover the relaying fee. This unwrap should
                         // be fine since we've already checked that min_amount_out is Some
                         // in the account context.
@@ -233,17 +250,25 @@ pub fn stage_outbound(ctx: Context<StageOutbound>, args: StageOutboundArgs) -> R
                             SwapLayerError::InsufficientAmountOut
                         );
 
-                        amount_in
+                        min_amount_out
+                    } else {
+                        // For exact out swaps, the swap must produce enough USDC to pay for the
+                        // relaying fee on top of the amount specified.
+                        min_amount_out
+                            .unwrap()
+                            .checked_add(relaying_fee)
+                            .ok_or_else(|| SwapLayerError::U64Overflow)?
+                            .into()
                     },
                     StagedRedeem::Relay {
                         gas_dropoff,
-                        relaying_fee,
+                        relaying_fee: staged_relaying_fee,
                     },
                 )
             }
-            RedeemOption::Payload(buf) => (amount_in, StagedRedeem::Payload(buf)),
+            RedeemOption::Payload(buf) => (amount_in, min_amount_out, StagedRedeem::Payload(buf)),
         },
-        None => (amount_in, StagedRedeem::Direct),
+        None => (amount_in, min_amount_out, StagedRedeem::Direct),
     };
 
     let src_token_program = &ctx.accounts.src_token_program;
@@ -352,7 +377,7 @@ pub fn stage_outbound(ctx: Context<StageOutbound>, args: StageOutboundArgs) -> R
             min_amount_out,
         },
         staged_redeem,
-        encoded_output_token,
+        output_token,
     });
 
     // Done.
@@ -19,7 +19,7 @@ pub struct Custodian {
 }
 
 impl Custodian {
-    pub const SEED_PREFIX: &'static [u8] = b"custodian";
+    pub const SEED_PREFIX: &'static [u8] = swap_layer_messages::seeds::CUSTODIAN_SEED_PREFIX;
     pub const BUMP: u8 = crate::CUSTODIAN_BUMP;
     pub const SIGNER_SEEDS: &'static [&'static [u8]] = &[Self::SEED_PREFIX, &[Self::BUMP]];
 }
@@ -55,8 +55,12 @@ pub struct Peer {
     pub address: [u8; 32],
     /// Relay parameters.
     pub relay_params: RelayParams,
+    /// Maximum integrator fee in atomic USDC for transfers from this peer. Zero disables
+    /// integrator fees. The fee is only enforced when a fill is completed on Solana, because
+    /// outbound transfers are encoded as swap message V1, which cannot carry one.
+    pub max_integrator_fee: u64,
 }
 
 impl Peer {
-    pub const SEED_PREFIX: &'static [u8] = b"peer";
+    pub const SEED_PREFIX: &'static [u8] = swap_layer_messages::seeds::PEER_SEED_PREFIX;
 }
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use swap_layer_messages::{messages::SwapMessageV1, types::RedeemMode};
+use swap_layer_messages::raw::RedeemModeRef;
 
 use crate::error::SwapLayerError;
 
@@ -38,17 +38,18 @@ pub struct StagedInbound {
 }
 
 impl StagedInbound {
-    pub const SEED_PREFIX: &'static [u8] = b"staged-inbound";
+    pub const SEED_PREFIX: &'static [u8] = swap_layer_messages::seeds::STAGED_INBOUND_SEED_PREFIX;
 
-    pub fn try_compute_size(swap_msg: SwapMessageV1) -> Result<usize> {
+    pub fn try_compute_size(redeem_mode: RedeemModeRef) -> Result<usize> {
         const FIXED: usize = 8 // DISCRIMINATOR
             + StagedInboundSeeds::INIT_SPACE
             + StagedInboundInfo::INIT_SPACE
             + 4 // payload len
         ;
 
-        match swap_msg.redeem_mode {
-            RedeemMode::Payload { sender: _, buf } => buf
+        match redeem_mode {
+            RedeemModeRef::Payload { sender: _, buf }
+            | RedeemModeRef::RelayWithPayload { buf, .. } => buf
                 .len()
                 .checked_add(FIXED)
                 .ok_or_else(|| error!(SwapLayerError::PayloadTooLarge)),
@@ -58,10 +59,10 @@ impl StagedInbound {
 
     pub fn try_compute_size_if_needed(
         acc_info: &AccountInfo,
-        swap_msg: SwapMessageV1,
+        redeem_mode: RedeemModeRef,
     ) -> Result<usize> {
         if acc_info.data_is_empty() {
-            Self::try_compute_size(swap_msg)
+            Self::try_compute_size(redeem_mode)
         } else {
             Ok(acc_info.data_len())
         }
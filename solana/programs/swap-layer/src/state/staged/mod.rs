@@ -3,3 +3,6 @@ pub use inbound::*;
 
 mod outbound;
 pub use outbound::*;
+
+mod split;
+pub use split::*;
@@ -1,9 +1,9 @@
 use crate::error::SwapLayerError;
 use anchor_lang::prelude::*;
-use common::wormhole_io::Readable;
+use common::wormhole_io::Writeable;
 use swap_layer_messages::{
-    messages::SwapMessageV1,
-    types::{OutputToken, RedeemMode},
+    messages::{SwapMessage, SwapMessageV1},
+    types::{OutputToken, RedeemMode, Uint48},
 };
 
 #[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
@@ -24,7 +24,7 @@ pub enum StagedRedeem {
     Direct,
     Relay {
         gas_dropoff: u32,
-        relaying_fee: u64,
+        relaying_fee: Uint48,
     },
     Payload(Vec<u8>),
 }
@@ -64,7 +64,7 @@ pub struct StagedOutboundInfo {
 pub struct StagedOutbound {
     pub info: StagedOutboundInfo,
     pub staged_redeem: StagedRedeem,
-    pub encoded_output_token: Vec<u8>,
+    pub output_token: OutputToken,
 }
 
 impl StagedOutbound {
@@ -75,7 +75,7 @@ impl StagedOutbound {
 
     pub fn try_compute_size(
         redeem_option: &Option<RedeemOption>,
-        encoded_output_token: &Option<Vec<u8>>,
+        output_token: &Option<OutputToken>,
     ) -> Result<usize> {
         Ok(Self::BASE_SIZE
             .saturating_add(match redeem_option {
@@ -85,26 +85,31 @@ impl StagedOutbound {
                 },
                 None => 0,
             })
-            .saturating_add(match encoded_output_token {
-                Some(encoded_output_token) => {
-                    // First validate the encoded output token by attempting to deserialize it.
-                    OutputToken::read(&mut &encoded_output_token[..])
+            .saturating_add(match output_token {
+                Some(output_token) => {
+                    // First validate the output token by attempting to encode it for the swap
+                    // message.
+                    output_token
+                        .write(&mut std::io::sink())
                         .map_err(|_| error!(SwapLayerError::InvalidOutputToken))?;
 
-                    encoded_output_token.len().saturating_add(4)
+                    output_token.try_to_vec()?.len()
                 }
-                None => 5, // len + OutputToken::Usdc,
+                None => 1, // OutputToken::Usdc
             }))
     }
 
-    pub fn to_swap_message_v1(&mut self) -> Result<SwapMessageV1> {
+    /// Build the swap message for the outbound transfer. Peers may not support newer versions of
+    /// the swap message yet, so V1 is encoded.
+    pub fn to_swap_message(&mut self) -> Result<SwapMessage> {
         let Self {
             info,
             staged_redeem,
-            encoded_output_token,
+            output_token,
         } = self;
 
         let staged_redeem = std::mem::take(staged_redeem);
+        let output_token = std::mem::replace(output_token, OutputToken::Usdc);
 
         Ok(SwapMessageV1 {
             recipient: info.recipient,
@@ -121,12 +126,12 @@ impl StagedOutbound {
                     relaying_fee,
                 } => RedeemMode::Relay {
                     gas_dropoff,
-                    relaying_fee: relaying_fee.try_into().unwrap(),
+                    relaying_fee,
                 },
             },
-            output_token: Readable::read(&mut &encoded_output_token[..])
-                .map_err(|_| SwapLayerError::InvalidOutputToken)?,
-        })
+            output_token,
+        }
+        .into())
     }
 }
 
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use swap_layer_messages::{messages::SwapMessageV2, types::OutputToken};
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Eq, InitSpace)]
+pub struct StagedSplitSeeds {
+    pub prepared_fill: Pubkey,
+    pub bump: u8,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct StagedSplitInfo {
+    pub custody_token_bump: u8,
+
+    /// Payer that created this StagedSplit.
+    pub staged_by: Pubkey,
+
+    /// Exposed out of convenience for the receiving program.
+    pub source_chain: u16,
+
+    /// Time after which a relayer may deliver USDC to the recipient of an output token that is not
+    /// USDC instead of performing the swap. This is only set for relayed fills.
+    pub swap_time_limit_end: Option<i64>,
+}
+
+/// Share of the fill owed to one recipient.
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct StagedSplitOutput {
+    /// Owner of the token account receiving the output token.
+    pub recipient: Pubkey,
+
+    /// Owner of the USDC token account receiving this share if a relayer redeems USDC instead of
+    /// performing the swap. This is the fallback recipient if the message specifies one.
+    pub usdc_recipient: Pubkey,
+
+    /// Amount of USDC owed to this output.
+    pub amount: u64,
+
+    pub output_token: OutputToken,
+
+    pub is_completed: bool,
+}
+
+#[account]
+#[derive(Debug)]
+pub struct StagedSplit {
+    pub seeds: StagedSplitSeeds,
+    pub info: StagedSplitInfo,
+
+    /// The message recipient's share followed by each split output in the order encoded in the
+    /// swap message.
+    pub outputs: Vec<StagedSplitOutput>,
+}
+
+impl StagedSplit {
+    pub const SEED_PREFIX: &'static [u8] = swap_layer_messages::seeds::STAGED_SPLIT_SEED_PREFIX;
+
+    pub fn try_compute_size(swap_msg: &SwapMessageV2) -> Result<usize> {
+        const FIXED: usize = 8 // DISCRIMINATOR
+            + StagedSplitSeeds::INIT_SPACE
+            + StagedSplitInfo::INIT_SPACE
+            + 4 // outputs len
+        ;
+        const OUTPUT_FIXED: usize = 32 // recipient
+            + 32 // usdc_recipient
+            + 8 // amount
+            + 1 // is_completed
+        ;
+
+        std::iter::once(&swap_msg.output_token)
+            .chain(
+                swap_msg
+                    .split_outputs
+                    .iter()
+                    .map(|split_output| &split_output.output_token),
+            )
+            .try_fold(FIXED, |size, output_token| {
+                Ok(size
+                    .saturating_add(OUTPUT_FIXED)
+                    .saturating_add(output_token.try_to_vec()?.len()))
+            })
+    }
+
+    /// Whether every output has been delivered.
+    pub fn is_completed(&self) -> bool {
+        self.outputs.iter().all(|output| output.is_completed)
+    }
+}
+
+impl std::ops::Deref for StagedSplit {
+    type Target = StagedSplitInfo;
+
+    fn deref(&self) -> &Self::Target {
+        &self.info
+    }
+}
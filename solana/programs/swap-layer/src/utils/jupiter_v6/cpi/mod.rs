@@ -1,2 +1,5 @@
+mod shared_accounts_exact_out_route;
+pub use shared_accounts_exact_out_route::*;
+
 mod shared_accounts_route;
 pub use shared_accounts_route::*;
@@ -0,0 +1,31 @@
+use crate::utils::{jupiter_v6::types::RoutePlanStep, AnchorInstructionData, AnchorSelector};
+use anchor_lang::prelude::*;
+
+pub const SHARED_ACCOUNTS_EXACT_OUT_ROUTE_SELECTOR: AnchorSelector =
+    AnchorSelector([176, 209, 105, 168, 154, 125, 69, 62]);
+
+/// NOTE: The shared accounts exact out route instruction uses the same accounts as
+/// [SharedAccountsRoute](super::SharedAccountsRoute). Its arguments also share the same layout as
+/// [SharedAccountsRouteArgs](super::SharedAccountsRouteArgs), where the in amount and quoted out
+/// amount are replaced with the out amount and quoted in amount, respectively.
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SharedAccountsExactOutRouteArgs {
+    pub authority_id: u8,
+    pub route_plan: Vec<RoutePlanStep>,
+    pub out_amount: u64,
+    pub quoted_in_amount: u64,
+    pub slippage_bps: u16,
+    pub platform_fee_bps: u8,
+}
+
+impl AnchorInstructionData for SharedAccountsExactOutRouteArgs {
+    fn require_selector(data: &mut &[u8]) -> Result<()> {
+        require_eq!(
+            AnchorSelector::deserialize(data)?,
+            SHARED_ACCOUNTS_EXACT_OUT_ROUTE_SELECTOR,
+            ErrorCode::InstructionDidNotDeserialize
+        );
+
+        Ok(())
+    }
+}
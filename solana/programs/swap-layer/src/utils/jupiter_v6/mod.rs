@@ -1,5 +1,5 @@
 pub mod cpi;
-pub use cpi::SHARED_ACCOUNTS_ROUTE_SELECTOR;
+pub use cpi::{SHARED_ACCOUNTS_EXACT_OUT_ROUTE_SELECTOR, SHARED_ACCOUNTS_ROUTE_SELECTOR};
 
 mod types;
 pub use types::*;
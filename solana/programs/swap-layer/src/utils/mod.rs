@@ -1,5 +1,6 @@
 pub mod gas_dropoff;
 pub mod jupiter_v6;
+pub mod orca_whirlpool;
 pub mod relay_parameters;
 pub mod relayer_fees;
 //pub mod token_router;
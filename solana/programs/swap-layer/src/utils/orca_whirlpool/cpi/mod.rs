@@ -0,0 +1,5 @@
+mod swap;
+pub use swap::*;
+
+mod two_hop_swap;
+pub use two_hop_swap::*;
@@ -0,0 +1,27 @@
+use crate::utils::{AnchorInstructionData, AnchorSelector};
+use anchor_lang::prelude::*;
+
+pub const SWAP_SELECTOR: AnchorSelector = AnchorSelector([248, 198, 158, 145, 225, 117, 135, 200]);
+
+/// NOTE: The accounts of the swap instruction are deserialized by
+/// [OrcaWhirlpoolSwap](crate::composite::OrcaWhirlpoolSwap).
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SwapArgs {
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+    pub sqrt_price_limit: u128,
+    pub amount_specified_is_input: bool,
+    pub a_to_b: bool,
+}
+
+impl AnchorInstructionData for SwapArgs {
+    fn require_selector(data: &mut &[u8]) -> Result<()> {
+        require_eq!(
+            AnchorSelector::deserialize(data)?,
+            SWAP_SELECTOR,
+            ErrorCode::InstructionDidNotDeserialize
+        );
+
+        Ok(())
+    }
+}
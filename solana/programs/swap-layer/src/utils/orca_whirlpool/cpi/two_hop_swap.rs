@@ -0,0 +1,30 @@
+use crate::utils::{AnchorInstructionData, AnchorSelector};
+use anchor_lang::prelude::*;
+
+pub const TWO_HOP_SWAP_SELECTOR: AnchorSelector =
+    AnchorSelector([195, 96, 237, 108, 68, 162, 219, 230]);
+
+/// NOTE: The accounts of the two hop swap instruction are deserialized by
+/// [OrcaWhirlpoolTwoHopSwap](crate::composite::OrcaWhirlpoolTwoHopSwap).
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TwoHopSwapArgs {
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+    pub amount_specified_is_input: bool,
+    pub a_to_b_one: bool,
+    pub a_to_b_two: bool,
+    pub sqrt_price_limit_one: u128,
+    pub sqrt_price_limit_two: u128,
+}
+
+impl AnchorInstructionData for TwoHopSwapArgs {
+    fn require_selector(data: &mut &[u8]) -> Result<()> {
+        require_eq!(
+            AnchorSelector::deserialize(data)?,
+            TWO_HOP_SWAP_SELECTOR,
+            ErrorCode::InstructionDidNotDeserialize
+        );
+
+        Ok(())
+    }
+}
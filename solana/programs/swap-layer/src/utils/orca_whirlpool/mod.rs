@@ -0,0 +1,84 @@
+pub mod cpi;
+pub use cpi::{SWAP_SELECTOR, TWO_HOP_SWAP_SELECTOR};
+
+use anchor_lang::prelude::*;
+use solana_program::pubkey;
+
+use crate::utils::AnchorInstructionData;
+
+pub const ORCA_WHIRLPOOL_PROGRAM_ID: Pubkey =
+    pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+
+/// Lowest and highest prices a whirlpool can reach (Q64.64 square roots). Swap Layer enforces the
+/// amount out itself, so swaps are never limited by price.
+pub const MIN_SQRT_PRICE_X64: u128 = 4_295_048_016;
+pub const MAX_SQRT_PRICE_X64: u128 = 79_226_673_515_401_279_992_447_579_055;
+
+/// Price limit that does not restrict a swap in the given direction.
+pub fn sqrt_price_limit(a_to_b: bool) -> u128 {
+    match a_to_b {
+        true => MIN_SQRT_PRICE_X64,
+        false => MAX_SQRT_PRICE_X64,
+    }
+}
+
+/// Whether the instruction data is for the Whirlpool swap or two hop swap instruction.
+pub fn is_swap_instruction(ix_data: &[u8]) -> bool {
+    ix_data.starts_with(&SWAP_SELECTOR.0) || ix_data.starts_with(&TWO_HOP_SWAP_SELECTOR.0)
+}
+
+/// Direction of each swap encoded in the Whirlpool swap or two hop swap instruction data.
+pub fn swap_directions(ix_data: &[u8]) -> Result<Vec<bool>> {
+    if ix_data.starts_with(&TWO_HOP_SWAP_SELECTOR.0) {
+        let args = cpi::TwoHopSwapArgs::deserialize_checked(ix_data)?;
+        Ok(vec![args.a_to_b_one, args.a_to_b_two])
+    } else {
+        let args = cpi::SwapArgs::deserialize_checked(ix_data)?;
+        Ok(vec![args.a_to_b])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_swap_directions() {
+        let ix_data = (
+            SWAP_SELECTOR,
+            cpi::SwapArgs {
+                amount: 69,
+                other_amount_threshold: 420,
+                sqrt_price_limit: sqrt_price_limit(true),
+                amount_specified_is_input: true,
+                a_to_b: true,
+            },
+        )
+            .try_to_vec()
+            .unwrap();
+        assert!(is_swap_instruction(&ix_data));
+        assert_eq!(swap_directions(&ix_data).unwrap(), vec![true]);
+
+        let ix_data = (
+            TWO_HOP_SWAP_SELECTOR,
+            cpi::TwoHopSwapArgs {
+                amount: 69,
+                other_amount_threshold: 420,
+                amount_specified_is_input: true,
+                a_to_b_one: false,
+                a_to_b_two: true,
+                sqrt_price_limit_one: sqrt_price_limit(false),
+                sqrt_price_limit_two: sqrt_price_limit(true),
+            },
+        )
+            .try_to_vec()
+            .unwrap();
+        assert!(is_swap_instruction(&ix_data));
+        assert_eq!(swap_directions(&ix_data).unwrap(), vec![false, true]);
+
+        // Jupiter V6 instruction data is not a Whirlpool swap.
+        assert!(!is_swap_instruction(
+            &crate::utils::jupiter_v6::SHARED_ACCOUNTS_ROUTE_SELECTOR.0
+        ));
+    }
+}
@@ -3,7 +3,11 @@ export * from "./layouts";
 import * as jupAg from "@jup-ag/api";
 import * as splToken from "@solana/spl-token";
 import { Connection, PublicKey, TransactionInstruction } from "@solana/web3.js";
-import { decodeSharedAccountsRouteArgs, encodeSharedAccountsRouteArgs } from "./layouts";
+import {
+    decodeSharedAccountsRouteArgs,
+    encodeSharedAccountsExactOutRouteArgs,
+    encodeSharedAccountsRouteArgs,
+} from "./layouts";
 
 export const JUPITER_V6_PROGRAM_ID = new PublicKey("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
 
@@ -127,6 +131,49 @@ export async function modifySharedAccountsRouteInstruction(
     };
 }
 
+export type ToSharedAccountsExactOutRouteOpts = {
+    outAmount: bigint;
+    quotedInAmount: bigint;
+    slippageBps?: number;
+};
+
+export type SharedAccountsExactOutRoute = {
+    instruction: TransactionInstruction;
+    maxAmountIn: bigint;
+};
+
+/**
+ * Convert a shared accounts route instruction into a shared accounts exact out route instruction.
+ * Both instructions use the same accounts, so only the instruction data is replaced.
+ */
+export function toSharedAccountsExactOutRouteInstruction(
+    ix: TransactionInstruction,
+    opts: ToSharedAccountsExactOutRouteOpts,
+): SharedAccountsExactOutRoute {
+    const { outAmount, quotedInAmount } = opts;
+
+    const { id, routePlan, slippageBps, platformFeeBps } = decodeSharedAccountsRouteArgs(ix.data);
+    const args = {
+        id,
+        routePlan,
+        outAmount,
+        quotedInAmount,
+        slippageBps: opts.slippageBps ?? slippageBps,
+        platformFeeBps,
+    };
+
+    const maxAmountIn = (quotedInAmount * BigInt(10000 + args.slippageBps)) / BigInt(10000);
+
+    return {
+        instruction: new TransactionInstruction({
+            programId: ix.programId,
+            keys: ix.keys,
+            data: encodeSharedAccountsExactOutRouteArgs(args),
+        }),
+        maxAmountIn,
+    };
+}
+
 export function eventAuthorityAddress(programId: PublicKey): PublicKey {
     return PublicKey.findProgramAddressSync([Buffer.from("__event_authority")], programId)[0];
 }
@@ -17,6 +17,19 @@ export const decodeSharedAccountsRouteArgs = (data: Buffer | Uint8Array): Shared
 export const encodeSharedAccountsRouteArgs = (decoded: SharedAccountsRouteArgs): Buffer =>
     Buffer.from(serializeLayout(sharedAccountsRouteArgsLayout, decoded));
 
+export type SharedAccountsExactOutRouteArgs = LayoutToType<
+    typeof sharedAccountsExactOutRouteArgsLayout
+>;
+
+export const decodeSharedAccountsExactOutRouteArgs = (
+    data: Buffer | Uint8Array,
+): SharedAccountsExactOutRouteArgs =>
+    deserializeLayout(sharedAccountsExactOutRouteArgsLayout, Uint8Array.from(data));
+
+export const encodeSharedAccountsExactOutRouteArgs = (
+    decoded: SharedAccountsExactOutRouteArgs,
+): Buffer => Buffer.from(serializeLayout(sharedAccountsExactOutRouteArgsLayout, decoded));
+
 const boolItem = {
     binary: "uint",
     size: 1,
@@ -121,3 +134,19 @@ const sharedAccountsRouteArgsLayout = [
     { name: "slippageBps", binary: "uint", size: 2, endianness: "little" },
     { name: "platformFeeBps", binary: "uint", size: 1 },
 ] as const satisfies Layout;
+
+const sharedAccountsExactOutRouteArgsLayout = [
+    selectorItem([176, 209, 105, 168, 154, 125, 69, 62]),
+    { name: "id", binary: "uint", size: 1 },
+    {
+        name: "routePlan",
+        binary: "array",
+        lengthSize: 4,
+        lengthEndianness: "little",
+        layout: routePlanStep,
+    },
+    { name: "outAmount", binary: "uint", size: 8, endianness: "little" },
+    { name: "quotedInAmount", binary: "uint", size: 8, endianness: "little" },
+    { name: "slippageBps", binary: "uint", size: 2, endianness: "little" },
+    { name: "platformFeeBps", binary: "uint", size: 1 },
+] as const satisfies Layout;
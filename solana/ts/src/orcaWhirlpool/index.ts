@@ -0,0 +1,203 @@
+export * from "./layouts";
+
+import * as splToken from "@solana/spl-token";
+import { AccountMeta, Connection, PublicKey, TransactionInstruction } from "@solana/web3.js";
+import { encodeSwapArgs, encodeTwoHopSwapArgs } from "./layouts";
+
+export const ORCA_WHIRLPOOL_PROGRAM_ID = new PublicKey(
+    "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc",
+);
+
+/** Lowest and highest prices a whirlpool can reach (Q64.64 square roots). */
+export const MIN_SQRT_PRICE_X64 = 4295048016n;
+export const MAX_SQRT_PRICE_X64 = 79226673515401279992447579055n;
+
+/** Number of ticks in a tick array. */
+export const TICK_ARRAY_SIZE = 88;
+
+/** Price limit that does not restrict a swap in the given direction. */
+export function sqrtPriceLimit(aToB: boolean): bigint {
+    return aToB ? MIN_SQRT_PRICE_X64 : MAX_SQRT_PRICE_X64;
+}
+
+export function oracleAddress(whirlpool: PublicKey): PublicKey {
+    return PublicKey.findProgramAddressSync(
+        [Buffer.from("oracle"), whirlpool.toBuffer()],
+        ORCA_WHIRLPOOL_PROGRAM_ID,
+    )[0];
+}
+
+export function tickArrayAddress(whirlpool: PublicKey, startTickIndex: number): PublicKey {
+    return PublicKey.findProgramAddressSync(
+        [Buffer.from("tick_array"), whirlpool.toBuffer(), Buffer.from(startTickIndex.toString())],
+        ORCA_WHIRLPOOL_PROGRAM_ID,
+    )[0];
+}
+
+export type WhirlpoolSwap = {
+    whirlpool: PublicKey;
+    tokenOwnerAccountA: PublicKey;
+    tokenVaultA: PublicKey;
+    tokenOwnerAccountB: PublicKey;
+    tokenVaultB: PublicKey;
+    tickArrays: [PublicKey, PublicKey, PublicKey];
+    aToB: boolean;
+};
+
+/**
+ * Fetch the whirlpool to find the accounts of a swap from the input mint. The token owner's
+ * associated token accounts are used for both tokens, and the tick arrays are the three starting
+ * with the one containing the current tick in the direction of the swap.
+ */
+export async function fetchWhirlpoolSwap(
+    connection: Connection,
+    whirlpool: PublicKey,
+    tokenOwner: PublicKey,
+    inputMint: PublicKey,
+): Promise<WhirlpoolSwap> {
+    const accInfo = await connection.getAccountInfo(whirlpool);
+    if (accInfo === null) {
+        throw new Error("Whirlpool not found");
+    }
+    const { data } = accInfo;
+
+    const tickSpacing = data.readUInt16LE(41);
+    const tickCurrentIndex = data.readInt32LE(81);
+    const tokenMintA = new PublicKey(data.subarray(101, 133));
+    const tokenVaultA = new PublicKey(data.subarray(133, 165));
+    const tokenMintB = new PublicKey(data.subarray(181, 213));
+    const tokenVaultB = new PublicKey(data.subarray(213, 245));
+
+    const aToB = inputMint.equals(tokenMintA);
+    if (!aToB && !inputMint.equals(tokenMintB)) {
+        throw new Error("Input mint not in whirlpool");
+    }
+
+    const ticksInArray = TICK_ARRAY_SIZE * tickSpacing;
+    const startTickIndex = Math.floor(tickCurrentIndex / ticksInArray) * ticksInArray;
+    const direction = aToB ? -1 : 1;
+    const tickArrays = [0, 1, 2].map((i) =>
+        tickArrayAddress(whirlpool, startTickIndex + direction * i * ticksInArray),
+    ) as [PublicKey, PublicKey, PublicKey];
+
+    return {
+        whirlpool,
+        tokenOwnerAccountA: splToken.getAssociatedTokenAddressSync(tokenMintA, tokenOwner, true),
+        tokenVaultA,
+        tokenOwnerAccountB: splToken.getAssociatedTokenAddressSync(tokenMintB, tokenOwner, true),
+        tokenVaultB,
+        tickArrays,
+        aToB,
+    };
+}
+
+export type WhirlpoolSwapOpts = {
+    amount?: bigint;
+    otherAmountThreshold?: bigint;
+    cpi?: boolean;
+};
+
+/**
+ * Whirlpool swap instruction. When the instruction is passed to the Swap Layer (cpi == true), the
+ * Whirlpool program is appended to its accounts and the token authority is not a signer. The Swap
+ * Layer swaps its whole amount with its own limit, so the amounts are ignored.
+ */
+export function swapInstruction(
+    tokenAuthority: PublicKey,
+    swap: WhirlpoolSwap,
+    opts: WhirlpoolSwapOpts = {},
+): TransactionInstruction {
+    const { aToB } = swap;
+
+    let { amount, otherAmountThreshold, cpi } = opts;
+    amount ??= 0n;
+    otherAmountThreshold ??= 0n;
+    cpi ??= false;
+
+    const keys = [
+        readonlyMeta(splToken.TOKEN_PROGRAM_ID),
+        { pubkey: tokenAuthority, isSigner: !cpi, isWritable: false },
+        writableMeta(swap.whirlpool),
+        writableMeta(swap.tokenOwnerAccountA),
+        writableMeta(swap.tokenVaultA),
+        writableMeta(swap.tokenOwnerAccountB),
+        writableMeta(swap.tokenVaultB),
+        ...swap.tickArrays.map(writableMeta),
+        readonlyMeta(oracleAddress(swap.whirlpool)),
+    ];
+    if (cpi) {
+        keys.push(readonlyMeta(ORCA_WHIRLPOOL_PROGRAM_ID));
+    }
+
+    return new TransactionInstruction({
+        programId: ORCA_WHIRLPOOL_PROGRAM_ID,
+        keys,
+        data: encodeSwapArgs({
+            amount,
+            otherAmountThreshold,
+            sqrtPriceLimit: sqrtPriceLimit(aToB),
+            amountSpecifiedIsInput: true,
+            aToB,
+        }),
+    });
+}
+
+/**
+ * Whirlpool two hop swap instruction. The output token account of the first swap must be the
+ * input token account of the second swap. See {@link swapInstruction} for the Swap Layer.
+ */
+export function twoHopSwapInstruction(
+    tokenAuthority: PublicKey,
+    swapOne: WhirlpoolSwap,
+    swapTwo: WhirlpoolSwap,
+    opts: WhirlpoolSwapOpts = {},
+): TransactionInstruction {
+    let { amount, otherAmountThreshold, cpi } = opts;
+    amount ??= 0n;
+    otherAmountThreshold ??= 0n;
+    cpi ??= false;
+
+    const keys = [
+        readonlyMeta(splToken.TOKEN_PROGRAM_ID),
+        { pubkey: tokenAuthority, isSigner: !cpi, isWritable: false },
+        writableMeta(swapOne.whirlpool),
+        writableMeta(swapTwo.whirlpool),
+        writableMeta(swapOne.tokenOwnerAccountA),
+        writableMeta(swapOne.tokenVaultA),
+        writableMeta(swapOne.tokenOwnerAccountB),
+        writableMeta(swapOne.tokenVaultB),
+        writableMeta(swapTwo.tokenOwnerAccountA),
+        writableMeta(swapTwo.tokenVaultA),
+        writableMeta(swapTwo.tokenOwnerAccountB),
+        writableMeta(swapTwo.tokenVaultB),
+        ...swapOne.tickArrays.map(writableMeta),
+        ...swapTwo.tickArrays.map(writableMeta),
+        readonlyMeta(oracleAddress(swapOne.whirlpool)),
+        readonlyMeta(oracleAddress(swapTwo.whirlpool)),
+    ];
+    if (cpi) {
+        keys.push(readonlyMeta(ORCA_WHIRLPOOL_PROGRAM_ID));
+    }
+
+    return new TransactionInstruction({
+        programId: ORCA_WHIRLPOOL_PROGRAM_ID,
+        keys,
+        data: encodeTwoHopSwapArgs({
+            amount,
+            otherAmountThreshold,
+            amountSpecifiedIsInput: true,
+            aToBOne: swapOne.aToB,
+            aToBTwo: swapTwo.aToB,
+            sqrtPriceLimitOne: sqrtPriceLimit(swapOne.aToB),
+            sqrtPriceLimitTwo: sqrtPriceLimit(swapTwo.aToB),
+        }),
+    });
+}
+
+function readonlyMeta(pubkey: PublicKey): AccountMeta {
+    return { pubkey, isSigner: false, isWritable: false };
+}
+
+function writableMeta(pubkey: PublicKey): AccountMeta {
+    return { pubkey, isSigner: false, isWritable: true };
+}
@@ -0,0 +1,61 @@
+import {
+    CustomConversion,
+    Layout,
+    LayoutToType,
+    UintLayoutItem,
+    deserializeLayout,
+    serializeLayout,
+} from "@wormhole-foundation/sdk-base";
+
+export type SwapArgs = LayoutToType<typeof swapArgsLayout>;
+export type TwoHopSwapArgs = LayoutToType<typeof twoHopSwapArgsLayout>;
+
+export const decodeSwapArgs = (data: Buffer | Uint8Array): SwapArgs =>
+    deserializeLayout(swapArgsLayout, Uint8Array.from(data));
+
+export const encodeSwapArgs = (decoded: SwapArgs): Buffer =>
+    Buffer.from(serializeLayout(swapArgsLayout, decoded));
+
+export const decodeTwoHopSwapArgs = (data: Buffer | Uint8Array): TwoHopSwapArgs =>
+    deserializeLayout(twoHopSwapArgsLayout, Uint8Array.from(data));
+
+export const encodeTwoHopSwapArgs = (decoded: TwoHopSwapArgs): Buffer =>
+    Buffer.from(serializeLayout(twoHopSwapArgsLayout, decoded));
+
+const boolItem = {
+    binary: "uint",
+    size: 1,
+    custom: {
+        to: (encoded: number): boolean => encoded > 0,
+        from: (val: boolean): number => (val ? 1 : 0),
+    } satisfies CustomConversion<number, boolean>,
+} as const satisfies UintLayoutItem;
+
+// The Anchor selector is omitted from the decoded args.
+const selectorItem = (selector: number[]) =>
+    ({
+        name: "selector",
+        binary: "bytes",
+        custom: Uint8Array.from(selector),
+        omit: true,
+    } as const);
+
+const swapArgsLayout = [
+    selectorItem([248, 198, 158, 145, 225, 117, 135, 200]),
+    { name: "amount", binary: "uint", size: 8, endianness: "little" },
+    { name: "otherAmountThreshold", binary: "uint", size: 8, endianness: "little" },
+    { name: "sqrtPriceLimit", binary: "uint", size: 16, endianness: "little" },
+    { name: "amountSpecifiedIsInput", ...boolItem },
+    { name: "aToB", ...boolItem },
+] as const satisfies Layout;
+
+const twoHopSwapArgsLayout = [
+    selectorItem([195, 96, 237, 108, 68, 162, 219, 230]),
+    { name: "amount", binary: "uint", size: 8, endianness: "little" },
+    { name: "otherAmountThreshold", binary: "uint", size: 8, endianness: "little" },
+    { name: "amountSpecifiedIsInput", ...boolItem },
+    { name: "aToBOne", ...boolItem },
+    { name: "aToBTwo", ...boolItem },
+    { name: "sqrtPriceLimitOne", binary: "uint", size: 16, endianness: "little" },
+    { name: "sqrtPriceLimitTwo", binary: "uint", size: 16, endianness: "little" },
+] as const satisfies Layout;
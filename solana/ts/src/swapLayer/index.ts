@@ -16,9 +16,23 @@ import { ChainId } from "@wormhole-foundation/sdk-base";
 import { keccak256 } from "@wormhole-foundation/sdk-definitions";
 import IDL from "../../../target/idl/swap_layer.json";
 import { SwapLayer } from "../../../target/types/swap_layer";
-import { OutputToken, encodeOutputToken } from "./messages";
-import { Custodian, Peer, RedeemOption, RelayParams, StagedInbound, StagedOutbound } from "./state";
-import { programDataAddress } from "./utils";
+import {
+    OutputToken,
+    OutputTokenV2,
+    SwapLayerMessageV2,
+    decodeAnySwapLayerMessage,
+} from "./messages";
+import {
+    Custodian,
+    Peer,
+    RedeemOption,
+    RelayParams,
+    StagedInbound,
+    StagedOutbound,
+    StagedSplit,
+    toStagedOutputToken,
+} from "./state";
+import { markSigner, programDataAddress } from "./utils";
 
 export const PROGRAM_IDS = ["SwapLayer1111111111111111111111111111111111"] as const;
 
@@ -28,6 +42,8 @@ export type AddPeerArgs = {
     chain: ChainId;
     address: Array<number>;
     relayParams: RelayParams;
+    // Maximum integrator fee in atomic USDC. Defaults to zero, which disables integrator fees.
+    maxIntegratorFee?: Uint64;
 };
 
 export type RelayOptions = {
@@ -157,6 +173,7 @@ export class SwapLayerProgram {
             preparedFill: PublicKey;
             beneficiary: PublicKey;
             sourcePeer?: PublicKey;
+            integratorFeeToken?: PublicKey | null;
         },
         opts: { sourceChain?: ChainId } = {},
     ): Promise<{
@@ -165,11 +182,12 @@ export class SwapLayerProgram {
         fillCustodyToken: PublicKey;
         sourcePeer: RegisteredPeerComposite;
         beneficiary: PublicKey;
+        integratorFeeToken: PublicKey | null;
         tokenRouterProgram: PublicKey;
     }> {
         const { preparedFill, beneficiary } = accounts;
 
-        let { sourcePeer: peer } = accounts;
+        let { sourcePeer: peer, integratorFeeToken } = accounts;
         let { sourceChain } = opts;
 
         const tokenRouter = this.tokenRouterProgram();
@@ -180,6 +198,9 @@ export class SwapLayerProgram {
         }
 
         peer ??= this.peerAddress(sourceChain);
+        if (integratorFeeToken === undefined) {
+            integratorFeeToken = await this.integratorFeeToken(preparedFill);
+        }
 
         return {
             custodian: this.checkedCustodianComposite(),
@@ -187,10 +208,41 @@ export class SwapLayerProgram {
             fillCustodyToken: tokenRouter.preparedCustodyTokenAddress(preparedFill),
             sourcePeer: { peer },
             beneficiary,
+            integratorFeeToken,
             tokenRouterProgram: tokenRouter.ID,
         };
     }
 
+    async consumeStagedSplitComposite(accounts: {
+        preparedFill: PublicKey;
+        stagedBy?: PublicKey;
+        feeRecipientToken?: PublicKey;
+    }): Promise<{
+        custodian: CheckedCustodianComposite;
+        stagedSplit: PublicKey;
+        stagedCustodyToken: PublicKey;
+        stagedBy: PublicKey;
+        feeRecipientToken: PublicKey;
+        tokenProgram: PublicKey;
+    }> {
+        const { preparedFill } = accounts;
+
+        let { stagedBy, feeRecipientToken } = accounts;
+
+        const stagedSplit = this.stagedSplitAddress(preparedFill);
+        stagedBy ??= await this.fetchStagedSplit(stagedSplit).then((s) => s.info.stagedBy);
+        feeRecipientToken ??= await this.fetchCustodian().then((c) => c.feeRecipientToken);
+
+        return {
+            custodian: this.checkedCustodianComposite(),
+            stagedSplit,
+            stagedCustodyToken: this.stagedCustodyTokenAddress(stagedSplit),
+            stagedBy,
+            feeRecipientToken,
+            tokenProgram: splToken.TOKEN_PROGRAM_ID,
+        };
+    }
+
     swapAuthorityAddress(preparedSource: PublicKey): PublicKey {
         return PublicKey.findProgramAddressSync(
             [Buffer.from("swap-authority"), preparedSource.toBuffer()],
@@ -265,6 +317,86 @@ export class SwapLayerProgram {
         };
     }
 
+    /**
+     * USDC not spent by an exact out swap is sent to the encoded recipient's USDC token account.
+     * Returns the recipient's USDC ATA if the prepared fill has an exact out swap, null otherwise.
+     *
+     * NOTE: The program does not create this account. If it does not exist, the swap cannot be
+     * completed, so it must be created (e.g. with an idempotent create ATA instruction) first.
+     */
+    async exactOutRecipientUsdcToken(preparedFill: PublicKey): Promise<PublicKey | null> {
+        const { redeemerMessage } = await this.tokenRouterProgram().fetchPreparedFill(preparedFill);
+        const { recipient, outputToken } = decodeAnySwapLayerMessage(
+            Uint8Array.from(redeemerMessage),
+        );
+        if (outputToken.type === "Usdc" || outputToken.swap.mode !== "ExactOut") {
+            return null;
+        }
+
+        return splToken.getAssociatedTokenAddressSync(
+            this.usdcMint,
+            new PublicKey(recipient.toUint8Array()),
+            true, // allowOwnerOffCurve
+            splToken.TOKEN_PROGRAM_ID,
+        );
+    }
+
+    /**
+     * USDC redeemed by a relayer instead of the encoded swap (because the swap failed or was not
+     * performed in time) is sent to the encoded fallback recipient's USDC token account. Returns
+     * the fallback recipient's USDC ATA if the prepared fill has a swap and a fallback recipient,
+     * null otherwise.
+     *
+     * NOTE: The recipient redeeming USDC itself always receives it, so this account is not used.
+     */
+    async fallbackRecipientToken(preparedFill: PublicKey): Promise<PublicKey | null> {
+        const { redeemerMessage } = await this.tokenRouterProgram().fetchPreparedFill(preparedFill);
+        const { outputToken, fallbackRecipient } = decodeAnySwapLayerMessage(
+            Uint8Array.from(redeemerMessage),
+        );
+        if (outputToken.type === "Usdc" || !fallbackRecipient.isSome) {
+            return null;
+        }
+
+        return splToken.getAssociatedTokenAddressSync(
+            this.usdcMint,
+            new PublicKey(fallbackRecipient.address.toUint8Array()),
+            true, // allowOwnerOffCurve
+            splToken.TOKEN_PROGRAM_ID,
+        );
+    }
+
+    /**
+     * The integrator fee is paid to the encoded integrator fee recipient's USDC token account.
+     * Returns the recipient's USDC ATA if the prepared fill has an integrator fee, null otherwise.
+     *
+     * NOTE: The program does not create this account. If it does not exist, the fill cannot be
+     * completed, so it must be created (e.g. with an idempotent create ATA instruction) first.
+     */
+    async integratorFeeToken(preparedFill: PublicKey): Promise<PublicKey | null> {
+        // The program rejects a prepared fill whose swap message it cannot decode, so no fee is
+        // paid for one.
+        let integratorFee: SwapLayerMessageV2["integratorFee"];
+        try {
+            const { redeemerMessage } = await this.tokenRouterProgram().fetchPreparedFill(
+                preparedFill,
+            );
+            ({ integratorFee } = decodeAnySwapLayerMessage(Uint8Array.from(redeemerMessage)));
+        } catch {
+            return null;
+        }
+        if (!integratorFee.isSome) {
+            return null;
+        }
+
+        return splToken.getAssociatedTokenAddressSync(
+            this.usdcMint,
+            new PublicKey(integratorFee.recipient.toUint8Array()),
+            true, // allowOwnerOffCurve
+            splToken.TOKEN_PROGRAM_ID,
+        );
+    }
+
     stagedInboundAddress(preparedFill: PublicKey) {
         return StagedInbound.address(this.ID, preparedFill);
     }
@@ -283,6 +415,14 @@ export class SwapLayerProgram {
         return this.program.account.stagedOutbound.fetch(addr);
     }
 
+    stagedSplitAddress(preparedFill: PublicKey) {
+        return StagedSplit.address(this.ID, preparedFill);
+    }
+
+    async fetchStagedSplit(addr: PublicKey): Promise<StagedSplit> {
+        return this.program.account.stagedSplit.fetch(addr);
+    }
+
     stagedCustodyTokenAddress(stagedAccount: PublicKey): PublicKey {
         return PublicKey.findProgramAddressSync(
             [Buffer.from("staged-custody"), stagedAccount.toBuffer()],
@@ -346,7 +486,7 @@ export class SwapLayerProgram {
         return (
             this.program.methods
                 // @ts-ignore
-                .addPeer(args)
+                .addPeer(toAddPeerArgs(args))
                 .accounts({
                     payer,
                     admin: this.adminComposite(ownerOrAssistant, custodian),
@@ -371,7 +511,7 @@ export class SwapLayerProgram {
         return (
             this.program.methods
                 // @ts-ignore
-                .updatePeer(args)
+                .updatePeer(toAddPeerArgs(args))
                 .accounts({
                     admin: this.ownerOnlyComposite(owner, custodian),
                     peer,
@@ -380,6 +520,30 @@ export class SwapLayerProgram {
         );
     }
 
+    async migratePeerIx(
+        accounts: {
+            ownerOrAssistant: PublicKey;
+            payer?: PublicKey;
+            custodian?: PublicKey;
+            peer?: PublicKey;
+        },
+        chain: ChainId,
+    ) {
+        let { ownerOrAssistant, payer, custodian, peer } = accounts;
+        payer ??= ownerOrAssistant;
+        peer ??= this.peerAddress(chain);
+
+        return this.program.methods
+            .migratePeer(chain)
+            .accounts({
+                payer,
+                admin: this.adminComposite(ownerOrAssistant, custodian),
+                peer,
+                systemProgram: SystemProgram.programId,
+            })
+            .instruction();
+    }
+
     async submitOwnershipTransferIx(accounts: {
         owner: PublicKey;
         newOwner: PublicKey;
@@ -561,7 +725,7 @@ export class SwapLayerProgram {
                 | { relay: { gasDropoff: number; maxRelayerFee: Uint64 } }
                 | { payload: Uint8Array | Buffer }
                 | null;
-            outputToken: OutputToken | null;
+            outputToken: OutputToken | OutputTokenV2 | null;
         },
     ): Promise<[approveIx: TransactionInstruction | null, stageIx: TransactionInstruction]> {
         const { payer, stagedOutbound, usdcRefundToken, peer } = accounts;
@@ -593,14 +757,12 @@ export class SwapLayerProgram {
             }
         })();
 
-        const encodedOutputToken =
-            outputToken === null ? null : Buffer.from(encodeOutputToken(outputToken));
         const ixBuilder = this.program.methods.stageOutbound({
             ...args,
             amountIn: uint64ToBN(amountIn),
             minAmountOut: args.minAmountOut === undefined ? null : uint64ToBN(args.minAmountOut),
             redeemOption,
-            encodedOutputToken,
+            outputToken: outputToken === null ? null : toStagedOutputToken(outputToken),
         });
 
         if (transferType === "native") {
@@ -840,6 +1002,118 @@ export class SwapLayerProgram {
         return [srcCreateAtaIx, dstCreateAtaIx, swapIx];
     }
 
+    async initiateSwapExactOutIxes(
+        accounts: {
+            payer: PublicKey;
+            stagedOutbound: PublicKey;
+            stagedCustodyToken?: PublicKey;
+            preparedOrder?: PublicKey;
+            srcMint?: PublicKey;
+            srcTokenProgram?: PublicKey;
+            preparedBy?: PublicKey;
+            usdcRefundToken?: PublicKey;
+            sender?: PublicKey;
+            senderToken?: PublicKey | null;
+        },
+        args: {
+            cpiInstruction: TransactionInstruction;
+            targetChain?: ChainId;
+        },
+    ): Promise<
+        [
+            TransactionInstruction, // srcCreateAtaIx
+            TransactionInstruction, // dstCreateAtaIx
+            TransactionInstruction, // swapIx
+        ]
+    > {
+        const { payer, stagedOutbound } = accounts;
+        const { cpiInstruction } = args;
+
+        let {
+            stagedCustodyToken,
+            preparedOrder,
+            srcMint,
+            srcTokenProgram,
+            preparedBy,
+            usdcRefundToken,
+            sender,
+            senderToken,
+        } = accounts;
+        srcMint ??= splToken.NATIVE_MINT;
+
+        let { targetChain } = args;
+        if (
+            targetChain === undefined ||
+            usdcRefundToken === undefined ||
+            preparedBy === undefined ||
+            sender === undefined
+        ) {
+            const { info } = await this.fetchStagedOutbound(stagedOutbound);
+            targetChain ??= info.targetChain as ChainId;
+            usdcRefundToken ??= info.usdcRefundToken;
+            preparedBy ??= info.preparedBy;
+            sender ??= info.sender;
+        }
+
+        preparedOrder ??= this.preparedOrderAddress(stagedOutbound);
+        stagedCustodyToken ??= this.stagedCustodyTokenAddress(stagedOutbound);
+
+        const swapAuthority = this.swapAuthorityAddress(preparedOrder);
+        const preparedSwap = await this.prepareSwapAccounts({
+            payer,
+            authority: swapAuthority,
+            srcMint,
+            dstMint: this.usdcMint,
+            srcTokenProgram,
+            dstTokenProgram: splToken.TOKEN_PROGRAM_ID,
+        });
+        const { srcSwapToken, dstSwapToken, srcCreateAtaIx, dstCreateAtaIx } = preparedSwap;
+        srcTokenProgram ??= preparedSwap.srcTokenProgram;
+
+        // Unspent source tokens are refunded to the sender's ATA by default. Only lamports can be
+        // refunded without a sender token account, so the native mint does not require one.
+        if (senderToken === undefined) {
+            senderToken = srcMint.equals(splToken.NATIVE_MINT)
+                ? null
+                : splToken.getAssociatedTokenAddressSync(srcMint, sender, true, srcTokenProgram);
+        }
+
+        const tokenRouter = this.tokenRouterProgram();
+
+        const swapIx = await this.program.methods
+            .initiateSwapExactOut(cpiInstruction.data)
+            .accounts({
+                payer,
+                custodian: this.checkedCustodianComposite(),
+                preparedBy,
+                sender,
+                senderToken,
+                stagedOutbound,
+                stagedCustodyToken,
+                usdcRefundToken,
+                targetPeer: this.registeredPeerComposite({ chain: targetChain }),
+                preparedOrder,
+                swapAuthority,
+                srcSwapToken,
+                dstSwapToken,
+                srcMint,
+                usdc: this.usdcComposite(),
+                tokenRouterCustodian: tokenRouter.custodianAddress(),
+                targetRouterEndpoint: tokenRouter
+                    .matchingEngineProgram()
+                    .routerEndpointAddress(targetChain),
+                preparedCustodyToken: tokenRouter.preparedCustodyTokenAddress(preparedOrder),
+                tokenRouterProgram: tokenRouter.ID,
+                srcTokenProgram,
+                tokenProgram: splToken.TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .remainingAccounts(cpiInstruction.keys)
+            .instruction();
+
+        return [srcCreateAtaIx, dstCreateAtaIx, swapIx];
+    }
+
     async completeTransferRelayIx(
         accounts: {
             payer: PublicKey;
@@ -848,6 +1122,7 @@ export class SwapLayerProgram {
             recipient: PublicKey;
             peer?: PublicKey;
             beneficiary?: PublicKey;
+            integratorFeeToken?: PublicKey | null;
             recipientTokenAccount?: PublicKey;
             feeRecipientToken?: PublicKey;
         },
@@ -859,7 +1134,14 @@ export class SwapLayerProgram {
 
         redeemer ??= payer;
         beneficiary ??= payer;
-        recipientTokenAccount ??= splToken.getAssociatedTokenAddressSync(this.usdcMint, recipient);
+        if (recipientTokenAccount === undefined) {
+            const fallbackRecipientToken = redeemer.equals(recipient)
+                ? null
+                : await this.fallbackRecipientToken(preparedFill);
+            recipientTokenAccount =
+                fallbackRecipientToken ??
+                splToken.getAssociatedTokenAddressSync(this.usdcMint, recipient);
+        }
 
         // Need the undefined check to satisfy the type checker.
         feeRecipientToken ??= await this.fetchCustodian().then((c) => c.feeRecipientToken);
@@ -876,6 +1158,7 @@ export class SwapLayerProgram {
                     {
                         preparedFill,
                         beneficiary,
+                        integratorFeeToken: accounts.integratorFeeToken,
                         sourcePeer: peer,
                     },
                     { sourceChain },
@@ -899,6 +1182,7 @@ export class SwapLayerProgram {
             peer?: PublicKey;
             recipient: PublicKey;
             beneficiary?: PublicKey;
+            integratorFeeToken?: PublicKey | null;
             recipientTokenAccount?: PublicKey;
         },
         sourceChain?: ChainId,
@@ -919,6 +1203,7 @@ export class SwapLayerProgram {
                     {
                         preparedFill,
                         beneficiary,
+                        integratorFeeToken: accounts.integratorFeeToken,
                         sourcePeer: peer,
                     },
                     { sourceChain },
@@ -937,19 +1222,24 @@ export class SwapLayerProgram {
             preparedFill: PublicKey;
             peer?: PublicKey;
             beneficiary?: PublicKey;
+            integratorFeeToken?: PublicKey | null;
+            recipient?: PublicKey | null;
+            feeRecipientToken?: PublicKey | null;
         },
         sourceChain?: ChainId,
     ) {
         const { payer, preparedFill } = accounts;
-        let { redeemer, peer, beneficiary } = accounts;
+        let { redeemer, peer, beneficiary, recipient, feeRecipientToken } = accounts;
 
         redeemer ??= payer;
         beneficiary ??= payer;
+        recipient ??= null;
+        feeRecipientToken ??= null;
 
         const stagedInbound = this.stagedInboundAddress(preparedFill);
         const stagedCustodyToken = this.stagedCustodyTokenAddress(stagedInbound);
 
-        return this.program.methods
+        const ix = await this.program.methods
             .completeTransferPayload()
             .accounts({
                 payer,
@@ -958,17 +1248,28 @@ export class SwapLayerProgram {
                     {
                         preparedFill,
                         beneficiary,
+                        integratorFeeToken: accounts.integratorFeeToken,
                         sourcePeer: peer,
                     },
                     { sourceChain },
                 ),
                 stagedInbound,
                 stagedCustodyToken,
+                recipient,
+                feeRecipientToken,
                 usdc: this.usdcComposite(),
                 tokenProgram: splToken.TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
             })
             .instruction();
+
+        // A recipient redeeming a relayed payload itself (self redemption) does not pay the
+        // relaying fee, which the program detects by the recipient's signature.
+        if (recipient !== null && recipient.equals(redeemer)) {
+            markSigner(ix, recipient);
+        }
+
+        return ix;
     }
 
     async releaseInboundIx(accounts: {
@@ -1012,8 +1313,10 @@ export class SwapLayerProgram {
             dstMint?: PublicKey;
             recipientToken?: PublicKey;
             beneficiary?: PublicKey;
+            integratorFeeToken?: PublicKey | null;
             dstTokenProgram?: PublicKey;
             feeRecipientToken?: PublicKey;
+            recipientUsdcToken?: PublicKey | null;
         },
         args: {
             cpiInstruction: TransactionInstruction;
@@ -1033,6 +1336,11 @@ export class SwapLayerProgram {
         dstMint ??= splToken.NATIVE_MINT;
         feeRecipientToken ??= await this.fetchCustodian().then((c) => c.feeRecipientToken);
 
+        let { recipientUsdcToken } = accounts;
+        if (recipientUsdcToken === undefined) {
+            recipientUsdcToken = await this.exactOutRecipientUsdcToken(preparedFill);
+        }
+
         const swapAuthority = this.swapAuthorityAddress(preparedFill);
         const preparedSwap = await this.prepareSwapAccounts({
             payer,
@@ -1059,11 +1367,13 @@ export class SwapLayerProgram {
                     consumeSwapLayerFill: await this.consumeSwapLayerFillComposite({
                         preparedFill,
                         beneficiary,
+                        integratorFeeToken: accounts.integratorFeeToken,
                     }),
                     authority: swapAuthority,
                     srcSwapToken,
                     dstSwapToken,
                     feeRecipientToken,
+                    recipientUsdcToken,
                     usdc: this.usdcComposite(),
                     dstMint,
                     tokenProgram: splToken.TOKEN_PROGRAM_ID,
@@ -1086,8 +1396,10 @@ export class SwapLayerProgram {
             recipient: PublicKey;
             dstMint?: PublicKey;
             beneficiary?: PublicKey;
+            integratorFeeToken?: PublicKey | null;
             dstTokenProgram?: PublicKey;
             feeRecipientToken?: PublicKey;
+            recipientUsdcToken?: PublicKey | null;
             recipientToken?: PublicKey;
         },
         args: {
@@ -1108,6 +1420,11 @@ export class SwapLayerProgram {
         dstMint ??= splToken.NATIVE_MINT;
         feeRecipientToken ??= await this.fetchCustodian().then((c) => c.feeRecipientToken);
 
+        let { recipientUsdcToken } = accounts;
+        if (recipientUsdcToken === undefined) {
+            recipientUsdcToken = await this.exactOutRecipientUsdcToken(preparedFill);
+        }
+
         const swapAuthority = this.swapAuthorityAddress(preparedFill);
         const preparedSwap = await this.prepareSwapAccounts({
             payer,
@@ -1134,11 +1451,13 @@ export class SwapLayerProgram {
                     consumeSwapLayerFill: await this.consumeSwapLayerFillComposite({
                         preparedFill,
                         beneficiary,
+                        integratorFeeToken: accounts.integratorFeeToken,
                     }),
                     authority: swapAuthority,
                     srcSwapToken,
                     dstSwapToken,
                     feeRecipientToken,
+                    recipientUsdcToken,
                     usdc: this.usdcComposite(),
                     dstMint,
                     tokenProgram: splToken.TOKEN_PROGRAM_ID,
@@ -1160,8 +1479,11 @@ export class SwapLayerProgram {
             preparedFill: PublicKey;
             dstMint?: PublicKey;
             beneficiary?: PublicKey;
+            integratorFeeToken?: PublicKey | null;
             dstTokenProgram?: PublicKey;
             feeRecipientToken?: PublicKey;
+            recipientUsdcToken?: PublicKey | null;
+            recipient?: PublicKey | null;
         },
         args: {
             cpiInstruction: TransactionInstruction;
@@ -1176,10 +1498,16 @@ export class SwapLayerProgram {
         const { payer, preparedFill } = accounts;
         const { cpiInstruction } = args;
 
-        let { beneficiary, dstMint, dstTokenProgram, feeRecipientToken } = accounts;
+        let { beneficiary, dstMint, dstTokenProgram, feeRecipientToken, recipient } = accounts;
         beneficiary ??= payer;
+        recipient ??= null;
         dstMint ??= splToken.NATIVE_MINT;
 
+        let { recipientUsdcToken } = accounts;
+        if (recipientUsdcToken === undefined) {
+            recipientUsdcToken = await this.exactOutRecipientUsdcToken(preparedFill);
+        }
+
         const stagedInbound = this.stagedInboundAddress(preparedFill);
         const preparedSwap = await this.prepareSwapAccounts({
             payer,
@@ -1200,11 +1528,14 @@ export class SwapLayerProgram {
                 consumeSwapLayerFill: await this.consumeSwapLayerFillComposite({
                     preparedFill,
                     beneficiary,
+                    integratorFeeToken: accounts.integratorFeeToken,
                 }),
                 stagedInbound,
                 srcSwapToken,
                 dstSwapToken,
                 feeRecipientToken,
+                recipientUsdcToken,
+                recipient,
                 usdc: this.usdcComposite(),
                 dstMint,
                 tokenProgram: splToken.TOKEN_PROGRAM_ID,
@@ -1214,6 +1545,189 @@ export class SwapLayerProgram {
             .remainingAccounts(cpiInstruction.keys)
             .instruction();
 
+        // A recipient redeeming a relayed payload itself (self redemption) does not pay the
+        // relaying fee, which the program detects by the recipient's signature.
+        if (recipient !== null && recipient.equals(payer)) {
+            markSigner(swapIx, recipient);
+        }
+
+        return [srcCreateAtaIx, dstCreateAtaIx, swapIx];
+    }
+
+    async stageSplitIx(
+        accounts: {
+            payer: PublicKey;
+            preparedFill: PublicKey;
+            peer?: PublicKey;
+            beneficiary?: PublicKey;
+            integratorFeeToken?: PublicKey | null;
+            recipient?: PublicKey | null;
+            feeRecipientToken?: PublicKey | null;
+        },
+        sourceChain?: ChainId,
+    ) {
+        const { payer, preparedFill } = accounts;
+        let { peer, beneficiary, recipient, feeRecipientToken } = accounts;
+
+        beneficiary ??= payer;
+        recipient ??= null;
+        feeRecipientToken ??= null;
+
+        const stagedSplit = this.stagedSplitAddress(preparedFill);
+
+        return this.program.methods
+            .stageSplit()
+            .accounts({
+                payer,
+                consumeSwapLayerFill: await this.consumeSwapLayerFillComposite(
+                    {
+                        preparedFill,
+                        beneficiary,
+                        integratorFeeToken: accounts.integratorFeeToken,
+                        sourcePeer: peer,
+                    },
+                    { sourceChain },
+                ),
+                stagedSplit,
+                stagedCustodyToken: this.stagedCustodyTokenAddress(stagedSplit),
+                recipient,
+                feeRecipientToken,
+                usdc: this.usdcComposite(),
+                tokenProgram: splToken.TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .instruction();
+    }
+
+    async completeSplitTransferIx(
+        accounts: {
+            redeemer: PublicKey;
+            preparedFill: PublicKey;
+            stagedBy?: PublicKey;
+            feeRecipientToken?: PublicKey;
+            recipientTokenAccount?: PublicKey;
+        },
+        index: number,
+    ) {
+        const { redeemer, preparedFill } = accounts;
+
+        let { recipientTokenAccount } = accounts;
+        if (recipientTokenAccount === undefined) {
+            const { outputs } = await this.fetchStagedSplit(this.stagedSplitAddress(preparedFill));
+            if (index >= outputs.length) {
+                throw new Error("split output index out of range");
+            }
+
+            // The recipient redeeming USDC itself always receives it.
+            const { recipient, usdcRecipient } = outputs[index];
+            recipientTokenAccount = splToken.getAssociatedTokenAddressSync(
+                this.usdcMint,
+                redeemer.equals(recipient) ? recipient : usdcRecipient,
+                true, // allowOwnerOffCurve
+            );
+        }
+
+        return this.program.methods
+            .completeSplitTransfer(index)
+            .accounts({
+                redeemer,
+                consumeStagedSplit: await this.consumeStagedSplitComposite(accounts),
+                recipientTokenAccount,
+            })
+            .instruction();
+    }
+
+    async completeSplitSwapIxes(
+        accounts: {
+            payer: PublicKey;
+            preparedFill: PublicKey;
+            recipient?: PublicKey;
+            dstMint?: PublicKey;
+            stagedBy?: PublicKey;
+            dstTokenProgram?: PublicKey;
+            feeRecipientToken?: PublicKey;
+            recipientUsdcToken?: PublicKey | null;
+            recipientToken?: PublicKey;
+        },
+        args: {
+            index: number;
+            cpiInstruction: TransactionInstruction;
+        },
+    ): Promise<
+        [
+            TransactionInstruction, // srcCreateAtaIx
+            TransactionInstruction, // dstCreateAtaIx
+            TransactionInstruction, // swapIx
+        ]
+    > {
+        const { payer, preparedFill } = accounts;
+        const { index, cpiInstruction } = args;
+
+        let { recipient, dstMint, dstTokenProgram, recipientUsdcToken, recipientToken } = accounts;
+        dstMint ??= splToken.NATIVE_MINT;
+
+        if (recipient === undefined || recipientUsdcToken === undefined) {
+            const { outputs } = await this.fetchStagedSplit(this.stagedSplitAddress(preparedFill));
+            if (index >= outputs.length) {
+                throw new Error("split output index out of range");
+            }
+            const output = outputs[index];
+            recipient ??= output.recipient;
+
+            // USDC not spent by an exact out swap is sent to the output recipient's USDC ATA.
+            if (recipientUsdcToken === undefined) {
+                const { outputToken } = output;
+                const isExactOut =
+                    ("gas" in outputToken && "exactOut" in outputToken.gas[0].mode) ||
+                    ("other" in outputToken && "exactOut" in outputToken.other.swap.mode);
+                recipientUsdcToken = isExactOut
+                    ? splToken.getAssociatedTokenAddressSync(
+                          this.usdcMint,
+                          output.recipient,
+                          true, // allowOwnerOffCurve
+                          splToken.TOKEN_PROGRAM_ID,
+                      )
+                    : null;
+            }
+        }
+
+        const swapAuthority = this.swapAuthorityAddress(preparedFill);
+        const preparedSwap = await this.prepareSwapAccounts({
+            payer,
+            authority: swapAuthority,
+            srcMint: this.usdcMint,
+            dstMint,
+            srcTokenProgram: splToken.TOKEN_PROGRAM_ID,
+            dstTokenProgram,
+        });
+        const { srcSwapToken, dstSwapToken, srcCreateAtaIx, dstCreateAtaIx } = preparedSwap;
+        dstTokenProgram ??= preparedSwap.dstTokenProgram;
+        recipientToken ??= splToken.getAssociatedTokenAddressSync(
+            dstMint,
+            recipient,
+            true,
+            dstTokenProgram,
+        );
+
+        const swapIx = await this.program.methods
+            .completeSplitSwap(index, cpiInstruction.data)
+            .accounts({
+                payer,
+                consumeStagedSplit: await this.consumeStagedSplitComposite(accounts),
+                authority: swapAuthority,
+                srcSwapToken,
+                dstSwapToken,
+                recipientUsdcToken,
+                recipientToken,
+                recipient,
+                usdc: this.usdcComposite(),
+                dstMint,
+                dstTokenProgram,
+                systemProgram: SystemProgram.programId,
+            })
+            .remainingAccounts(cpiInstruction.keys)
+            .instruction();
+
         return [srcCreateAtaIx, dstCreateAtaIx, swapIx];
     }
 
@@ -1233,6 +1747,16 @@ export class SwapLayerProgram {
     }
 }
 
+function toAddPeerArgs(args: AddPeerArgs) {
+    const { chain, address, relayParams, maxIntegratorFee } = args;
+    return {
+        chain,
+        address,
+        relayParams,
+        maxIntegratorFee: uint64ToBN(maxIntegratorFee ?? 0n),
+    };
+}
+
 export function localnet(): ProgramId {
     return "SwapLayer1111111111111111111111111111111111";
 }
@@ -14,10 +14,16 @@ import { layoutItems } from "@wormhole-foundation/sdk-definitions";
 import { EvmAddress } from "@wormhole-foundation/sdk-evm";
 
 export type SwapLayerMessage = LayoutToType<typeof swapLayerMessageLayout>;
+export type SwapLayerMessageV2 = LayoutToType<typeof swapLayerMessageV2Layout>;
 export type OutputToken = LayoutToType<typeof outputTokenItem>;
+export type OutputTokenV2 = LayoutToType<typeof outputTokenV2Item>;
 export type RedeemMode = LayoutToType<typeof redeemModeItem>;
+export type RedeemModeV2 = LayoutToType<typeof redeemModeV2Item>;
+export type SwapMode = "ExactIn" | "ExactOut";
+export type SplitOutput = LayoutToType<typeof splitOutputLayout>;
 export type SwapType = LayoutToType<typeof swapItem>;
 export type JupiterV6SwapParameters = LayoutToType<typeof jupiterV6SwapParametersLayout>;
+export type OrcaWhirlpoolSwapParameters = LayoutToType<typeof orcaWhirlpoolSwapParametersLayout>;
 
 export const decodeSwapLayerMessage = (encoded: Uint8Array): SwapLayerMessage =>
     deserializeLayout(swapLayerMessageLayout, encoded);
@@ -28,6 +34,35 @@ export const encodeSwapLayerMessage = (message: SwapLayerMessage): Uint8Array =>
 export const encodeOutputToken = (outputToken: OutputToken): Uint8Array =>
     serializeLayout(outputTokenItem, outputToken);
 
+export const decodeSwapLayerMessageV2 = (encoded: Uint8Array): SwapLayerMessageV2 =>
+    deserializeLayout(swapLayerMessageV2Layout, encoded);
+
+export const encodeSwapLayerMessageV2 = (message: SwapLayerMessageV2): Uint8Array =>
+    serializeLayout(swapLayerMessageV2Layout, message);
+
+/**
+ * Decode a swap message of either version. Version 1 messages are returned as the equivalent
+ * version 2 message, whose swaps are exact in and which has none of the fields added by version 2.
+ */
+export function decodeAnySwapLayerMessage(encoded: Uint8Array): SwapLayerMessageV2 {
+    if (encoded[0] === 2) {
+        return decodeSwapLayerMessageV2(encoded);
+    }
+
+    const { recipient, redeemMode, outputToken } = decodeSwapLayerMessage(encoded);
+    return {
+        recipient,
+        redeemMode,
+        outputToken:
+            outputToken.type === "Usdc"
+                ? outputToken
+                : { ...outputToken, swap: { ...outputToken.swap, mode: "ExactIn" } },
+        fallbackRecipient: { isSome: false },
+        integratorFee: { isSome: false },
+        splitOutputs: [],
+    };
+}
+
 const forceBigIntConversion = {
     custom: {
         to: (encoded: number) => BigInt(encoded),
@@ -111,23 +146,69 @@ const jupiterV6SwapParametersLayout = [
     },
 ] as const satisfies Layout;
 
+const orcaWhirlpoolSwapParametersLayout = [
+    {
+        name: "pools",
+        binary: "array",
+        lengthSize: 1,
+        layout: [
+            { name: "whirlpool", ...layoutItems.universalAddressItem },
+            { name: "aToB", ...boolItem },
+        ],
+    },
+] as const satisfies Layout;
+
 const [swapTypes, swapItemLayouts] = [
     [
         [1, "UniswapV3"],
         [2, "TraderJoe"],
         [16, "JupiterV6"],
+        [18, "OrcaWhirlpool"],
+    ],
+    [
+        sharedUniswapTraderJoeLayout,
+        sharedUniswapTraderJoeLayout,
+        jupiterV6SwapParametersLayout,
+        orcaWhirlpoolSwapParametersLayout,
     ],
-    [sharedUniswapTraderJoeLayout, sharedUniswapTraderJoeLayout, jupiterV6SwapParametersLayout],
 ] as const;
 
+const swapLayout = [
+    { name: "deadline", ...timestampItem },
+    { name: "limitAmount", ...amountItem },
+    { name: "type", binary: "switch", idSize: 1, layouts: zip([swapTypes, swapItemLayouts]) },
+] as const satisfies Layout;
+
 const swapItem = {
     name: "swap",
     binary: "bytes",
-    layout: [
-        { name: "deadline", ...timestampItem },
-        { name: "limitAmount", ...amountItem },
-        { name: "type", binary: "switch", idSize: 1, layouts: zip([swapTypes, swapItemLayouts]) },
-    ],
+    layout: swapLayout,
+} as const satisfies NamedLayoutItem;
+
+// Version 2 encodes whether the limit amount is the minimum amount out (exact in) or the exact
+// amount out (exact out) after the swap type.
+const swapModeItem = {
+    binary: "uint",
+    size: 1,
+    custom: {
+        to: (encoded: number): SwapMode => {
+            switch (encoded) {
+                case 0:
+                    return "ExactIn";
+                case 1:
+                    return "ExactOut";
+                default:
+                    throw new Error(`Unknown swap mode: ${encoded}`);
+            }
+        },
+        from: (mode: SwapMode): number => (mode === "ExactOut" ? 1 : 0),
+    } satisfies CustomConversion<number, SwapMode>,
+} as const satisfies UintLayoutItem;
+
+const swapV2Item = {
+    name: "swap",
+    binary: "bytes",
+    layout: [...swapLayout, { name: "mode", ...swapModeItem }],
 } as const satisfies NamedLayoutItem;
 
 const [ioTokenTypes, outputTokenLayouts] = [
@@ -147,6 +228,17 @@ const outputTokenItem = {
     layouts: zip([ioTokenTypes, outputTokenLayouts]),
 } as const satisfies NamedLayoutItem;
 
+const outputTokenV2Item = {
+    name: "outputToken",
+    binary: "switch",
+    idSize: 1,
+    idTag: "type",
+    layouts: zip([
+        ioTokenTypes,
+        [[], [swapV2Item], [{ name: "address", ...layoutItems.universalAddressItem }, swapV2Item]],
+    ]),
+} as const satisfies NamedLayoutItem;
+
 // ---- message layout ----
 
 const swapLayerMessageLayout = [
@@ -155,3 +247,71 @@ const swapLayerMessageLayout = [
     redeemModeItem,
     outputTokenItem,
 ] as const satisfies Layout;
+
+const redeemModeV2Item = {
+    ...redeemModeItem,
+    layouts: [
+        ...redeemModeItem.layouts,
+        [
+            [3, "RelayWithPayload"],
+            [
+                { name: "gasDropoff", ...gasDropoffItem },
+                { name: "relayingFee", binary: "uint", size: 6, ...forceBigIntConversion },
+                { name: "sender", ...layoutItems.universalAddressItem },
+                { name: "buf", binary: "bytes", lengthSize: 2 },
+            ],
+        ],
+    ],
+} as const satisfies NamedLayoutItem;
+
+const integratorFeeAmountItem = {
+    name: "amount",
+    binary: "switch",
+    idSize: 1,
+    idTag: "type",
+    layouts: [
+        [[0, "Bps"], [{ name: "bps", binary: "uint", size: 2 }]],
+        [[1, "Absolute"], [{ name: "amount", binary: "uint", size: 6, ...forceBigIntConversion }]],
+    ],
+} as const satisfies NamedLayoutItem;
+
+const splitOutputLayout = [
+    { name: "recipient", ...layoutItems.universalAddressItem },
+    { name: "bps", binary: "uint", size: 2 },
+    outputTokenV2Item,
+] as const satisfies Layout;
+
+// Version 2 begins with the fields of version 1 (with the swap mode added to output swaps).
+const swapLayerMessageV2Layout = [
+    { name: "version", binary: "uint", size: 1, custom: 2, omit: true },
+    { name: "recipient", ...layoutItems.universalAddressItem },
+    redeemModeV2Item,
+    outputTokenV2Item,
+    {
+        name: "fallbackRecipient",
+        binary: "switch",
+        idSize: 1,
+        idTag: "isSome",
+        layouts: [
+            [[0, false], []],
+            [[1, true], [{ name: "address", ...layoutItems.universalAddressItem }]],
+        ],
+    },
+    {
+        name: "integratorFee",
+        binary: "switch",
+        idSize: 1,
+        idTag: "isSome",
+        layouts: [
+            [[0, false], []],
+            [
+                [1, true],
+                [
+                    { name: "recipient", ...layoutItems.universalAddressItem },
+                    integratorFeeAmountItem,
+                ],
+            ],
+        ],
+    },
+    { name: "splitOutputs", binary: "array", lengthSize: 1, layout: splitOutputLayout },
+] as const satisfies Layout;
@@ -32,11 +32,18 @@ export class Peer {
     seeds: PeerSeeds;
     address: Array<number>;
     relayParams: RelayParams;
+    maxIntegratorFee: BN;
 
-    constructor(seeds: PeerSeeds, address: Array<number>, relayParams: RelayParams) {
+    constructor(
+        seeds: PeerSeeds,
+        address: Array<number>,
+        relayParams: RelayParams,
+        maxIntegratorFee: BN,
+    ) {
         this.seeds = seeds;
         this.address = address;
         this.relayParams = relayParams;
+        this.maxIntegratorFee = maxIntegratorFee;
     }
 
     static address(programId: PublicKey, chain: number) {
@@ -1,5 +1,7 @@
 import { BN } from "@coral-xyz/anchor";
 import { PublicKey } from "@solana/web3.js";
+import { EvmAddress } from "@wormhole-foundation/sdk-evm";
+import { OutputToken, OutputTokenV2 } from "../messages";
 
 export type RedeemOption =
     | {
@@ -12,6 +14,47 @@ export type RedeemOption =
           payload: [Buffer];
       };
 
+export type StagedSwapType =
+    | { invalid: {} }
+    | { uniswapV3: { 0: StagedUniswapSwapParameters } }
+    | { traderJoe: { 0: StagedTraderJoeSwapParameters } }
+    | { jupiterV6: { 0: StagedJupiterV6SwapParameters } }
+    | { orcaWhirlpool: { 0: StagedOrcaWhirlpoolSwapParameters } };
+
+export type StagedUniswapSwapParameters = {
+    firstLegFee: number;
+    path: { evmAddress: Array<number>; fee: number }[];
+};
+
+export type StagedTraderJoePoolId = { version: number; binSize: number };
+
+export type StagedTraderJoeSwapParameters = {
+    firstPoolId: StagedTraderJoePoolId;
+    path: { evmAddress: Array<number>; poolId: StagedTraderJoePoolId }[];
+};
+
+export type StagedJupiterV6SwapParameters = {
+    dexProgramId: Array<number> | null;
+};
+
+export type StagedOrcaWhirlpoolSwapParameters = {
+    pools: { whirlpool: Array<number>; aToB: boolean }[];
+};
+
+export type StagedSwapMode = { exactIn: {} } | { exactOut: {} };
+
+export type StagedOutputSwap = {
+    deadline: number;
+    limitAmount: BN;
+    swapType: StagedSwapType;
+    mode: StagedSwapMode;
+};
+
+export type StagedOutputToken =
+    | { usdc: {} }
+    | { gas: { 0: StagedOutputSwap } }
+    | { other: { address: Array<number>; swap: StagedOutputSwap } };
+
 export type StagedRedeem =
     | { direct: {} }
     | { relay: { gasDropoff: number; relayingFee: BN } }
@@ -31,11 +74,114 @@ export type StagedOutboundInfo = {
 export class StagedOutbound {
     info: StagedOutboundInfo;
     stagedRedeem: StagedRedeem;
-    encodedOutputToken: Buffer;
+    outputToken: StagedOutputToken;
 
-    constructor(info: StagedOutboundInfo, stagedRedeem: StagedRedeem, encodedOutputToken: Buffer) {
+    constructor(
+        info: StagedOutboundInfo,
+        stagedRedeem: StagedRedeem,
+        outputToken: StagedOutputToken,
+    ) {
         this.info = info;
         this.stagedRedeem = stagedRedeem;
-        this.encodedOutputToken = encodedOutputToken;
+        this.outputToken = outputToken;
+    }
+}
+
+/**
+ * Convert an output token (as it is encoded in the swap message) into the form expected by the
+ * Swap Layer program's instruction arguments and stored in the staged outbound account.
+ */
+export function toStagedOutputToken(outputToken: OutputToken | OutputTokenV2): StagedOutputToken {
+    const toStagedSwap = (
+        swap: Extract<OutputToken | OutputTokenV2, { type: "Gas" | "Other" }>,
+    ): StagedOutputSwap => {
+        const { deadline, limitAmount, type: swapType } = swap.swap;
+        // Version 1 output swaps are always exact in.
+        const isExactOut = "mode" in swap.swap && swap.swap.mode === "ExactOut";
+        const evmAddress = (address: string) =>
+            Array.from(new EvmAddress(address).toUint8Array());
+        const poolId = (value: number): StagedTraderJoePoolId => ({
+            version: value >> 16,
+            binSize: value & 0xffff,
+        });
+
+        return {
+            deadline,
+            limitAmount: new BN(limitAmount.toString()),
+            swapType: (() => {
+                switch (swapType.id) {
+                    case "UniswapV3": {
+                        const { firstPoolId, path } = swapType;
+                        return {
+                            uniswapV3: {
+                                0: {
+                                    firstLegFee: firstPoolId,
+                                    path: path.map(({ address, poolId }) => ({
+                                        evmAddress: evmAddress(address),
+                                        fee: poolId,
+                                    })),
+                                },
+                            },
+                        };
+                    }
+                    case "TraderJoe": {
+                        const { firstPoolId, path } = swapType;
+                        return {
+                            traderJoe: {
+                                0: {
+                                    firstPoolId: poolId(firstPoolId),
+                                    path: path.map((hop) => ({
+                                        evmAddress: evmAddress(hop.address),
+                                        poolId: poolId(hop.poolId),
+                                    })),
+                                },
+                            },
+                        };
+                    }
+                    case "JupiterV6": {
+                        const { dexProgramId } = swapType;
+                        return {
+                            jupiterV6: {
+                                0: {
+                                    dexProgramId: dexProgramId.isSome
+                                        ? Array.from(dexProgramId.address.toUint8Array())
+                                        : null,
+                                },
+                            },
+                        };
+                    }
+                    case "OrcaWhirlpool": {
+                        return {
+                            orcaWhirlpool: {
+                                0: {
+                                    pools: swapType.pools.map(({ whirlpool, aToB }) => ({
+                                        whirlpool: Array.from(whirlpool.toUint8Array()),
+                                        aToB,
+                                    })),
+                                },
+                            },
+                        };
+                    }
+                }
+            })(),
+            mode: isExactOut ? { exactOut: {} } : { exactIn: {} },
+        };
+    };
+
+    switch (outputToken.type) {
+        case "Usdc": {
+            return { usdc: {} };
+        }
+        case "Gas": {
+            return { gas: { 0: toStagedSwap(outputToken) } };
+        }
+        case "Other": {
+            return {
+                other: {
+                    address: Array.from(outputToken.address.toUint8Array()),
+                    swap: toStagedSwap(outputToken),
+                },
+            };
+        }
     }
 }
@@ -0,0 +1,42 @@
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+import { StagedOutputToken } from "./StagedOutbound";
+
+export type StagedSplitSeeds = {
+    preparedFill: PublicKey;
+    bump: number;
+};
+
+export type StagedSplitInfo = {
+    custodyTokenBump: number;
+    stagedBy: PublicKey;
+    sourceChain: number;
+    swapTimeLimitEnd: BN | null;
+};
+
+export type StagedSplitOutput = {
+    recipient: PublicKey;
+    usdcRecipient: PublicKey;
+    amount: BN;
+    outputToken: StagedOutputToken;
+    isCompleted: boolean;
+};
+
+export class StagedSplit {
+    seeds: StagedSplitSeeds;
+    info: StagedSplitInfo;
+    outputs: StagedSplitOutput[];
+
+    constructor(seeds: StagedSplitSeeds, info: StagedSplitInfo, outputs: StagedSplitOutput[]) {
+        this.seeds = seeds;
+        this.info = info;
+        this.outputs = outputs;
+    }
+
+    static address(programId: PublicKey, preparedFill: PublicKey) {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("staged-split"), preparedFill.toBuffer()],
+            programId,
+        )[0];
+    }
+}
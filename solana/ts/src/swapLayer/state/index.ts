@@ -2,3 +2,4 @@ export * from "./Custodian";
 export * from "./Peer";
 export * from "./StagedInbound";
 export * from "./StagedOutbound";
+export * from "./StagedSplit";
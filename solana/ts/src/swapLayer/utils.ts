@@ -1,4 +1,4 @@
-import { PublicKey } from "@solana/web3.js";
+import { PublicKey, TransactionInstruction } from "@solana/web3.js";
 
 export const BPF_LOADER_UPGRADEABLE_PROGRAM_ID = new PublicKey(
     "BPFLoaderUpgradeab1e11111111111111111111111",
@@ -10,3 +10,15 @@ export function programDataAddress(programId: PublicKey) {
         BPF_LOADER_UPGRADEABLE_PROGRAM_ID,
     )[0];
 }
+
+/**
+ * Mark an account of the instruction as a signer. The IDL does not mark accounts that the program
+ * only checks for a signature in some cases (e.g. a recipient redeeming a relayed fill itself).
+ */
+export function markSigner(ix: TransactionInstruction, pubkey: PublicKey) {
+    for (const key of ix.keys) {
+        if (key.pubkey.equals(pubkey)) {
+            key.isSigner = true;
+        }
+    }
+}
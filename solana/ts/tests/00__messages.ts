@@ -2,8 +2,17 @@ import { SYSVAR_CLOCK_PUBKEY, SYSVAR_EPOCH_SCHEDULE_PUBKEY } from "@solana/web3.
 import { encoding } from "@wormhole-foundation/sdk-base";
 import { toUniversal } from "@wormhole-foundation/sdk-definitions";
 import { expect } from "chai";
-import { SwapLayerMessage, decodeSwapLayerMessage, encodeSwapLayerMessage } from "../src/swapLayer";
+import {
+    SwapLayerMessage,
+    SwapLayerMessageV2,
+    decodeAnySwapLayerMessage,
+    decodeSwapLayerMessage,
+    decodeSwapLayerMessageV2,
+    encodeSwapLayerMessage,
+    encodeSwapLayerMessageV2,
+} from "../src/swapLayer";
 import { USDC_MINT_ADDRESS } from "@wormhole-foundation/example-liquidity-layer-solana/testing";
+import { USDT_MINT_ADDRESS, WHIRLPOOL_USDC_USDT } from "./helpers";
 
 describe("Swap Layer Messages", () => {
     it("USDC Direct", function () {
@@ -106,6 +115,50 @@ describe("Swap Layer Messages", () => {
         expect(encodeSwapLayerMessage(decoded)).to.eql(encoded);
     });
 
+    it("Jupiter V6 Exact Out Swap Direct Gas (Version 2)", function () {
+        const encoded = encoding.hex.decode(
+            "0206a7d51718c774c928566398691d5eb68b5eb8a39b4b6d5c73555b2100000000000100bc614e0000000000000000000000003b9ac9ff100001000000",
+        );
+
+        const decoded = decodeSwapLayerMessageV2(encoded);
+        expect(decoded).to.eql({
+            recipient: toUniversal("Solana", SYSVAR_CLOCK_PUBKEY.toBytes()),
+            redeemMode: { mode: "Direct" },
+            outputToken: {
+                type: "Gas",
+                swap: {
+                    deadline: 12345678,
+                    limitAmount: 999999999n,
+                    type: {
+                        id: "JupiterV6",
+                        dexProgramId: { isSome: false },
+                    },
+                    mode: "ExactOut",
+                },
+            },
+            fallbackRecipient: { isSome: false },
+            integratorFee: { isSome: false },
+            splitOutputs: [],
+        } as SwapLayerMessageV2);
+        expect(encodeSwapLayerMessageV2(decoded)).to.eql(encoded);
+        expect(decodeAnySwapLayerMessage(encoded)).to.eql(decoded);
+
+        // The same message in version 1 cannot encode the swap mode, so its swap is exact in.
+        const v1Encoded = Uint8Array.from([1, ...encoded.subarray(1, encoded.length - 4)]);
+        expect(decodeAnySwapLayerMessage(v1Encoded).outputToken).to.eql({
+            type: "Gas",
+            swap: {
+                deadline: 12345678,
+                limitAmount: 999999999n,
+                type: {
+                    id: "JupiterV6",
+                    dexProgramId: { isSome: false },
+                },
+                mode: "ExactIn",
+            },
+        });
+    });
+
     it("Jupiter V6 Swap Direct Other (Some Dex)", function () {
         const encoded = encoding.hex.decode(
             "0106a7d51718c774c928566398691d5eb68b5eb8a39b4b6d5c73555b21000000000002c6fa7af3bedbad3a3d65f36aabc97431b1bbe4c2d2f6e0e47ca60203452f5d6100bc614e0000000000000000000000003b9ac9ff100106a7d51718dc3fee02d3e47f0100f8b054f7942e60591e3f508719a805000000",
@@ -133,4 +186,41 @@ describe("Swap Layer Messages", () => {
         } as SwapLayerMessage);
         expect(encodeSwapLayerMessage(decoded)).to.eql(encoded);
     });
+
+    it("Orca Whirlpool Swap Direct Other (Two Hop)", function () {
+        const encoded = encoding.hex.decode(
+            "0106a7d51718c774c928566398691d5eb68b5eb8a39b4b6d5c73555b21000000000002ce010e60afedb22717bd63192f54145a3f965a33bb82d2c7029eb2ce1e20826400bc614e0000000000000000000000003b9ac9ff1202368a77eba5a6fe0fc958ddc9f4b8a6b10fbc1b3503b5d88131beb3d44074c3130006a7d51718dc3fee02d3e47f0100f8b054f7942e60591e3f508719a80500000001",
+        );
+
+        const decoded = decodeSwapLayerMessage(encoded);
+        expect(decoded).to.eql({
+            recipient: toUniversal("Solana", SYSVAR_CLOCK_PUBKEY.toBytes()),
+            redeemMode: { mode: "Direct" },
+            outputToken: {
+                type: "Other",
+                address: toUniversal("Solana", USDT_MINT_ADDRESS.toBytes()),
+                swap: {
+                    deadline: 12345678,
+                    limitAmount: 999999999n,
+                    type: {
+                        id: "OrcaWhirlpool",
+                        pools: [
+                            {
+                                whirlpool: toUniversal("Solana", WHIRLPOOL_USDC_USDT.toBytes()),
+                                aToB: false,
+                            },
+                            {
+                                whirlpool: toUniversal(
+                                    "Solana",
+                                    SYSVAR_EPOCH_SCHEDULE_PUBKEY.toBytes(),
+                                ),
+                                aToB: true,
+                            },
+                        ],
+                    },
+                },
+            },
+        } as SwapLayerMessage);
+        expect(encodeSwapLayerMessage(decoded)).to.eql(encoded);
+    });
 });
@@ -40,19 +40,24 @@ import {
     AddPeerArgs,
     Custodian,
     OutputToken,
+    OutputTokenV2,
     Peer,
+    RedeemModeV2,
     RelayParams,
+    SplitOutput,
     StagedInbound,
     StagedOutbound,
+    StagedSplit,
     SwapLayerProgram,
     TEST_RELAY_PARAMS,
     U32_MAX,
     UpdateRelayParametersArgs,
     calculateRelayerFee,
     denormalizeGasDropOff,
-    encodeOutputToken,
     encodeSwapLayerMessage,
+    encodeSwapLayerMessageV2,
     localnet,
+    toStagedOutputToken,
 } from "../src/swapLayer";
 import { FEE_UPDATER_KEYPAIR, REGISTERED_PEERS, createLut, tryNativeToUint8Array } from "./helpers";
 
@@ -77,7 +82,7 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
     const foreignTokenRouterAddress = REGISTERED_TOKEN_ROUTERS["Ethereum"]!;
     const foreignSwapLayerAddress = REGISTERED_PEERS["Ethereum"]!;
     const foreignRecipientAddress = Array.from(
-        Buffer.alloc(32, "0000000000000000000000beefdeadCf7178C407aA7369b67CB7edeadbeef", "hex"),
+        Buffer.from("000000000000000000000000beefdeadCf7178C407aA7369b67CB7edeadbeef", "hex"),
     );
 
     // Program SDKs
@@ -383,6 +388,7 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
                             { chain: foreignChain, bump: seeds.bump },
                             foreignRecipientAddress,
                             startParams,
+                            new BN(0),
                         ),
                     );
                 });
@@ -539,10 +545,41 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
                             { chain: foreignChain, bump: seeds.bump },
                             foreignSwapLayerAddress,
                             TEST_RELAY_PARAMS,
+                            new BN(0),
                         ),
                     );
                 });
             });
+
+            describe("Migrate", () => {
+                it("Cannot Migrate Peer (Only Owner or Assistant)", async () => {
+                    const ix = await swapLayer.migratePeerIx(
+                        { ownerOrAssistant: feeUpdater.publicKey, payer: payer.publicKey },
+                        foreignChain,
+                    );
+                    await expectIxErr(
+                        connection,
+                        [ix],
+                        [payer, feeUpdater],
+                        "Error Code: OwnerOrAssistantOnly",
+                    );
+                });
+
+                it("Migrate Peer As Owner", async () => {
+                    const peerAddress = swapLayer.peerAddress(foreignChain);
+                    const { data: dataBefore } = (await connection.getAccountInfo(peerAddress))!;
+
+                    // The peer already has room for the max integrator fee, so nothing changes.
+                    const ix = await swapLayer.migratePeerIx(
+                        { ownerOrAssistant: payer.publicKey },
+                        foreignChain,
+                    );
+                    await expectIxOk(connection, [ix], [payer]);
+
+                    const { data: dataAfter } = (await connection.getAccountInfo(peerAddress))!;
+                    assert.deepEqual(dataAfter, dataBefore);
+                });
+            });
         });
 
         describe("Ownership Transfer Request", async function () {
@@ -1112,7 +1149,7 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
     describe("Business Logic", function () {
         describe("Stage Outbound", function () {
             describe("Native", function () {
-                it("Cannot Stage Outbound (Exact In Required)", async function () {
+                it("Cannot Stage Outbound (Exact Out Zero Min Amount Out)", async function () {
                     const stagedOutboundSigner = Keypair.generate();
                     const stagedOutbound = stagedOutboundSigner.publicKey;
 
@@ -1130,7 +1167,7 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
                         {
                             transferType: "native",
                             amountIn,
-                            minAmountOut: 1n,
+                            minAmountOut: 0n,
                             isExactIn: false,
                             targetChain: foreignChain,
                             recipient: foreignRecipientAddress,
@@ -1144,7 +1181,7 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
                         connection,
                         [ix],
                         [payer, stagedOutboundSigner],
-                        "Error Code: ExactInRequired",
+                        "Error Code: ZeroMinAmountOut",
                     );
                 });
                 it("Cannot Stage Outbound (Sender Required)", async function () {
@@ -1341,7 +1378,7 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
                                 minAmountOut: uint64ToBN(minAmountOut),
                             },
                             { direct: {} },
-                            Buffer.alloc(1),
+                            { usdc: {} },
                         ),
                     );
                 });
@@ -1439,7 +1476,7 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
                                 minAmountOut: null,
                             },
                             { direct: {} },
-                            Buffer.alloc(1),
+                            { usdc: {} },
                         ),
                     );
                 });
@@ -1503,7 +1540,7 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
                                 minAmountOut: null,
                             },
                             { payload: { "0": Buffer.from("All your base are belong to us.") } },
-                            Buffer.alloc(1),
+                            { usdc: {} },
                         ),
                     );
                 });
@@ -1629,6 +1666,81 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
                     );
                 });
 
+                it("Cannot Stage Outbound (Invalid EVM Recipient)", async function () {
+                    const stagedOutboundSigner = Keypair.generate();
+                    const stagedOutbound = stagedOutboundSigner.publicKey;
+
+                    const senderToken = splToken.getAssociatedTokenAddressSync(
+                        swapLayer.usdcMint,
+                        payer.publicKey,
+                    );
+                    const [, ix] = await swapLayer.stageOutboundIx(
+                        {
+                            payer: payer.publicKey,
+                            senderToken,
+                            stagedOutbound,
+                            usdcRefundToken: senderToken,
+                        },
+                        {
+                            transferType: "sender",
+                            amountIn: 690000n,
+                            isExactIn: false,
+                            targetChain: foreignChain,
+                            recipient: Array.from(payer.publicKey.toBuffer()),
+                            redeemOption: null,
+                            outputToken: null,
+                        },
+                    );
+
+                    await expectIxErr(
+                        connection,
+                        [ix],
+                        [payer, stagedOutboundSigner],
+                        "Error Code: InvalidRecipient",
+                    );
+                });
+
+                it("Cannot Stage Outbound (Unsupported Output Token)", async function () {
+                    const stagedOutboundSigner = Keypair.generate();
+                    const stagedOutbound = stagedOutboundSigner.publicKey;
+
+                    const senderToken = splToken.getAssociatedTokenAddressSync(
+                        swapLayer.usdcMint,
+                        payer.publicKey,
+                    );
+                    const [, ix] = await swapLayer.stageOutboundIx(
+                        {
+                            payer: payer.publicKey,
+                            senderToken,
+                            stagedOutbound,
+                            usdcRefundToken: senderToken,
+                        },
+                        {
+                            transferType: "sender",
+                            amountIn: 690000n,
+                            isExactIn: false,
+                            targetChain: foreignChain,
+                            recipient: foreignRecipientAddress,
+                            redeemOption: null,
+                            outputToken: {
+                                type: "Gas",
+                                swap: {
+                                    deadline: 0,
+                                    limitAmount: 0n,
+                                    type: { id: "JupiterV6", dexProgramId: { isSome: false } },
+                                },
+                            },
+                        },
+                    );
+
+                    await expectIxErr(
+                        connection,
+                        [ix],
+                        [payer, stagedOutboundSigner],
+                        "Error Code: InvalidOutputToken",
+                    );
+                });
+
                 it("Cannot Stage Outbound (Exceeds Max Relayer Fee)", async function () {
                     const stagedOutboundSigner = Keypair.generate();
                     const stagedOutbound = stagedOutboundSigner.publicKey;
@@ -1896,7 +2008,7 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
                                     relayingFee: uint64ToBN(expectedRelayerFee),
                                 },
                             },
-                            Buffer.alloc(1),
+                            { usdc: {} },
                         ),
                     );
                 });
@@ -1970,7 +2082,7 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
                                     relayingFee: uint64ToBN(expectedRelayerFee),
                                 },
                             },
-                            Buffer.alloc(1),
+                            { usdc: {} },
                         ),
                     );
                 });
@@ -2031,7 +2143,7 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
                                 minAmountOut: null,
                             },
                             { direct: {} },
-                            Buffer.alloc(1),
+                            { usdc: {} },
                         ),
                     );
                 });
@@ -2109,7 +2221,7 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
                                 minAmountOut: null,
                             },
                             { direct: {} },
-                            Buffer.from(encodeOutputToken(outputToken)),
+                            toStagedOutputToken(outputToken),
                         ),
                     );
                 });
@@ -3953,6 +4065,1137 @@ describe("Swap Layer -- Admin and USDC Transfer", () => {
                 });
             });
         });
+
+        describe("USDC Transfer (Relay With Payload)", function () {
+            describe("Inbound", function () {
+                const payload = Buffer.from("Insert payload here");
+                const sender = toUniversal(
+                    "Ethereum",
+                    "0x000000000000000000000000000000000000d00d",
+                );
+                const relayerFee = 1000000n;
+                const gasAmountDenorm = 690000000;
+
+                const encodeRelayWithPayloadForTest = () =>
+                    encodeSwapLayerMessageV2({
+                        recipient: toUniversal("Solana", recipient.publicKey.toBytes()),
+                        redeemMode: {
+                            mode: "RelayWithPayload",
+                            gasDropoff: gasAmountDenorm / 1000,
+                            relayingFee: relayerFee,
+                            sender,
+                            buf: Uint8Array.from(payload),
+                        },
+                        outputToken: { type: "Usdc" },
+                        fallbackRecipient: { isSome: false },
+                        integratorFee: { isSome: false },
+                        splitOutputs: [],
+                    });
+
+                it("Cannot Complete Transfer (Fee Recipient Token Required)", async function () {
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        encodeRelayWithPayloadForTest(),
+                    );
+                    const { vaa } = result!;
+
+                    const transferIx = await swapLayer.completeTransferPayloadIx(
+                        {
+                            payer: payer.publicKey,
+                            preparedFill: tokenRouter.preparedFillAddress(vaa),
+                            recipient: recipient.publicKey,
+                        },
+                        foreignChain,
+                    );
+
+                    await expectIxErr(
+                        connection,
+                        [transferIx],
+                        [payer],
+                        "Error Code: FeeRecipientTokenRequired",
+                    );
+                });
+
+                it("Complete Transfer (Relayer Paid From USDC)", async function () {
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        encodeRelayWithPayloadForTest(),
+                    );
+                    const { vaa, message } = result!;
+
+                    const preparedFill = tokenRouter.preparedFillAddress(vaa);
+
+                    // Balance check.
+                    const recipientLamportBefore = await connection.getBalance(recipient.publicKey);
+                    const feeRecipientBefore = await getUsdcAtaBalance(connection, feeRecipient);
+
+                    const transferIx = await swapLayer.completeTransferPayloadIx(
+                        {
+                            payer: payer.publicKey,
+                            preparedFill,
+                            recipient: recipient.publicKey,
+                            feeRecipientToken,
+                        },
+                        foreignChain,
+                    );
+
+                    await expectIxOk(connection, [transferIx], [payer]);
+
+                    // Balance check.
+                    const stagedInbound = swapLayer.stagedInboundAddress(preparedFill);
+                    const stagedInboundTokenAddress =
+                        swapLayer.stagedCustodyTokenAddress(stagedInbound);
+
+                    const { amount: stagedBalance } = await splToken.getAccount(
+                        connection,
+                        stagedInboundTokenAddress,
+                    );
+                    const recipientLamportAfter = await connection.getBalance(recipient.publicKey);
+                    const feeRecipientAfter = await getUsdcAtaBalance(connection, feeRecipient);
+
+                    assert.equal(stagedBalance, message.deposit!.message.amount - relayerFee);
+                    assert.equal(recipientLamportAfter - recipientLamportBefore, gasAmountDenorm);
+                    assert.equal(feeRecipientAfter, feeRecipientBefore + relayerFee);
+
+                    // The payload is delivered to the recipient.
+                    const stagedInboundData = await swapLayer.fetchStagedInbound(stagedInbound);
+                    assert.deepEqual(
+                        stagedInboundData,
+                        new StagedInbound(
+                            {
+                                preparedFill,
+                                bump: stagedInboundData.seeds.bump,
+                            },
+                            {
+                                custodyToken: stagedInboundTokenAddress,
+                                stagedBy: payer.publicKey,
+                                sourceChain: foreignChain,
+                                sender: Array.from(sender.toUint8Array()),
+                                recipient: recipient.publicKey,
+                                isNative: false,
+                            },
+                            payload,
+                        ),
+                    );
+                });
+
+                it("Complete Transfer (Self Redeem)", async function () {
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        encodeRelayWithPayloadForTest(),
+                    );
+                    const { vaa, message } = result!;
+
+                    const preparedFill = tokenRouter.preparedFillAddress(vaa);
+
+                    // Balance check.
+                    const recipientLamportBefore = await connection.getBalance(recipient.publicKey);
+                    const feeRecipientBefore = await getUsdcAtaBalance(connection, feeRecipient);
+
+                    // The recipient signs, so neither the relaying fee nor the gas dropoff apply.
+                    const transferIx = await swapLayer.completeTransferPayloadIx(
+                        {
+                            payer: payer.publicKey,
+                            redeemer: recipient.publicKey,
+                            preparedFill,
+                            recipient: recipient.publicKey,
+                        },
+                        foreignChain,
+                    );
+
+                    await expectIxOk(connection, [transferIx], [payer, recipient]);
+
+                    // Balance check.
+                    const stagedInbound = swapLayer.stagedInboundAddress(preparedFill);
+                    const { amount: stagedBalance } = await splToken.getAccount(
+                        connection,
+                        swapLayer.stagedCustodyTokenAddress(stagedInbound),
+                    );
+                    const recipientLamportAfter = await connection.getBalance(recipient.publicKey);
+                    const feeRecipientAfter = await getUsdcAtaBalance(connection, feeRecipient);
+
+                    assert.equal(stagedBalance, message.deposit!.message.amount);
+                    assert.equal(recipientLamportAfter, recipientLamportBefore);
+                    assert.equal(feeRecipientAfter, feeRecipientBefore);
+
+                    const { recipientPayload } = await swapLayer.fetchStagedInbound(stagedInbound);
+                    assert.deepEqual(recipientPayload, payload);
+                });
+            });
+        });
+
+        describe("USDC Transfer (Integrator Fee)", function () {
+            describe("Inbound", function () {
+                const maxIntegratorFee = 10000000n;
+                const relayerFee = 1000000n;
+                const integrator = Keypair.generate();
+                const integratorToken = splToken.getAssociatedTokenAddressSync(
+                    USDC_MINT_ADDRESS,
+                    integrator.publicKey,
+                );
+
+                const encodeIntegratorFeeForTest = (
+                    amount: { type: "Bps"; bps: number } | { type: "Absolute"; amount: bigint },
+                ) =>
+                    encodeSwapLayerMessageV2({
+                        recipient: toUniversal("Solana", recipient.publicKey.toBytes()),
+                        redeemMode: { mode: "Relay", gasDropoff: 0, relayingFee: relayerFee },
+                        outputToken: { type: "Usdc" },
+                        fallbackRecipient: { isSome: false },
+                        integratorFee: {
+                            isSome: true,
+                            recipient: toUniversal("Solana", integrator.publicKey.toBytes()),
+                            amount,
+                        },
+                        splitOutputs: [],
+                    });
+
+                const updateMaxIntegratorFeeForTest = async (maxIntegratorFee: bigint) => {
+                    const { address, relayParams } = await swapLayer.fetchPeer(foreignChain);
+                    const ix = await swapLayer.updatePeerIx(
+                        { owner: owner.publicKey },
+                        { chain: foreignChain, address, relayParams, maxIntegratorFee },
+                    );
+                    await expectIxOk(connection, [ix], [payer, owner]);
+                };
+
+                before("Set Max Integrator Fee", async function () {
+                    await splToken.getOrCreateAssociatedTokenAccount(
+                        connection,
+                        payer,
+                        USDC_MINT_ADDRESS,
+                        integrator.publicKey,
+                    );
+
+                    await updateMaxIntegratorFeeForTest(maxIntegratorFee);
+                });
+
+                after("Disable Integrator Fees", async function () {
+                    await updateMaxIntegratorFeeForTest(0n);
+                });
+
+                it("Cannot Complete Transfer (Integrator Fee Token Required)", async function () {
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        encodeIntegratorFeeForTest({ type: "Bps", bps: 10 }),
+                    );
+                    const { vaa } = result!;
+
+                    const transferIx = await swapLayer.completeTransferRelayIx(
+                        {
+                            payer: payer.publicKey,
+                            preparedFill: tokenRouter.preparedFillAddress(vaa),
+                            recipient: recipient.publicKey,
+                            integratorFeeToken: null,
+                        },
+                        foreignChain,
+                    );
+
+                    await expectIxErr(
+                        connection,
+                        [transferIx],
+                        [payer],
+                        "Error Code: IntegratorFeeTokenRequired",
+                    );
+                });
+
+                it("Cannot Complete Transfer (Invalid Integrator Fee Token)", async function () {
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        encodeIntegratorFeeForTest({ type: "Bps", bps: 10 }),
+                    );
+                    const { vaa } = result!;
+
+                    // Pass a token account not owned by the encoded integrator fee recipient.
+                    const transferIx = await swapLayer.completeTransferRelayIx(
+                        {
+                            payer: payer.publicKey,
+                            preparedFill: tokenRouter.preparedFillAddress(vaa),
+                            recipient: recipient.publicKey,
+                            integratorFeeToken: await createTokenAccountForTest(),
+                        },
+                        foreignChain,
+                    );
+
+                    await expectIxErr(
+                        connection,
+                        [transferIx],
+                        [payer],
+                        "Error Code: InvalidIntegratorFeeToken",
+                    );
+                });
+
+                it("Complete Transfer With Integrator Fee", async function () {
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        encodeIntegratorFeeForTest({ type: "Bps", bps: 10 }),
+                    );
+                    const { vaa, message } = result!;
+                    const amount = message.deposit!.message.amount;
+                    const integratorFee = (amount * 10n) / 10000n;
+                    assert.isBelow(Number(integratorFee), Number(maxIntegratorFee));
+
+                    // Balance check.
+                    const recipientBefore = await getUsdcAtaBalance(
+                        connection,
+                        recipient.publicKey,
+                    );
+                    const integratorBefore = await getUsdcAtaBalance(
+                        connection,
+                        integrator.publicKey,
+                    );
+                    const feeRecipientBefore = await getUsdcAtaBalance(connection, feeRecipient);
+
+                    // The integrator fee token account is derived from the swap message.
+                    const transferIx = await swapLayer.completeTransferRelayIx(
+                        {
+                            payer: payer.publicKey,
+                            preparedFill: tokenRouter.preparedFillAddress(vaa),
+                            recipient: recipient.publicKey,
+                        },
+                        foreignChain,
+                    );
+
+                    await expectIxOk(connection, [transferIx], [payer]);
+
+                    // Balance check.
+                    const recipientAfter = await getUsdcAtaBalance(connection, recipient.publicKey);
+                    const integratorAfter = await getUsdcAtaBalance(
+                        connection,
+                        integrator.publicKey,
+                    );
+                    const feeRecipientAfter = await getUsdcAtaBalance(connection, feeRecipient);
+
+                    assert.equal(
+                        recipientAfter - recipientBefore,
+                        amount - integratorFee - relayerFee,
+                    );
+                    assert.equal(integratorAfter - integratorBefore, integratorFee);
+                    assert.equal(feeRecipientAfter - feeRecipientBefore, relayerFee);
+                });
+
+                it("Complete Transfer With Integrator Fee (Capped by Peer)", async function () {
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        encodeIntegratorFeeForTest({
+                            type: "Absolute",
+                            amount: maxIntegratorFee * 2n,
+                        }),
+                    );
+                    const { vaa, message } = result!;
+                    const amount = message.deposit!.message.amount;
+
+                    // Balance check.
+                    const recipientBefore = await getUsdcAtaBalance(
+                        connection,
+                        recipient.publicKey,
+                    );
+                    const integratorBefore = await getUsdcAtaBalance(
+                        connection,
+                        integrator.publicKey,
+                    );
+
+                    const transferIx = await swapLayer.completeTransferRelayIx(
+                        {
+                            payer: payer.publicKey,
+                            preparedFill: tokenRouter.preparedFillAddress(vaa),
+                            recipient: recipient.publicKey,
+                        },
+                        foreignChain,
+                    );
+
+                    await expectIxOk(connection, [transferIx], [payer]);
+
+                    // Balance check. Only the peer's maximum integrator fee is paid.
+                    const recipientAfter = await getUsdcAtaBalance(connection, recipient.publicKey);
+                    const integratorAfter = await getUsdcAtaBalance(
+                        connection,
+                        integrator.publicKey,
+                    );
+
+                    assert.equal(
+                        recipientAfter - recipientBefore,
+                        amount - maxIntegratorFee - relayerFee,
+                    );
+                    assert.equal(integratorAfter - integratorBefore, maxIntegratorFee);
+                });
+            });
+        });
+
+        describe("USDC Transfer (Split)", function () {
+            describe("Inbound", function () {
+                const splitRecipient = Keypair.generate();
+                const splitBps = 2500;
+                const relayerFee = 1000000n;
+                const gasAmountDenorm = 690000000;
+                const gasOutputToken: OutputTokenV2 = {
+                    type: "Gas",
+                    swap: {
+                        deadline: 0,
+                        limitAmount: 0n,
+                        type: {
+                            id: "JupiterV6",
+                            dexProgramId: { isSome: false },
+                        },
+                        mode: "ExactIn",
+                    },
+                };
+
+                const encodeSplitForTest = (
+                    opts: {
+                        redeemMode?: RedeemModeV2;
+                        splitOutputToken?: OutputTokenV2;
+                    } = {},
+                ) =>
+                    encodeSwapLayerMessageV2({
+                        recipient: toUniversal("Solana", recipient.publicKey.toBytes()),
+                        redeemMode: opts.redeemMode ?? {
+                            mode: "Relay",
+                            gasDropoff: gasAmountDenorm / 1000,
+                            relayingFee: relayerFee,
+                        },
+                        outputToken: { type: "Usdc" },
+                        fallbackRecipient: { isSome: false },
+                        integratorFee: { isSome: false },
+                        splitOutputs: [
+                            {
+                                recipient: toUniversal(
+                                    "Solana",
+                                    splitRecipient.publicKey.toBytes(),
+                                ),
+                                bps: splitBps,
+                                outputToken: opts.splitOutputToken ?? { type: "Usdc" },
+                            },
+                        ],
+                    });
+
+                before("Set Up Recipients", async function () {
+                    await splToken.getOrCreateAssociatedTokenAccount(
+                        connection,
+                        payer,
+                        USDC_MINT_ADDRESS,
+                        splitRecipient.publicKey,
+                    );
+
+                    // The recipient pays to stage a split when it redeems the fill itself.
+                    await expectIxOk(
+                        connection,
+                        [
+                            SystemProgram.transfer({
+                                fromPubkey: payer.publicKey,
+                                toPubkey: recipient.publicKey,
+                                lamports: 1000000000,
+                            }),
+                        ],
+                        [payer],
+                    );
+                });
+
+                it("Cannot Complete Transfer (Split Outputs Not Supported)", async function () {
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        encodeSplitForTest({ redeemMode: { mode: "Direct" } }),
+                    );
+                    const { vaa } = result!;
+
+                    const transferIx = await swapLayer.completeTransferDirectIx(
+                        {
+                            payer: payer.publicKey,
+                            preparedFill: tokenRouter.preparedFillAddress(vaa),
+                            recipient: recipient.publicKey,
+                        },
+                        foreignChain,
+                    );
+
+                    await expectIxErr(
+                        connection,
+                        [transferIx],
+                        [payer],
+                        "Error Code: SplitOutputsNotSupported",
+                    );
+                });
+
+                it("Cannot Stage Split (Split Outputs Required)", async function () {
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        encodeSwapLayerMessageV2({
+                            recipient: toUniversal("Solana", recipient.publicKey.toBytes()),
+                            redeemMode: { mode: "Direct" },
+                            outputToken: { type: "Usdc" },
+                            fallbackRecipient: { isSome: false },
+                            integratorFee: { isSome: false },
+                            splitOutputs: [],
+                        }),
+                    );
+                    const { vaa } = result!;
+
+                    const ix = await swapLayer.stageSplitIx(
+                        {
+                            payer: payer.publicKey,
+                            preparedFill: tokenRouter.preparedFillAddress(vaa),
+                        },
+                        foreignChain,
+                    );
+
+                    await expectIxErr(
+                        connection,
+                        [ix],
+                        [payer],
+                        "Error Code: SplitOutputsRequired",
+                    );
+                });
+
+                it("Stage and Complete Split (Relayer Paid From USDC)", async function () {
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        encodeSplitForTest(),
+                    );
+                    const { vaa, message } = result!;
+                    const preparedFill = tokenRouter.preparedFillAddress(vaa);
+
+                    // Balance check.
+                    const recipientLamportBefore = await connection.getBalance(recipient.publicKey);
+                    const feeRecipientBefore = await getUsdcAtaBalance(connection, feeRecipient);
+
+                    const ix = await swapLayer.stageSplitIx(
+                        {
+                            payer: payer.publicKey,
+                            preparedFill,
+                            recipient: recipient.publicKey,
+                            feeRecipientToken,
+                        },
+                        foreignChain,
+                    );
+
+                    await expectIxOk(connection, [ix], [payer]);
+
+                    // Balance check. The relaying fee is deducted before the fill is split.
+                    const stagedSplit = swapLayer.stagedSplitAddress(preparedFill);
+                    const stagedCustodyToken = swapLayer.stagedCustodyTokenAddress(stagedSplit);
+
+                    const { amount: stagedBalance } = await splToken.getAccount(
+                        connection,
+                        stagedCustodyToken,
+                    );
+                    const recipientLamportAfter = await connection.getBalance(recipient.publicKey);
+                    const feeRecipientAfter = await getUsdcAtaBalance(connection, feeRecipient);
+
+                    const splitAmount = message.deposit!.message.amount - relayerFee;
+                    const splitOutputAmount = (splitAmount * BigInt(splitBps)) / 10000n;
+                    assert.equal(stagedBalance, splitAmount);
+                    assert.equal(recipientLamportAfter - recipientLamportBefore, gasAmountDenorm);
+                    assert.equal(feeRecipientAfter, feeRecipientBefore + relayerFee);
+
+                    const stagedSplitData = await swapLayer.fetchStagedSplit(stagedSplit);
+                    assert.isNotNull(stagedSplitData.info.swapTimeLimitEnd);
+                    assert.deepEqual(
+                        stagedSplitData,
+                        new StagedSplit(
+                            {
+                                preparedFill,
+                                bump: stagedSplitData.seeds.bump,
+                            },
+                            {
+                                custodyTokenBump: stagedSplitData.info.custodyTokenBump,
+                                stagedBy: payer.publicKey,
+                                sourceChain: foreignChain,
+                                swapTimeLimitEnd: stagedSplitData.info.swapTimeLimitEnd,
+                            },
+                            [
+                                {
+                                    recipient: recipient.publicKey,
+                                    usdcRecipient: recipient.publicKey,
+                                    amount: uint64ToBN(splitAmount - splitOutputAmount),
+                                    outputToken: { usdc: {} },
+                                    isCompleted: false,
+                                },
+                                {
+                                    recipient: splitRecipient.publicKey,
+                                    usdcRecipient: splitRecipient.publicKey,
+                                    amount: uint64ToBN(splitOutputAmount),
+                                    outputToken: { usdc: {} },
+                                    isCompleted: false,
+                                },
+                            ],
+                        ),
+                    );
+
+                    // Anyone can deliver USDC outputs.
+                    const recipientBefore = await getUsdcAtaBalance(
+                        connection,
+                        recipient.publicKey,
+                    );
+                    const splitRecipientBefore = await getUsdcAtaBalance(
+                        connection,
+                        splitRecipient.publicKey,
+                    );
+
+                    const splitTransferIx = await swapLayer.completeSplitTransferIx(
+                        { redeemer: payer.publicKey, preparedFill },
+                        1,
+                    );
+                    await expectIxOk(connection, [splitTransferIx], [payer]);
+
+                    // The staged split stays open until every output is delivered.
+                    assert.isNotNull(await connection.getAccountInfo(stagedSplit));
+
+                    const transferIx = await swapLayer.completeSplitTransferIx(
+                        { redeemer: payer.publicKey, preparedFill },
+                        0,
+                    );
+                    await expectIxOk(connection, [transferIx], [payer]);
+
+                    const recipientAfter = await getUsdcAtaBalance(connection, recipient.publicKey);
+                    const splitRecipientAfter = await getUsdcAtaBalance(
+                        connection,
+                        splitRecipient.publicKey,
+                    );
+                    assert.equal(recipientAfter - recipientBefore, splitAmount - splitOutputAmount);
+                    assert.equal(splitRecipientAfter - splitRecipientBefore, splitOutputAmount);
+
+                    // Confirm that the staged accounts have been deleted.
+                    {
+                        const accInfo = await connection.getAccountInfo(stagedSplit);
+                        assert.isNull(accInfo);
+                    }
+                    {
+                        const accInfo = await connection.getAccountInfo(stagedCustodyToken);
+                        assert.isNull(accInfo);
+                    }
+                });
+
+                it("Stage Split (Self Redeem)", async function () {
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        encodeSplitForTest(),
+                    );
+                    const { vaa, message } = result!;
+                    const preparedFill = tokenRouter.preparedFillAddress(vaa);
+
+                    // Balance check.
+                    const feeRecipientBefore = await getUsdcAtaBalance(connection, feeRecipient);
+
+                    // The recipient pays, so neither the relaying fee nor the gas dropoff apply.
+                    const ix = await swapLayer.stageSplitIx(
+                        {
+                            payer: recipient.publicKey,
+                            preparedFill,
+                        },
+                        foreignChain,
+                    );
+
+                    await expectIxOk(connection, [ix], [recipient]);
+
+                    // Balance check.
+                    const stagedSplit = swapLayer.stagedSplitAddress(preparedFill);
+                    const { amount: stagedBalance } = await splToken.getAccount(
+                        connection,
+                        swapLayer.stagedCustodyTokenAddress(stagedSplit),
+                    );
+                    const feeRecipientAfter = await getUsdcAtaBalance(connection, feeRecipient);
+
+                    assert.equal(stagedBalance, message.deposit!.message.amount);
+                    assert.equal(feeRecipientAfter, feeRecipientBefore);
+
+                    const splitOutputAmount =
+                        (message.deposit!.message.amount * BigInt(splitBps)) / 10000n;
+                    const { info, outputs } = await swapLayer.fetchStagedSplit(stagedSplit);
+                    assert.deepEqual(info.stagedBy, recipient.publicKey);
+                    assert.equal(outputs[1].amount.toString(), splitOutputAmount.toString());
+                });
+
+                it("Cannot Complete Split Transfer (Invalid Split Output Index)", async function () {
+                    const { preparedFill } = await stageSplitForTest(encodeSplitForTest());
+
+                    const transferIx = await swapLayer.completeSplitTransferIx(
+                        {
+                            redeemer: payer.publicKey,
+                            preparedFill,
+                            recipientTokenAccount: splToken.getAssociatedTokenAddressSync(
+                                USDC_MINT_ADDRESS,
+                                recipient.publicKey,
+                            ),
+                        },
+                        2,
+                    );
+
+                    await expectIxErr(
+                        connection,
+                        [transferIx],
+                        [payer],
+                        "Error Code: InvalidSplitOutputIndex",
+                    );
+                });
+
+                it("Cannot Complete Split Transfer (Split Output Already Completed)", async function () {
+                    const { preparedFill } = await stageSplitForTest(encodeSplitForTest());
+
+                    const transferIx = await swapLayer.completeSplitTransferIx(
+                        { redeemer: payer.publicKey, preparedFill },
+                        1,
+                    );
+                    await expectIxOk(connection, [transferIx], [payer]);
+
+                    const transferIxAgain = await swapLayer.completeSplitTransferIx(
+                        { redeemer: payer.publicKey, preparedFill },
+                        1,
+                    );
+                    await expectIxErr(
+                        connection,
+                        [transferIxAgain],
+                        [payer],
+                        "Error Code: SplitOutputAlreadyCompleted",
+                    );
+                });
+
+                it("Cannot Complete Split Transfer (Invalid Redeemer)", async function () {
+                    const { preparedFill } = await stageSplitForTest(
+                        encodeSplitForTest({
+                            redeemMode: { mode: "Direct" },
+                            splitOutputToken: gasOutputToken,
+                        }),
+                    );
+
+                    // Only the split recipient may take USDC instead of the swap for a direct fill.
+                    const transferIx = await swapLayer.completeSplitTransferIx(
+                        { redeemer: payer.publicKey, preparedFill },
+                        1,
+                    );
+
+                    await expectIxErr(
+                        connection,
+                        [transferIx],
+                        [payer],
+                        "Error Code: InvalidRedeemer",
+                    );
+                });
+
+                it("Cannot Complete Split Transfer (Swap Time Limit Not Exceeded)", async function () {
+                    const currTime = await connection.getBlockTime(await connection.getSlot());
+                    const { preparedFill } = await stageSplitForTest(
+                        encodeSplitForTest({ splitOutputToken: gasOutputToken }),
+                        currTime - TEST_RELAY_PARAMS.swapTimeLimit.finalizedLimit + 5,
+                    );
+
+                    const transferIx = await swapLayer.completeSplitTransferIx(
+                        { redeemer: payer.publicKey, preparedFill },
+                        1,
+                    );
+
+                    await expectIxErr(
+                        connection,
+                        [transferIx],
+                        [payer],
+                        "Error Code: SwapTimeLimitNotExceeded",
+                    );
+                });
+
+                it("Complete Split Transfer with Redeemer == Recipient and Output Token != USDC", async function () {
+                    const currTime = await connection.getBlockTime(await connection.getSlot());
+                    const { preparedFill } = await stageSplitForTest(
+                        encodeSplitForTest({ splitOutputToken: gasOutputToken }),
+                        currTime - TEST_RELAY_PARAMS.swapTimeLimit.finalizedLimit + 5,
+                    );
+
+                    // The split recipient may take USDC before the swap time limit is exceeded.
+                    const transferIx = await swapLayer.completeSplitTransferIx(
+                        { redeemer: splitRecipient.publicKey, preparedFill },
+                        1,
+                    );
+
+                    await expectIxOk(connection, [transferIx], [payer, splitRecipient]);
+                });
+
+                it("Complete Split Transfer with Redeemer != Recipient and Output Token != USDC (Swap Time Limit Exceeded)", async function () {
+                    const currTime = await connection.getBlockTime(await connection.getSlot());
+                    const { preparedFill } = await stageSplitForTest(
+                        encodeSplitForTest({ splitOutputToken: gasOutputToken }),
+                        currTime - TEST_RELAY_PARAMS.swapTimeLimit.finalizedLimit - 1,
+                    );
+
+                    const transferIx = await swapLayer.completeSplitTransferIx(
+                        { redeemer: payer.publicKey, preparedFill },
+                        1,
+                    );
+
+                    await expectIxOk(connection, [transferIx], [payer]);
+                });
+
+                it("Complete Split Transfer (Sweep Dust)", async function () {
+                    const { preparedFill } = await stageSplitForTest(
+                        encodeSplitForTest({ redeemMode: { mode: "Direct" } }),
+                    );
+                    const stagedSplit = swapLayer.stagedSplitAddress(preparedFill);
+                    const stagedCustodyToken = swapLayer.stagedCustodyTokenAddress(stagedSplit);
+
+                    const splitTransferIx = await swapLayer.completeSplitTransferIx(
+                        { redeemer: payer.publicKey, preparedFill },
+                        1,
+                    );
+                    await expectIxOk(connection, [splitTransferIx], [payer]);
+
+                    // Someone sends USDC to the staged custody token account.
+                    const dust = 42069n;
+                    await expectIxOk(
+                        connection,
+                        [
+                            splToken.createTransferInstruction(
+                                splToken.getAssociatedTokenAddressSync(
+                                    USDC_MINT_ADDRESS,
+                                    payer.publicKey,
+                                ),
+                                stagedCustodyToken,
+                                payer.publicKey,
+                                dust,
+                            ),
+                        ],
+                        [payer],
+                    );
+
+                    // Balance check.
+                    const { outputs } = await swapLayer.fetchStagedSplit(stagedSplit);
+                    const recipientBefore = await getUsdcAtaBalance(
+                        connection,
+                        recipient.publicKey,
+                    );
+                    const feeRecipientBefore = await getUsdcAtaBalance(connection, feeRecipient);
+
+                    const transferIx = await swapLayer.completeSplitTransferIx(
+                        { redeemer: payer.publicKey, preparedFill },
+                        0,
+                    );
+                    await expectIxOk(connection, [transferIx], [payer]);
+
+                    // Balance check. The dust goes to the fee recipient.
+                    const recipientAfter = await getUsdcAtaBalance(connection, recipient.publicKey);
+                    const feeRecipientAfter = await getUsdcAtaBalance(connection, feeRecipient);
+
+                    assert.equal(
+                        recipientAfter - recipientBefore,
+                        BigInt(outputs[0].amount.toString()),
+                    );
+                    assert.equal(feeRecipientAfter - feeRecipientBefore, dust);
+
+                    // Confirm that the staged accounts have been deleted.
+                    {
+                        const accInfo = await connection.getAccountInfo(stagedSplit);
+                        assert.isNull(accInfo);
+                    }
+                    {
+                        const accInfo = await connection.getAccountInfo(stagedCustodyToken);
+                        assert.isNull(accInfo);
+                    }
+                });
+
+                async function stageSplitForTest(
+                    redeemerMessage: Uint8Array,
+                    vaaTimestamp?: number,
+                ) {
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        redeemerMessage,
+                        { vaaTimestamp },
+                    );
+                    const { vaa } = result!;
+                    const preparedFill = tokenRouter.preparedFillAddress(vaa);
+
+                    const ix = await swapLayer.stageSplitIx(
+                        {
+                            payer: payer.publicKey,
+                            preparedFill,
+                            recipient: recipient.publicKey,
+                            feeRecipientToken,
+                        },
+                        foreignChain,
+                    );
+                    await expectIxOk(connection, [ix], [payer]);
+
+                    return { preparedFill };
+                }
+            });
+        });
+
+        describe("USDC Transfer (Fallback Recipient)", function () {
+            describe("Inbound", function () {
+                const fallbackRecipient = Keypair.generate();
+                const relayerFee = 1000000n;
+
+                const encodeFallbackForTest = (
+                    fallbackRecipient: PublicKey,
+                    splitOutputs: SplitOutput[] = [],
+                ) =>
+                    encodeSwapLayerMessageV2({
+                        recipient: toUniversal("Solana", recipient.publicKey.toBytes()),
+                        redeemMode: { mode: "Relay", gasDropoff: 0, relayingFee: relayerFee },
+                        outputToken: {
+                            type: "Gas",
+                            swap: {
+                                deadline: 0,
+                                limitAmount: 0n,
+                                type: {
+                                    id: "JupiterV6",
+                                    dexProgramId: { isSome: false },
+                                },
+                                mode: "ExactIn",
+                            },
+                        },
+                        fallbackRecipient: {
+                            isSome: true,
+                            address: toUniversal("Solana", fallbackRecipient.toBytes()),
+                        },
+                        integratorFee: { isSome: false },
+                        splitOutputs,
+                    });
+
+                before("Create Fallback Recipient Token Account", async function () {
+                    await splToken.getOrCreateAssociatedTokenAccount(
+                        connection,
+                        payer,
+                        USDC_MINT_ADDRESS,
+                        fallbackRecipient.publicKey,
+                    );
+                });
+
+                it("Complete Transfer with Redeemer != Recipient (Fallback Recipient)", async function () {
+                    const currTime = await connection.getBlockTime(await connection.getSlot());
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        encodeFallbackForTest(fallbackRecipient.publicKey),
+                        {
+                            vaaTimestamp:
+                                currTime - TEST_RELAY_PARAMS.swapTimeLimit.finalizedLimit - 1,
+                        },
+                    );
+                    const { vaa, message } = result!;
+
+                    // Balance check.
+                    const recipientBefore = await getUsdcAtaBalance(
+                        connection,
+                        recipient.publicKey,
+                    );
+                    const fallbackRecipientBefore = await getUsdcAtaBalance(
+                        connection,
+                        fallbackRecipient.publicKey,
+                    );
+
+                    // The swap was not performed in time, so the relayer delivers USDC to the
+                    // fallback recipient.
+                    const transferIx = await swapLayer.completeTransferRelayIx(
+                        {
+                            payer: payer.publicKey,
+                            preparedFill: tokenRouter.preparedFillAddress(vaa),
+                            recipient: recipient.publicKey,
+                        },
+                        foreignChain,
+                    );
+
+                    await expectIxOk(connection, [transferIx], [payer]);
+
+                    // Balance check.
+                    const recipientAfter = await getUsdcAtaBalance(connection, recipient.publicKey);
+                    const fallbackRecipientAfter = await getUsdcAtaBalance(
+                        connection,
+                        fallbackRecipient.publicKey,
+                    );
+
+                    assert.equal(recipientAfter, recipientBefore);
+                    assert.equal(
+                        fallbackRecipientAfter - fallbackRecipientBefore,
+                        message.deposit!.message.amount - relayerFee,
+                    );
+                });
+
+                it("Complete Transfer with Redeemer == Recipient (Fallback Recipient Ignored)", async function () {
+                    // The fallback recipient does not need a token account.
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        encodeFallbackForTest(Keypair.generate().publicKey),
+                    );
+                    const { vaa, message } = result!;
+
+                    // Balance check.
+                    const recipientBefore = await getUsdcAtaBalance(
+                        connection,
+                        recipient.publicKey,
+                    );
+
+                    const transferIx = await swapLayer.completeTransferRelayIx(
+                        {
+                            payer: payer.publicKey,
+                            redeemer: recipient.publicKey,
+                            preparedFill: tokenRouter.preparedFillAddress(vaa),
+                            recipient: recipient.publicKey,
+                        },
+                        foreignChain,
+                    );
+
+                    await expectIxOk(connection, [transferIx], [payer, recipient]);
+
+                    // Balance check.
+                    const recipientAfter = await getUsdcAtaBalance(connection, recipient.publicKey);
+                    assert.equal(recipientAfter - recipientBefore, message.deposit!.message.amount);
+                });
+
+                it("Complete Split Transfer with Redeemer != Recipient (Fallback Recipient)", async function () {
+                    const currTime = await connection.getBlockTime(await connection.getSlot());
+                    const { preparedFill } = await stageSplitForTest(
+                        encodeFallbackForTest(fallbackRecipient.publicKey, [
+                            {
+                                recipient: toUniversal("Solana", payer.publicKey.toBytes()),
+                                bps: 2500,
+                                outputToken: { type: "Usdc" },
+                            },
+                        ]),
+                        currTime - TEST_RELAY_PARAMS.swapTimeLimit.finalizedLimit - 1,
+                    );
+
+                    // Balance check.
+                    const { outputs } = await swapLayer.fetchStagedSplit(
+                        swapLayer.stagedSplitAddress(preparedFill),
+                    );
+                    const fallbackRecipientBefore = await getUsdcAtaBalance(
+                        connection,
+                        fallbackRecipient.publicKey,
+                    );
+
+                    const transferIx = await swapLayer.completeSplitTransferIx(
+                        { redeemer: payer.publicKey, preparedFill },
+                        0,
+                    );
+                    await expectIxOk(connection, [transferIx], [payer]);
+
+                    // Balance check.
+                    const fallbackRecipientAfter = await getUsdcAtaBalance(
+                        connection,
+                        fallbackRecipient.publicKey,
+                    );
+                    assert.equal(
+                        fallbackRecipientAfter - fallbackRecipientBefore,
+                        BigInt(outputs[0].amount.toString()),
+                    );
+                });
+
+                it("Complete Split Transfer with Redeemer == Recipient (Fallback Recipient Ignored)", async function () {
+                    // The fallback recipient does not need a token account.
+                    const { preparedFill } = await stageSplitForTest(
+                        encodeFallbackForTest(Keypair.generate().publicKey, [
+                            {
+                                recipient: toUniversal("Solana", payer.publicKey.toBytes()),
+                                bps: 2500,
+                                outputToken: { type: "Usdc" },
+                            },
+                        ]),
+                    );
+
+                    // Balance check.
+                    const { outputs } = await swapLayer.fetchStagedSplit(
+                        swapLayer.stagedSplitAddress(preparedFill),
+                    );
+                    const recipientBefore = await getUsdcAtaBalance(
+                        connection,
+                        recipient.publicKey,
+                    );
+
+                    const transferIx = await swapLayer.completeSplitTransferIx(
+                        { redeemer: recipient.publicKey, preparedFill },
+                        0,
+                    );
+                    await expectIxOk(connection, [transferIx], [payer, recipient]);
+
+                    // Balance check.
+                    const recipientAfter = await getUsdcAtaBalance(connection, recipient.publicKey);
+                    assert.equal(
+                        recipientAfter - recipientBefore,
+                        BigInt(outputs[0].amount.toString()),
+                    );
+                });
+
+                async function stageSplitForTest(
+                    redeemerMessage: Uint8Array,
+                    vaaTimestamp?: number,
+                ) {
+                    const result = await createAndRedeemCctpFillForTest(
+                        testCctpNonce++,
+                        foreignChain,
+                        foreignTokenRouterAddress,
+                        foreignSwapLayerAddress,
+                        wormholeSequence,
+                        redeemerMessage,
+                        { vaaTimestamp },
+                    );
+                    const { vaa } = result!;
+                    const preparedFill = tokenRouter.preparedFillAddress(vaa);
+
+                    const ix = await swapLayer.stageSplitIx(
+                        {
+                            payer: payer.publicKey,
+                            preparedFill,
+                            feeRecipientToken,
+                        },
+                        foreignChain,
+                    );
+                    await expectIxOk(connection, [ix], [payer]);
+
+                    return { preparedFill };
+                }
+            });
+        });
     });
 
     async function createAndRedeemCctpFillForTest(
@@ -5,6 +5,7 @@ import {
     Connection,
     Keypair,
     PublicKey,
+    SYSVAR_RENT_PUBKEY,
     Signer,
     SystemProgram,
     TransactionInstruction,
@@ -42,28 +43,37 @@ import { Chain, ChainId, toChainId } from "@wormhole-foundation/sdk-base";
 import { UniversalAddress, toNative, toUniversal } from "@wormhole-foundation/sdk-definitions";
 import "@wormhole-foundation/sdk-solana/address";
 import { assert } from "chai";
+import * as anchor029 from "anchor-0.29.0";
 import * as fs from "fs";
 import * as jupiterV6 from "../src/jupiterV6";
+import * as orcaWhirlpool from "../src/orcaWhirlpool";
 import {
     OutputToken,
+    OutputTokenV2,
     RedeemMode,
     StagedInbound,
     StagedOutboundInfo,
     SwapLayerMessage,
+    SwapLayerMessageV2,
+    SplitOutput,
     SwapLayerProgram,
     TEST_RELAY_PARAMS,
     calculateRelayerFee,
+    decodeAnySwapLayerMessage,
     decodeSwapLayerMessage,
     denormalizeGasDropOff,
     encodeSwapLayerMessage,
+    encodeSwapLayerMessageV2,
     localnet,
 } from "../src/swapLayer";
+import { IDL as WHIRLPOOL_IDL, Whirlpool } from "../src/types/whirlpool";
 import {
     BERN_MINT_ADDRESS,
     BONK_MINT_ADDRESS,
     FEE_UPDATER_KEYPAIR,
     REGISTERED_PEERS,
     USDT_MINT_ADDRESS,
+    WHIRLPOOL_USDC_USDT,
     createAta,
     createLut,
     tryNativeToUint8Array,
@@ -1326,6 +1336,51 @@ describe("Swap Layer -- Jupiter V6", () => {
                 );
             });
 
+            it("Other (USDT) via Whirlpool (Exact Out)", async function () {
+                const dstMint = USDT_MINT_ADDRESS;
+                const { limitAmount, outputToken } = newQuotedSwapOutputToken({
+                    quotedAmountOut: 190_000_000n,
+                    dstMint,
+                    exactOut: true,
+                });
+
+                const gasDropoff = 100_000; // .1 SOL (10,000 * 1e3)
+                const relayingFee = 690000n; // .69 USDC
+                const amountIn = 200_000_000n;
+                const { preparedFill, recipient } = await redeemSwapLayerFastFillForTest(
+                    { payer: payer.publicKey },
+                    emittedEvents,
+                    {
+                        dstMint,
+                        outputToken,
+                        redeemMode: {
+                            mode: "Relay",
+                            gasDropoff,
+                            relayingFee,
+                        },
+                        amountIn,
+                    },
+                );
+
+                // The USDC not spent by the swap goes to this token account.
+                await createAta(connection, payer, swapLayer.usdcMint, recipient);
+
+                await completeSwapRelayForTest(
+                    {
+                        payer: payer.publicKey,
+                        preparedFill,
+                        recipient,
+                        dstMint,
+                    },
+                    {
+                        limitAmount,
+                        relayingFee,
+                        denormGasDropoff: denormalizeGasDropOff(gasDropoff),
+                        swapResponseModifier: modifyUsdcToUsdtExactOutSwapResponseForTest,
+                    },
+                );
+            });
+
             it("Other (USDT) via Whirlpool (No Relayer Fee)", async function () {
                 const dstMint = USDT_MINT_ADDRESS;
                 const { limitAmount, outputToken } = newQuotedSwapOutputToken({
@@ -1929,7 +1984,7 @@ describe("Swap Layer -- Jupiter V6", () => {
                         isExactIn: true,
                         minAmountOut: 1n,
                         targetChain: toChainId("Ethereum"),
-                        recipient: Array.from(Buffer.alloc(32, "deadbeef")),
+                        recipient: Array.from(Buffer.alloc(32, "deadbeef", "hex").fill(0, 0, 12)),
                         redeemOption: null,
                         outputToken: null,
                     },
@@ -2164,6 +2219,97 @@ describe("Swap Layer -- Jupiter V6", () => {
                 );
                 assert.isTrue(preparedCustodyTokenBalance >= minAmountOut);
             });
+
+            it("USDT via Whirlpool (Exact Out)", async function () {
+                const srcMint = USDT_MINT_ADDRESS;
+                const senderToken = splToken.getAssociatedTokenAddressSync(
+                    srcMint,
+                    payer.publicKey,
+                    false,
+                    await whichTokenProgram(connection, srcMint),
+                );
+
+                // The order must land with exactly this USDC amount.
+                const usdcAmountOut = 600000n;
+
+                const { amountIn, stagedOutbound, stagedCustodyToken } = await stageOutboundForTest(
+                    {
+                        payer: payer.publicKey,
+                        senderToken,
+                        srcMint,
+                    },
+                    { minAmountOut: usdcAmountOut, isExactIn: false },
+                );
+
+                const preparedOrder = swapLayer.preparedOrderAddress(stagedOutbound);
+                const swapAuthority = swapLayer.swapAuthorityAddress(preparedOrder);
+                const { instruction: exactInInstruction, sourceToken, destinationToken } =
+                    await modifyUsdtToUsdcSwapResponseForTest(swapAuthority, { cpi: true });
+                const { instruction: cpiInstruction, maxAmountIn } =
+                    jupiterV6.toSharedAccountsExactOutRouteInstruction(exactInInstruction, {
+                        outAmount: usdcAmountOut,
+                        quotedInAmount: usdcAmountOut, // stable swap
+                        slippageBps: 50,
+                    });
+                assert.isTrue(maxAmountIn < amountIn);
+
+                const swapIxes = await swapLayer.initiateSwapExactOutIxes(
+                    {
+                        payer: payer.publicKey,
+                        stagedOutbound,
+                        srcMint,
+                    },
+                    {
+                        cpiInstruction,
+                    },
+                );
+
+                const computeIx = ComputeBudgetProgram.setComputeUnitLimit({
+                    units: 400_000,
+                });
+
+                const addressLookupTableAccounts = await Promise.all(
+                    luts.map(async (lookupTableAddress) => {
+                        const resp = await connection.getAddressLookupTable(lookupTableAddress);
+                        return resp.value;
+                    }),
+                );
+
+                const { amount: senderBalanceBefore } = await splToken.getAccount(
+                    connection,
+                    senderToken,
+                );
+
+                await expectIxOk(connection, [computeIx, ...swapIxes], [payer], {
+                    addressLookupTableAccounts,
+                });
+
+                {
+                    const accInfos = await connection.getMultipleAccountsInfo([
+                        sourceToken,
+                        destinationToken,
+                        stagedOutbound,
+                        stagedCustodyToken,
+                    ]);
+                    assert.isTrue(accInfos.every((info) => info === null));
+                }
+
+                // Verify the prepared custody token has exactly the USDC amount.
+                const { amount: preparedCustodyTokenBalance } = await splToken.getAccount(
+                    connection,
+                    tokenRouter.preparedCustodyTokenAddress(preparedOrder),
+                );
+                assert.equal(preparedCustodyTokenBalance, usdcAmountOut);
+
+                // Verify the unspent source tokens were refunded to the sender.
+                const { amount: senderBalanceAfter } = await splToken.getAccount(
+                    connection,
+                    senderToken,
+                );
+                const refund = senderBalanceAfter - senderBalanceBefore;
+                assert.isTrue(refund > 0n);
+                assert.isTrue(amountIn - refund <= maxAmountIn);
+            });
         });
 
         describe("Inbound", function () {
@@ -2483,6 +2629,77 @@ describe("Swap Layer -- Jupiter V6", () => {
                 );
             });
 
+            it("Cannot Swap (Recipient USDC Token Required)", async function () {
+                const dstMint = USDT_MINT_ADDRESS;
+                const { limitAmount, outputToken } = newQuotedSwapOutputToken({
+                    quotedAmountOut: 190_000_000n,
+                    dstMint,
+                    exactOut: true,
+                });
+
+                const amountIn = 200_000_000n;
+                const { preparedFill, recipient } = await redeemSwapLayerFastFillForTest(
+                    { payer: payer.publicKey },
+                    emittedEvents,
+                    {
+                        dstMint,
+                        outputToken,
+                        amountIn,
+                    },
+                );
+
+                await completeSwapDirectForTest(
+                    {
+                        payer: payer.publicKey,
+                        preparedFill,
+                        recipient,
+                        dstMint,
+                        recipientUsdcToken: null,
+                    },
+                    {
+                        limitAmount,
+                        swapResponseModifier: modifyUsdcToUsdtExactOutSwapResponseForTest,
+                        errorMsg: "Error Code: RecipientUsdcTokenRequired",
+                    },
+                );
+            });
+
+            it("Other (USDT) via Whirlpool (Exact Out)", async function () {
+                const dstMint = USDT_MINT_ADDRESS;
+                const { limitAmount, outputToken } = newQuotedSwapOutputToken({
+                    quotedAmountOut: 190_000_000n,
+                    dstMint,
+                    exactOut: true,
+                });
+
+                const amountIn = 200_000_000n;
+                const { preparedFill, recipient } = await redeemSwapLayerFastFillForTest(
+                    { payer: payer.publicKey },
+                    emittedEvents,
+                    {
+                        dstMint,
+                        outputToken,
+                        amountIn,
+                    },
+                );
+
+                // The USDC not spent by the swap goes to this token account.
+                await createAta(connection, payer, swapLayer.usdcMint, recipient);
+
+                await completeSwapDirectForTest(
+                    {
+                        payer: payer.publicKey,
+                        preparedFill,
+                        recipient,
+                        dstMint,
+                    },
+                    {
+                        limitAmount,
+                        swapResponseModifier: modifyUsdcToUsdtExactOutSwapResponseForTest,
+                    },
+                );
+            });
+
             it("Other (WSOL) via Phoenix V1", async function () {
                 const dstMint = splToken.NATIVE_MINT;
                 const { limitAmount, outputToken } = newQuotedSwapOutputToken({
@@ -2780,6 +2997,49 @@ describe("Swap Layer -- Jupiter V6", () => {
                 );
             });
 
+            it("Other (USDT) via Whirlpool (Exact Out)", async function () {
+                const dstMint = USDT_MINT_ADDRESS;
+                const { limitAmount, outputToken } = newQuotedSwapOutputToken({
+                    quotedAmountOut: 190_000_000n,
+                    dstMint,
+                    exactOut: true,
+                });
+
+                const amountIn = 200_000_000n;
+                const { preparedFill, recipient } = await redeemSwapLayerFastFillForTest(
+                    { payer: payer.publicKey },
+                    emittedEvents,
+                    {
+                        dstMint,
+                        redeemMode: {
+                            mode: "Payload",
+                            sender: toUniversal(
+                                "Ethereum",
+                                "0x000000000000000000000000000000000000d00d",
+                            ),
+                            buf: Uint8Array.from(Buffer.from("All your base are belong to us.")),
+                        },
+                        outputToken,
+                        amountIn,
+                    },
+                );
+
+                // The USDC not spent by the swap goes to this token account.
+                await createAta(connection, payer, swapLayer.usdcMint, recipient);
+
+                await completeSwapPayloadForTest(
+                    {
+                        payer: payer.publicKey,
+                        preparedFill,
+                        dstMint,
+                    },
+                    {
+                        limitAmount,
+                        swapResponseModifier: modifyUsdcToUsdtExactOutSwapResponseForTest,
+                    },
+                );
+            });
+
             it("Other (WSOL) via Phoenix V1", async function () {
                 const dstMint = splToken.NATIVE_MINT;
                 const { limitAmount, outputToken } = newQuotedSwapOutputToken({
@@ -2899,46 +3159,1008 @@ describe("Swap Layer -- Jupiter V6", () => {
         });
     });
 
-    function newQuotedSwapOutputToken(opts: {
-        quotedAmountOut: bigint;
-        dstMint?: PublicKey | null;
-        slippageBps?: number;
-        deadline?: number;
-        dexProgramId?: PublicKey | null;
-    }) {
-        const { quotedAmountOut } = opts;
+    describe("USDC Swap (Split)", function () {
+        describe("Inbound", function () {
+            const emittedEvents: EmittedFilledLocalFastOrder[] = [];
+            let listenerId: number | null;
 
-        let { dstMint, slippageBps, deadline, dexProgramId } = opts;
-        dstMint ??= null;
-        slippageBps ??= 0;
-        deadline ??= 0;
-        dexProgramId ??= null;
+            before("Start Event Listener", async function () {
+                listenerId = matchingEngine.onEventCpi((event, slot, signature) => {
+                    const { localFastOrderFilled } = event;
+                    if (localFastOrderFilled !== undefined) {
+                        emittedEvents.push({
+                            event: localFastOrderFilled,
+                            slot,
+                            signature,
+                        });
+                    }
+                });
+            });
 
-        const limitAmount = (quotedAmountOut * (10000n - BigInt(slippageBps))) / 10000n;
-        const swap = {
-            deadline,
-            limitAmount,
-            type: {
-                id: "JupiterV6",
-                dexProgramId:
-                    dexProgramId === null
-                        ? { isSome: false }
-                        : {
-                              isSome: true,
-                              address: toUniversal("Solana", dexProgramId.toString()),
-                          },
-            },
-        };
-        return {
-            limitAmount,
-            outputToken: (dstMint === null
-                ? { type: "Gas", swap }
-                : {
-                      type: "Other",
-                      address: toUniversal("Solana", dstMint.toString()),
-                      swap,
-                  }) as OutputToken,
-        };
+            after("Stop Event Listener", async function () {
+                if (listenerId !== null) {
+                    matchingEngine.program.removeEventListener(listenerId!);
+                }
+            });
+
+            it("Cannot Swap (Split Outputs Not Supported)", async function () {
+                const dstMint = USDT_MINT_ADDRESS;
+                const { limitAmount, outputToken } = newQuotedSwapOutputToken({
+                    quotedAmountOut: 198_800_000n,
+                    dstMint,
+                    slippageBps: 15,
+                });
+
+                const { preparedFill, recipient } = await redeemSwapLayerFastFillForTest(
+                    { payer: payer.publicKey },
+                    emittedEvents,
+                    {
+                        dstMint,
+                        outputToken,
+                        amountIn: 200_000_000n,
+                        splitOutputs: [
+                            {
+                                recipient: toUniversal("Solana", payer.publicKey.toBytes()),
+                                bps: 100,
+                                outputToken: { type: "Usdc" },
+                            },
+                        ],
+                    },
+                );
+
+                // A fill with split outputs must be staged with stage_split first.
+                await completeSwapDirectForTest(
+                    {
+                        payer: payer.publicKey,
+                        preparedFill,
+                        recipient,
+                        dstMint,
+                    },
+                    {
+                        limitAmount,
+                        swapResponseModifier: modifyUsdcToUsdtSwapResponseForTest,
+                        errorMsg: "Error Code: SplitOutputsNotSupported",
+                    },
+                );
+            });
+
+            it("Cannot Swap (Invalid Output Token)", async function () {
+                const dstMint = USDT_MINT_ADDRESS;
+                const { preparedFill } = await stageSplitForTest({
+                    splitOutputToken: newQuotedSwapOutputToken({
+                        quotedAmountOut: 99_400_000n,
+                        dstMint,
+                        slippageBps: 100,
+                    }).outputToken,
+                });
+
+                const addressLookupTableAccounts = await fetchLookupTablesForTest();
+
+                // The message recipient's share is USDC, so it cannot be swapped.
+                const { instruction: cpiInstruction } = await modifyUsdcToUsdtSwapResponseForTest(
+                    swapLayer.swapAuthorityAddress(preparedFill),
+                    { cpi: true },
+                );
+                const swapIxes = await swapLayer.completeSplitSwapIxes(
+                    { payer: payer.publicKey, preparedFill, dstMint },
+                    { index: 0, cpiInstruction },
+                );
+
+                await expectIxErr(
+                    connection,
+                    [ComputeBudgetProgram.setComputeUnitLimit({ units: 750_000 }), ...swapIxes],
+                    [payer],
+                    "Error Code: InvalidOutputToken",
+                    { addressLookupTableAccounts },
+                );
+            });
+
+            it("Other (USDT) via Whirlpool", async function () {
+                const dstMint = USDT_MINT_ADDRESS;
+                const { limitAmount, outputToken } = newQuotedSwapOutputToken({
+                    quotedAmountOut: 99_400_000n,
+                    dstMint,
+                    slippageBps: 100,
+                });
+
+                const { preparedFill, recipient, splitRecipient } = await stageSplitForTest({
+                    splitOutputToken: outputToken,
+                });
+                await createAta(connection, payer, dstMint, splitRecipient);
+
+                const addressLookupTableAccounts = await fetchLookupTablesForTest();
+
+                const stagedSplit = swapLayer.stagedSplitAddress(preparedFill);
+                const { outputs } = await swapLayer.fetchStagedSplit(stagedSplit);
+
+                // Swap the split recipient's share.
+                const { instruction: cpiInstruction } = await modifyUsdcToUsdtSwapResponseForTest(
+                    swapLayer.swapAuthorityAddress(preparedFill),
+                    { cpi: true },
+                );
+                const swapIxes = await swapLayer.completeSplitSwapIxes(
+                    { payer: payer.publicKey, preparedFill, dstMint },
+                    { index: 1, cpiInstruction },
+                );
+
+                const dstToken = splToken.getAssociatedTokenAddressSync(dstMint, splitRecipient);
+                const { amount: dstBalanceBefore } = await splToken.getAccount(
+                    connection,
+                    dstToken,
+                );
+
+                await expectIxOk(
+                    connection,
+                    [ComputeBudgetProgram.setComputeUnitLimit({ units: 750_000 }), ...swapIxes],
+                    [payer],
+                    { addressLookupTableAccounts },
+                );
+
+                const { amount: dstBalanceAfter } = await splToken.getAccount(
+                    connection,
+                    dstToken,
+                );
+                assert.isTrue(dstBalanceAfter - dstBalanceBefore >= limitAmount);
+
+                // The swap cannot be performed twice.
+                const { instruction: cpiInstructionAgain } =
+                    await modifyUsdcToUsdtSwapResponseForTest(
+                        swapLayer.swapAuthorityAddress(preparedFill),
+                        { cpi: true },
+                    );
+                const swapIxesAgain = await swapLayer.completeSplitSwapIxes(
+                    { payer: payer.publicKey, preparedFill, dstMint },
+                    { index: 1, cpiInstruction: cpiInstructionAgain },
+                );
+                await expectIxErr(
+                    connection,
+                    [
+                        ComputeBudgetProgram.setComputeUnitLimit({ units: 750_000 }),
+                        ...swapIxesAgain,
+                    ],
+                    [payer],
+                    "Error Code: SplitOutputAlreadyCompleted",
+                    { addressLookupTableAccounts },
+                );
+
+                // Deliver the message recipient's share, which closes the staged split.
+                const recipientBefore = await getUsdcAtaBalance(connection, recipient);
+
+                const transferIx = await swapLayer.completeSplitTransferIx(
+                    { redeemer: payer.publicKey, preparedFill },
+                    0,
+                );
+                await expectIxOk(connection, [transferIx], [payer]);
+
+                const recipientAfter = await getUsdcAtaBalance(connection, recipient);
+                assert.equal(
+                    recipientAfter - recipientBefore,
+                    BigInt(outputs[0].amount.toString()),
+                );
+
+                {
+                    const accInfo = await connection.getAccountInfo(stagedSplit);
+                    assert.isNull(accInfo);
+                }
+                {
+                    const accInfo = await connection.getAccountInfo(
+                        swapLayer.stagedCustodyTokenAddress(stagedSplit),
+                    );
+                    assert.isNull(accInfo);
+                }
+            });
+
+            async function stageSplitForTest(opts: {
+                splitOutputToken: OutputToken | OutputTokenV2;
+            }) {
+                const splitRecipient = Keypair.generate().publicKey;
+                const { preparedFill, recipient } = await redeemSwapLayerFastFillForTest(
+                    { payer: payer.publicKey },
+                    emittedEvents,
+                    {
+                        dstMint: USDC_MINT_ADDRESS,
+                        outputToken: { type: "Usdc" },
+                        amountIn: 200_000_000n,
+                        splitOutputs: [
+                            {
+                                recipient: toUniversal("Solana", splitRecipient.toBytes()),
+                                bps: 5000,
+                                outputToken: toOutputTokenV2ForTest(opts.splitOutputToken),
+                            },
+                        ],
+                    },
+                );
+
+                const ix = await swapLayer.stageSplitIx({
+                    payer: payer.publicKey,
+                    preparedFill,
+                });
+                await expectIxOk(connection, [ix], [payer]);
+
+                return { preparedFill, recipient, splitRecipient };
+            }
+
+            async function fetchLookupTablesForTest() {
+                return Promise.all(
+                    luts.map(async (lookupTableAddress) => {
+                        const resp = await connection.getAddressLookupTable(lookupTableAddress);
+                        return resp.value!;
+                    }),
+                );
+            }
+        });
+    });
+
+    describe("Orca Whirlpool", function () {
+        const emittedEvents: EmittedFilledLocalFastOrder[] = [];
+        let listenerId: number | null;
+
+        // Swaps between USDC and this mint go through the USDC-USDT whirlpool and a USDT whirlpool
+        // created for these tests.
+        let otherMint: PublicKey;
+        let whirlpoolUsdtOther: PublicKey;
+        const orcaLuts: PublicKey[] = [];
+
+        before("Start Event Listener", async function () {
+            listenerId = matchingEngine.onEventCpi((event, slot, signature) => {
+                const { localFastOrderFilled } = event;
+                if (localFastOrderFilled !== undefined) {
+                    emittedEvents.push({
+                        event: localFastOrderFilled,
+                        slot,
+                        signature,
+                    });
+                }
+            });
+        });
+
+        before("Set Up USDT Whirlpool", async function () {
+            otherMint = await splToken.createMint(connection, payer, payer.publicKey, null, 6);
+            const payerOtherToken = await createAta(connection, payer, otherMint, payer.publicKey);
+            await splToken.mintTo(
+                connection,
+                payer,
+                otherMint,
+                payerOtherToken,
+                payer,
+                1_000_000_000_000_000n,
+            );
+
+            whirlpoolUsdtOther = await createWhirlpoolForTest(USDT_MINT_ADDRESS, otherMint);
+
+            const addresses = [orcaWhirlpool.ORCA_WHIRLPOOL_PROGRAM_ID, otherMint];
+            for (const whirlpool of [WHIRLPOOL_USDC_USDT, whirlpoolUsdtOther]) {
+                const { tokenVaultA, tokenVaultB } = await orcaWhirlpool.fetchWhirlpoolSwap(
+                    connection,
+                    whirlpool,
+                    payer.publicKey,
+                    USDT_MINT_ADDRESS,
+                );
+                addresses.push(
+                    whirlpool,
+                    tokenVaultA,
+                    tokenVaultB,
+                    orcaWhirlpool.oracleAddress(whirlpool),
+                );
+            }
+            orcaLuts.push(await createLut(connection, payer, addresses));
+        });
+
+        after("Stop Event Listener", async function () {
+            if (listenerId !== null) {
+                matchingEngine.program.removeEventListener(listenerId!);
+            }
+        });
+
+        describe("Inbound", function () {
+            it("Cannot Swap (Whirlpool Mismatch)", async function () {
+                const dstMint = USDT_MINT_ADDRESS;
+                const limitAmount = 198_000_000n;
+
+                // The encoded whirlpool is not the one the swap goes through.
+                const { preparedFill, recipient } = await redeemSwapLayerFastFillForTest(
+                    { payer: payer.publicKey },
+                    emittedEvents,
+                    {
+                        dstMint,
+                        outputToken: newOrcaWhirlpoolOutputToken(dstMint, limitAmount, [
+                            { whirlpool: whirlpoolUsdtOther, aToB: true },
+                        ]),
+                        amountIn: 200_000_000n,
+                    },
+                );
+
+                const swapAuthority = swapLayer.swapAuthorityAddress(preparedFill);
+                const swap = await orcaWhirlpool.fetchWhirlpoolSwap(
+                    connection,
+                    WHIRLPOOL_USDC_USDT,
+                    swapAuthority,
+                    swapLayer.usdcMint,
+                );
+
+                await completeSwapDirectForTest(
+                    {
+                        payer: payer.publicKey,
+                        preparedFill,
+                        recipient,
+                        dstMint,
+                    },
+                    {
+                        limitAmount,
+                        cpiInstruction: orcaWhirlpool.swapInstruction(swapAuthority, swap, {
+                            cpi: true,
+                        }),
+                        additionalLuts: orcaLuts,
+                        errorMsg: "Error Code: OrcaWhirlpoolMismatch",
+                    },
+                );
+            });
+
+            it("Cannot Swap (Different Intermediate Tokens)", async function () {
+                const { preparedFill, recipient, swapAuthority, swapOne, swapTwo } =
+                    await redeemTwoHopForTest();
+
+                // The second swap takes its input from another USDT account of the swap authority.
+                await createAta(connection, payer, USDT_MINT_ADDRESS, swapAuthority);
+                const otherIntermediateToken = await createNonAtaForTest(
+                    USDT_MINT_ADDRESS,
+                    swapAuthority,
+                );
+
+                await completeSwapDirectForTest(
+                    {
+                        payer: payer.publicKey,
+                        preparedFill,
+                        recipient,
+                        dstMint: otherMint,
+                    },
+                    {
+                        limitAmount: 197_000_000n,
+                        cpiInstruction: orcaWhirlpool.twoHopSwapInstruction(
+                            swapAuthority,
+                            swapOne,
+                            replaceSwapToken(swapTwo, "input", otherIntermediateToken),
+                            { cpi: true },
+                        ),
+                        additionalLuts: orcaLuts,
+                        errorMsg: "Error Code: InvalidIntermediateSwapToken",
+                    },
+                );
+            });
+
+            it("Cannot Swap (Intermediate Token Not Owned by Swap Authority)", async function () {
+                const { preparedFill, recipient, swapAuthority, swapOne, swapTwo } =
+                    await redeemTwoHopForTest();
+
+                const payerUsdtToken = splToken.getAssociatedTokenAddressSync(
+                    USDT_MINT_ADDRESS,
+                    payer.publicKey,
+                );
+
+                await completeSwapDirectForTest(
+                    {
+                        payer: payer.publicKey,
+                        preparedFill,
+                        recipient,
+                        dstMint: otherMint,
+                    },
+                    {
+                        limitAmount: 197_000_000n,
+                        cpiInstruction: orcaWhirlpool.twoHopSwapInstruction(
+                            swapAuthority,
+                            replaceSwapToken(swapOne, "output", payerUsdtToken),
+                            replaceSwapToken(swapTwo, "input", payerUsdtToken),
+                            { cpi: true },
+                        ),
+                        additionalLuts: orcaLuts,
+                        errorMsg: "Error Code: InvalidIntermediateSwapToken",
+                    },
+                );
+            });
+
+            it("Other (USDT) via Whirlpool", async function () {
+                const dstMint = USDT_MINT_ADDRESS;
+                const limitAmount = 198_000_000n;
+
+                const { preparedFill, recipient } = await redeemSwapLayerFastFillForTest(
+                    { payer: payer.publicKey },
+                    emittedEvents,
+                    {
+                        dstMint,
+                        outputToken: newOrcaWhirlpoolOutputToken(dstMint, limitAmount, [
+                            { whirlpool: WHIRLPOOL_USDC_USDT, aToB: true },
+                        ]),
+                        amountIn: 200_000_000n,
+                    },
+                );
+
+                const swapAuthority = swapLayer.swapAuthorityAddress(preparedFill);
+                const swap = await orcaWhirlpool.fetchWhirlpoolSwap(
+                    connection,
+                    WHIRLPOOL_USDC_USDT,
+                    swapAuthority,
+                    swapLayer.usdcMint,
+                );
+
+                await completeSwapDirectForTest(
+                    {
+                        payer: payer.publicKey,
+                        preparedFill,
+                        recipient,
+                        dstMint,
+                    },
+                    {
+                        limitAmount,
+                        cpiInstruction: orcaWhirlpool.swapInstruction(swapAuthority, swap, {
+                            cpi: true,
+                        }),
+                        additionalLuts: orcaLuts,
+                    },
+                );
+            });
+
+            it("Other via Two Whirlpools", async function () {
+                const { preparedFill, recipient, swapAuthority, swapOne, swapTwo } =
+                    await redeemTwoHopForTest();
+
+                // The output of the first swap goes through this token account.
+                await createAta(connection, payer, USDT_MINT_ADDRESS, swapAuthority);
+
+                await completeSwapDirectForTest(
+                    {
+                        payer: payer.publicKey,
+                        preparedFill,
+                        recipient,
+                        dstMint: otherMint,
+                    },
+                    {
+                        limitAmount: 197_000_000n,
+                        cpiInstruction: orcaWhirlpool.twoHopSwapInstruction(
+                            swapAuthority,
+                            swapOne,
+                            swapTwo,
+                            { cpi: true },
+                        ),
+                        additionalLuts: orcaLuts,
+                    },
+                );
+            });
+
+            async function redeemTwoHopForTest() {
+                const swapTwoInfo = await orcaWhirlpool.fetchWhirlpoolSwap(
+                    connection,
+                    whirlpoolUsdtOther,
+                    payer.publicKey,
+                    USDT_MINT_ADDRESS,
+                );
+
+                const { preparedFill, recipient } = await redeemSwapLayerFastFillForTest(
+                    { payer: payer.publicKey },
+                    emittedEvents,
+                    {
+                        dstMint: otherMint,
+                        outputToken: newOrcaWhirlpoolOutputToken(otherMint, 197_000_000n, [
+                            { whirlpool: WHIRLPOOL_USDC_USDT, aToB: true },
+                            { whirlpool: whirlpoolUsdtOther, aToB: swapTwoInfo.aToB },
+                        ]),
+                        amountIn: 200_000_000n,
+                    },
+                );
+
+                const swapAuthority = swapLayer.swapAuthorityAddress(preparedFill);
+                const swapOne = await orcaWhirlpool.fetchWhirlpoolSwap(
+                    connection,
+                    WHIRLPOOL_USDC_USDT,
+                    swapAuthority,
+                    swapLayer.usdcMint,
+                );
+                const swapTwo = await orcaWhirlpool.fetchWhirlpoolSwap(
+                    connection,
+                    whirlpoolUsdtOther,
+                    swapAuthority,
+                    USDT_MINT_ADDRESS,
+                );
+
+                return { preparedFill, recipient, swapAuthority, swapOne, swapTwo };
+            }
+        });
+
+        describe("Outbound", function () {
+            it("Cannot Swap (Account Not Whirlpool)", async function () {
+                const { stagedOutbound, swapAuthority } =
+                    await stageOrcaOutboundForTest(USDT_MINT_ADDRESS);
+
+                const swap = await orcaWhirlpool.fetchWhirlpoolSwap(
+                    connection,
+                    WHIRLPOOL_USDC_USDT,
+                    swapAuthority,
+                    USDT_MINT_ADDRESS,
+                );
+
+                await swapExactInForTest(
+                    {
+                        payer: payer.publicKey,
+                        stagedOutbound,
+                        srcMint: USDT_MINT_ADDRESS,
+                    },
+                    {
+                        cpiInstruction: orcaWhirlpool.swapInstruction(
+                            swapAuthority,
+                            { ...swap, whirlpool: USDT_MINT_ADDRESS },
+                            { cpi: true },
+                        ),
+                    },
+                    {
+                        additionalLuts: orcaLuts,
+                        errorMsg: "Error Code: ConstraintOwner",
+                    },
+                );
+            });
+
+            it("Cannot Swap (Different Intermediate Tokens)", async function () {
+                const { stagedOutbound, swapAuthority, swapOne, swapTwo } =
+                    await stageTwoHopForTest();
+
+                // The second swap takes its input from another USDT account of the swap authority.
+                await createAta(connection, payer, USDT_MINT_ADDRESS, swapAuthority);
+                const otherIntermediateToken = await createNonAtaForTest(
+                    USDT_MINT_ADDRESS,
+                    swapAuthority,
+                );
+
+                await swapExactInForTest(
+                    {
+                        payer: payer.publicKey,
+                        stagedOutbound,
+                        srcMint: otherMint,
+                    },
+                    {
+                        cpiInstruction: orcaWhirlpool.twoHopSwapInstruction(
+                            swapAuthority,
+                            swapOne,
+                            replaceSwapToken(swapTwo, "input", otherIntermediateToken),
+                            { cpi: true },
+                        ),
+                    },
+                    {
+                        additionalLuts: orcaLuts,
+                        errorMsg: "Error Code: InvalidIntermediateSwapToken",
+                    },
+                );
+            });
+
+            it("Cannot Swap (Intermediate Token Not Owned by Swap Authority)", async function () {
+                const { stagedOutbound, swapAuthority, swapOne, swapTwo } =
+                    await stageTwoHopForTest();
+
+                const payerUsdtToken = splToken.getAssociatedTokenAddressSync(
+                    USDT_MINT_ADDRESS,
+                    payer.publicKey,
+                );
+
+                await swapExactInForTest(
+                    {
+                        payer: payer.publicKey,
+                        stagedOutbound,
+                        srcMint: otherMint,
+                    },
+                    {
+                        cpiInstruction: orcaWhirlpool.twoHopSwapInstruction(
+                            swapAuthority,
+                            replaceSwapToken(swapOne, "output", payerUsdtToken),
+                            replaceSwapToken(swapTwo, "input", payerUsdtToken),
+                            { cpi: true },
+                        ),
+                    },
+                    {
+                        additionalLuts: orcaLuts,
+                        errorMsg: "Error Code: InvalidIntermediateSwapToken",
+                    },
+                );
+            });
+
+            it("USDT via Whirlpool", async function () {
+                const { stagedOutbound, stagedCustodyToken, swapAuthority, minAmountOut } =
+                    await stageOrcaOutboundForTest(USDT_MINT_ADDRESS);
+
+                const swap = await orcaWhirlpool.fetchWhirlpoolSwap(
+                    connection,
+                    WHIRLPOOL_USDC_USDT,
+                    swapAuthority,
+                    USDT_MINT_ADDRESS,
+                );
+
+                await swapExactInForTest(
+                    {
+                        payer: payer.publicKey,
+                        stagedOutbound,
+                        srcMint: USDT_MINT_ADDRESS,
+                    },
+                    {
+                        cpiInstruction: orcaWhirlpool.swapInstruction(swapAuthority, swap, {
+                            cpi: true,
+                        }),
+                    },
+                    { additionalLuts: orcaLuts },
+                );
+
+                await checkOrcaOutboundForTest(stagedOutbound, stagedCustodyToken, minAmountOut);
+            });
+
+            it("Other via Two Whirlpools", async function () {
+                const {
+                    stagedOutbound,
+                    stagedCustodyToken,
+                    swapAuthority,
+                    minAmountOut,
+                    swapOne,
+                    swapTwo,
+                } = await stageTwoHopForTest();
+
+                // The output of the first swap goes through this token account.
+                await createAta(connection, payer, USDT_MINT_ADDRESS, swapAuthority);
+
+                await swapExactInForTest(
+                    {
+                        payer: payer.publicKey,
+                        stagedOutbound,
+                        srcMint: otherMint,
+                    },
+                    {
+                        cpiInstruction: orcaWhirlpool.twoHopSwapInstruction(
+                            swapAuthority,
+                            swapOne,
+                            swapTwo,
+                            { cpi: true },
+                        ),
+                    },
+                    { additionalLuts: orcaLuts },
+                );
+
+                await checkOrcaOutboundForTest(stagedOutbound, stagedCustodyToken, minAmountOut);
+            });
+
+            async function stageOrcaOutboundForTest(srcMint: PublicKey) {
+                const { stagedOutbound, stagedCustodyToken, stagedOutboundInfo } =
+                    await stageOutboundForTest({
+                        payer: payer.publicKey,
+                        senderToken: splToken.getAssociatedTokenAddressSync(
+                            srcMint,
+                            payer.publicKey,
+                        ),
+                        srcMint,
+                    });
+
+                const preparedOrder = swapLayer.preparedOrderAddress(stagedOutbound);
+                return {
+                    stagedOutbound,
+                    stagedCustodyToken,
+                    swapAuthority: swapLayer.swapAuthorityAddress(preparedOrder),
+                    minAmountOut: BigInt(stagedOutboundInfo.minAmountOut!.toString()),
+                };
+            }
+
+            async function stageTwoHopForTest() {
+                const staged = await stageOrcaOutboundForTest(otherMint);
+                const { swapAuthority } = staged;
+
+                const swapOne = await orcaWhirlpool.fetchWhirlpoolSwap(
+                    connection,
+                    whirlpoolUsdtOther,
+                    swapAuthority,
+                    otherMint,
+                );
+                const swapTwo = await orcaWhirlpool.fetchWhirlpoolSwap(
+                    connection,
+                    WHIRLPOOL_USDC_USDT,
+                    swapAuthority,
+                    USDT_MINT_ADDRESS,
+                );
+
+                return { ...staged, swapOne, swapTwo };
+            }
+
+            async function checkOrcaOutboundForTest(
+                stagedOutbound: PublicKey,
+                stagedCustodyToken: PublicKey,
+                minAmountOut: bigint,
+            ) {
+                const accInfos = await connection.getMultipleAccountsInfo([
+                    stagedOutbound,
+                    stagedCustodyToken,
+                ]);
+                assert.isTrue(accInfos.every((info) => info === null));
+
+                const preparedOrder = swapLayer.preparedOrderAddress(stagedOutbound);
+                const { amount: preparedCustodyTokenBalance } = await splToken.getAccount(
+                    connection,
+                    tokenRouter.preparedCustodyTokenAddress(preparedOrder),
+                );
+                assert.isTrue(preparedCustodyTokenBalance >= minAmountOut);
+            }
+        });
+
+        function newOrcaWhirlpoolOutputToken(
+            dstMint: PublicKey,
+            limitAmount: bigint,
+            pools: { whirlpool: PublicKey; aToB: boolean }[],
+        ): OutputToken {
+            return {
+                type: "Other",
+                address: toUniversal("Solana", dstMint.toString()),
+                swap: {
+                    deadline: 0,
+                    limitAmount,
+                    type: {
+                        id: "OrcaWhirlpool",
+                        pools: pools.map(({ whirlpool, aToB }) => ({
+                            whirlpool: toUniversal("Solana", whirlpool.toString()),
+                            aToB,
+                        })),
+                    },
+                },
+            };
+        }
+
+        function replaceSwapToken(
+            swap: orcaWhirlpool.WhirlpoolSwap,
+            side: "input" | "output",
+            token: PublicKey,
+        ): orcaWhirlpool.WhirlpoolSwap {
+            return swap.aToB === (side === "input")
+                ? { ...swap, tokenOwnerAccountA: token }
+                : { ...swap, tokenOwnerAccountB: token };
+        }
+
+        async function createNonAtaForTest(mint: PublicKey, owner: PublicKey) {
+            const token = Keypair.generate();
+            await expectIxOk(
+                connection,
+                [
+                    SystemProgram.createAccount({
+                        fromPubkey: payer.publicKey,
+                        newAccountPubkey: token.publicKey,
+                        lamports: await splToken.getMinimumBalanceForRentExemptAccount(connection),
+                        space: splToken.ACCOUNT_SIZE,
+                        programId: splToken.TOKEN_PROGRAM_ID,
+                    }),
+                    splToken.createInitializeAccount3Instruction(token.publicKey, mint, owner),
+                ],
+                [payer, token],
+            );
+
+            return token.publicKey;
+        }
+
+        async function createWhirlpoolForTest(mintOne: PublicKey, mintTwo: PublicKey) {
+            const whirlpoolProgram = new anchor029.Program<Whirlpool>(
+                WHIRLPOOL_IDL,
+                orcaWhirlpool.ORCA_WHIRLPOOL_PROGRAM_ID,
+                { connection } as anchor029.Provider,
+            );
+            const programId = whirlpoolProgram.programId;
+
+            // The Whirlpool program requires the mints to be ordered.
+            const [tokenMintA, tokenMintB] =
+                Buffer.compare(mintOne.toBuffer(), mintTwo.toBuffer()) < 0
+                    ? [mintOne, mintTwo]
+                    : [mintTwo, mintOne];
+
+            const config = Keypair.generate();
+            const tickSpacing = 64;
+            const tickSpacingSeed = Buffer.alloc(2);
+            tickSpacingSeed.writeUInt16LE(tickSpacing);
+
+            const [feeTier] = PublicKey.findProgramAddressSync(
+                [Buffer.from("fee_tier"), config.publicKey.toBuffer(), tickSpacingSeed],
+                programId,
+            );
+            const [whirlpool, whirlpoolBump] = PublicKey.findProgramAddressSync(
+                [
+                    Buffer.from("whirlpool"),
+                    config.publicKey.toBuffer(),
+                    tokenMintA.toBuffer(),
+                    tokenMintB.toBuffer(),
+                    tickSpacingSeed,
+                ],
+                programId,
+            );
+            const tokenVaultA = Keypair.generate();
+            const tokenVaultB = Keypair.generate();
+
+            const initializeConfigIx = await whirlpoolProgram.methods
+                .initializeConfig(payer.publicKey, payer.publicKey, payer.publicKey, 300)
+                .accounts({
+                    config: config.publicKey,
+                    funder: payer.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .instruction();
+            const initializeFeeTierIx = await whirlpoolProgram.methods
+                .initializeFeeTier(tickSpacing, 500)
+                .accounts({
+                    config: config.publicKey,
+                    feeTier,
+                    funder: payer.publicKey,
+                    feeAuthority: payer.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .instruction();
+
+            // Both mints have six decimals, so start at a price of one.
+            const initializePoolIx = await whirlpoolProgram.methods
+                .initializePool({ whirlpoolBump }, tickSpacing, new anchor029.BN(1).shln(64))
+                .accounts({
+                    whirlpoolsConfig: config.publicKey,
+                    tokenMintA,
+                    tokenMintB,
+                    funder: payer.publicKey,
+                    whirlpool,
+                    tokenVaultA: tokenVaultA.publicKey,
+                    tokenVaultB: tokenVaultB.publicKey,
+                    feeTier,
+                    tokenProgram: splToken.TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    rent: SYSVAR_RENT_PUBKEY,
+                })
+                .instruction();
+
+            await expectIxOk(
+                connection,
+                [initializeConfigIx, initializeFeeTierIx, initializePoolIx],
+                [payer, config, tokenVaultA, tokenVaultB],
+            );
+
+            // Initialize tick arrays around the current tick so swaps in either direction have
+            // three tick arrays.
+            const ticksInArray = orcaWhirlpool.TICK_ARRAY_SIZE * tickSpacing;
+            for (const i of [-3, -2, -1, 0, 1, 2, 3]) {
+                const startTickIndex = i * ticksInArray;
+                const ix = await whirlpoolProgram.methods
+                    .initializeTickArray(startTickIndex)
+                    .accounts({
+                        whirlpool,
+                        funder: payer.publicKey,
+                        tickArray: orcaWhirlpool.tickArrayAddress(whirlpool, startTickIndex),
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .instruction();
+                await expectIxOk(connection, [ix], [payer]);
+            }
+
+            // Provide liquidity between the neighboring tick arrays.
+            const positionMint = Keypair.generate();
+            const [position, positionBump] = PublicKey.findProgramAddressSync(
+                [Buffer.from("position"), positionMint.publicKey.toBuffer()],
+                programId,
+            );
+            const positionTokenAccount = splToken.getAssociatedTokenAddressSync(
+                positionMint.publicKey,
+                payer.publicKey,
+            );
+            const [tickLowerIndex, tickUpperIndex] = [-ticksInArray, ticksInArray];
+
+            const openPositionIx = await whirlpoolProgram.methods
+                .openPosition({ positionBump }, tickLowerIndex, tickUpperIndex)
+                .accounts({
+                    funder: payer.publicKey,
+                    owner: payer.publicKey,
+                    position,
+                    positionMint: positionMint.publicKey,
+                    positionTokenAccount,
+                    whirlpool,
+                    tokenProgram: splToken.TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    rent: SYSVAR_RENT_PUBKEY,
+                    associatedTokenProgram: splToken.ASSOCIATED_TOKEN_PROGRAM_ID,
+                })
+                .instruction();
+            const tokenMax = new anchor029.BN(1_000_000_000_000);
+            const increaseLiquidityIx = await whirlpoolProgram.methods
+                .increaseLiquidity(new anchor029.BN(1_000_000_000_000), tokenMax, tokenMax)
+                .accounts({
+                    whirlpool,
+                    tokenProgram: splToken.TOKEN_PROGRAM_ID,
+                    positionAuthority: payer.publicKey,
+                    position,
+                    positionTokenAccount,
+                    tokenOwnerAccountA: splToken.getAssociatedTokenAddressSync(
+                        tokenMintA,
+                        payer.publicKey,
+                    ),
+                    tokenOwnerAccountB: splToken.getAssociatedTokenAddressSync(
+                        tokenMintB,
+                        payer.publicKey,
+                    ),
+                    tokenVaultA: tokenVaultA.publicKey,
+                    tokenVaultB: tokenVaultB.publicKey,
+                    tickArrayLower: orcaWhirlpool.tickArrayAddress(whirlpool, tickLowerIndex),
+                    tickArrayUpper: orcaWhirlpool.tickArrayAddress(whirlpool, tickUpperIndex),
+                })
+                .instruction();
+
+            await expectIxOk(
+                connection,
+                [openPositionIx, increaseLiquidityIx],
+                [payer, positionMint],
+            );
+
+            return whirlpool;
+        }
+    });
+
+    function newQuotedSwapOutputToken(opts: {
+        quotedAmountOut: bigint;
+        dstMint?: PublicKey | null;
+        slippageBps?: number;
+        deadline?: number;
+        dexProgramId?: PublicKey | null;
+        exactOut?: boolean;
+    }) {
+        const { quotedAmountOut } = opts;
+
+        let { dstMint, slippageBps, deadline, dexProgramId, exactOut } = opts;
+        dstMint ??= null;
+        slippageBps ??= 0;
+        deadline ??= 0;
+        dexProgramId ??= null;
+        exactOut ??= false;
+
+        const limitAmount = (quotedAmountOut * (10000n - BigInt(slippageBps))) / 10000n;
+        const swap = {
+            deadline,
+            limitAmount,
+            type: {
+                id: "JupiterV6",
+                dexProgramId:
+                    dexProgramId === null
+                        ? { isSome: false }
+                        : {
+                              isSome: true,
+                              address: toUniversal("Solana", dexProgramId.toString()),
+                          },
+            },
+            // Only version 2 swap messages encode the swap mode.
+            ...(exactOut ? { mode: "ExactOut" } : {}),
+        };
+        return {
+            limitAmount,
+            outputToken: (dstMint === null
+                ? { type: "Gas", swap }
+                : {
+                      type: "Other",
+                      address: toUniversal("Solana", dstMint.toString()),
+                      swap,
+                  }) as OutputToken | OutputTokenV2,
+        };
+    }
+
+    function isExactOutForTest(outputToken: OutputToken | OutputTokenV2): boolean {
+        return (
+            outputToken.type !== "Usdc" &&
+            "mode" in outputToken.swap &&
+            outputToken.swap.mode === "ExactOut"
+        );
+    }
+
+    // Version 1 swaps do not encode the swap mode because they are always exact in.
+    function toOutputTokenV2ForTest(outputToken: OutputToken | OutputTokenV2): OutputTokenV2 {
+        if (outputToken.type === "Usdc" || "mode" in outputToken.swap) {
+            return outputToken as OutputTokenV2;
+        }
+        return { ...outputToken, swap: { ...outputToken.swap, mode: "ExactIn" } } as OutputTokenV2;
+    }
+
+    // For exact out swaps, the USDC not spent by the swap goes to the recipient's USDC token account
+    // instead of the fee recipient token account.
+    async function fetchUsdcBalancesForTest(recipient: PublicKey) {
+        const { feeRecipientToken } = await swapLayer.fetchCustodian();
+        const { amount: feeRecipient } = await splToken.getAccount(connection, feeRecipientToken);
+
+        return {
+            recipient: await getUsdcAtaBalance(connection, recipient),
+            feeRecipient,
+        };
     }
 
     async function completeSwapDirectForTest(
@@ -2948,16 +4170,18 @@ describe("Swap Layer -- Jupiter V6", () => {
             recipient: PublicKey;
             recipientToken?: PublicKey;
             dstMint?: PublicKey;
+            recipientUsdcToken?: PublicKey | null;
         },
         opts: ForTestOpts & {
             limitAmount: bigint;
             outputTokenOverride?: string;
             inAmount?: bigint;
             quotedAmountOut?: bigint;
-            swapResponseModifier: (
+            swapResponseModifier?: (
                 tokenOwner: PublicKey,
                 opts: jupiterV6.ModifySharedAccountsRouteOpts,
             ) => Promise<jupiterV6.ModifiedSharedAccountsRoute>;
+            cpiInstruction?: TransactionInstruction;
             additionalLuts?: PublicKey[];
         },
     ): Promise<undefined> {
@@ -2967,16 +4191,22 @@ describe("Swap Layer -- Jupiter V6", () => {
         let { additionalLuts } = otherOpts;
         additionalLuts ??= [];
 
-        const { instruction: cpiInstruction, destinationMint } = await swapResponseModifier(
-            swapLayer.swapAuthorityAddress(accounts.preparedFill),
-            {
-                cpi: true,
-                inAmount: opts.inAmount,
-                quotedOutAmount: opts.quotedAmountOut,
-            },
-        );
         const expectedDstMint = accounts.dstMint ?? splToken.NATIVE_MINT;
-        assert.deepEqual(destinationMint, expectedDstMint);
+
+        // Use the swap instruction as is if it was not built from a Jupiter V6 swap response.
+        let { cpiInstruction } = otherOpts;
+        if (cpiInstruction === undefined) {
+            const modified = await swapResponseModifier!(
+                swapLayer.swapAuthorityAddress(accounts.preparedFill),
+                {
+                    cpi: true,
+                    inAmount: opts.inAmount,
+                    quotedOutAmount: opts.quotedAmountOut,
+                },
+            );
+            assert.deepEqual(modified.destinationMint, expectedDstMint);
+            cpiInstruction = modified.instruction;
+        }
 
         const swapIxes = await swapLayer.completeSwapDirectIxes(accounts, { cpiInstruction });
 
@@ -3002,7 +4232,7 @@ describe("Swap Layer -- Jupiter V6", () => {
         }
 
         const { redeemerMessage } = await tokenRouter.fetchPreparedFill(accounts.preparedFill);
-        const outputToken = decodeSwapLayerMessage(Uint8Array.from(redeemerMessage)).outputToken;
+        const outputToken = decodeAnySwapLayerMessage(Uint8Array.from(redeemerMessage)).outputToken;
 
         if (outputToken.type === "Gas" || outputTokenOverride === "Gas") {
             const balanceBefore = await connection.getBalance(accounts.recipient).then(BigInt);
@@ -3027,6 +4257,9 @@ describe("Swap Layer -- Jupiter V6", () => {
                 undefined,
                 tokenProgram,
             );
+            const usdcBalancesBefore = isExactOutForTest(outputToken)
+                ? await fetchUsdcBalancesForTest(accounts.recipient)
+                : null;
 
             await expectIxOk(connection, ixs, signers, {
                 addressLookupTableAccounts,
@@ -3038,7 +4271,15 @@ describe("Swap Layer -- Jupiter V6", () => {
                 undefined,
                 tokenProgram,
             );
-            assert.isTrue(dstBalanceAfter - dstBalanceBefore >= limitAmount);
+            if (usdcBalancesBefore !== null) {
+                assert.equal(dstBalanceAfter - dstBalanceBefore, limitAmount);
+
+                const usdcBalancesAfter = await fetchUsdcBalancesForTest(accounts.recipient);
+                assert.isTrue(usdcBalancesAfter.recipient > usdcBalancesBefore.recipient);
+                assert.equal(usdcBalancesAfter.feeRecipient, usdcBalancesBefore.feeRecipient);
+            } else {
+                assert.isTrue(dstBalanceAfter - dstBalanceBefore >= limitAmount);
+            }
         } else {
             assert.fail("Invalid output token type");
         }
@@ -3103,7 +4344,7 @@ describe("Swap Layer -- Jupiter V6", () => {
         }
 
         const { redeemerMessage } = await tokenRouter.fetchPreparedFill(accounts.preparedFill);
-        const swapMsg = decodeSwapLayerMessage(Uint8Array.from(redeemerMessage));
+        const swapMsg = decodeAnySwapLayerMessage(Uint8Array.from(redeemerMessage));
         const selfRedeem = accounts.payer == accounts.recipient;
 
         // Fetch the balance of the fee recipient before the swap.
@@ -3140,6 +4381,9 @@ describe("Swap Layer -- Jupiter V6", () => {
                 dstTokenProgram,
             );
             const balanceBefore = await connection.getBalance(accounts.recipient).then(BigInt);
+            const usdcBalancesBefore = isExactOutForTest(swapMsg.outputToken)
+                ? await fetchUsdcBalancesForTest(accounts.recipient)
+                : null;
 
             await expectIxOk(connection, ixs, signers, {
                 addressLookupTableAccounts,
@@ -3152,7 +4396,15 @@ describe("Swap Layer -- Jupiter V6", () => {
                 dstTokenProgram,
             );
 
-            assert.isTrue(dstBalanceAfter - dstBalanceBefore >= limitAmount);
+            if (usdcBalancesBefore !== null) {
+                assert.equal(dstBalanceAfter - dstBalanceBefore, limitAmount);
+
+                // The fee recipient only receives the relaying fee (checked below).
+                const usdcBalancesAfter = await fetchUsdcBalancesForTest(accounts.recipient);
+                assert.isTrue(usdcBalancesAfter.recipient > usdcBalancesBefore.recipient);
+            } else {
+                assert.isTrue(dstBalanceAfter - dstBalanceBefore >= limitAmount);
+            }
 
             const balanceAfter = await connection.getBalance(accounts.recipient).then(BigInt);
             if (!selfRedeem) {
@@ -3241,14 +4493,16 @@ describe("Swap Layer -- Jupiter V6", () => {
         const { info: preparedFillInfo, redeemerMessage } = await tokenRouter.fetchPreparedFill(
             accounts.preparedFill,
         );
+        const { recipient, redeemMode, outputToken } = decodeAnySwapLayerMessage(
+            Uint8Array.from(redeemerMessage),
+        );
+        const usdcBalancesBefore = isExactOutForTest(outputToken)
+            ? await fetchUsdcBalancesForTest(new PublicKey(recipient.toUint8Array()))
+            : null;
 
         await expectIxOk(connection, ixs, signers, {
             addressLookupTableAccounts,
         });
-
-        const { recipient, redeemMode, outputToken } = decodeSwapLayerMessage(
-            Uint8Array.from(redeemerMessage),
-        );
         if (redeemMode.mode !== "Payload") {
             assert.fail("Not in payload mode");
         }
@@ -3283,7 +4537,17 @@ describe("Swap Layer -- Jupiter V6", () => {
                 undefined,
                 await whichTokenProgram(connection, expectedDstMint),
             );
-            assert.isTrue(amount >= limitAmount);
+            if (usdcBalancesBefore !== null) {
+                assert.equal(amount, limitAmount);
+
+                const usdcBalancesAfter = await fetchUsdcBalancesForTest(
+                    new PublicKey(recipient.toUint8Array()),
+                );
+                assert.isTrue(usdcBalancesAfter.recipient > usdcBalancesBefore.recipient);
+                assert.equal(usdcBalancesAfter.feeRecipient, usdcBalancesBefore.feeRecipient);
+            } else {
+                assert.isTrue(amount >= limitAmount);
+            }
         } else {
             assert.fail("Invalid output token type");
         }
@@ -3296,8 +4560,9 @@ describe("Swap Layer -- Jupiter V6", () => {
             dstMint?: PublicKey;
             recipient?: PublicKey;
             redeemMode?: RedeemMode;
-            outputToken?: OutputToken;
+            outputToken?: OutputToken | OutputTokenV2;
             recipientOverride?: PublicKey;
+            splitOutputs?: SplitOutput[];
         },
         createRecipientAta = true,
     ) {
@@ -3327,15 +4592,26 @@ describe("Swap Layer -- Jupiter V6", () => {
             encodedRecipient = opts.recipientOverride;
         }
 
+        // Exact out swaps and split outputs can only be encoded in version 2 swap messages.
+        const isV2 = isExactOutForTest(outputToken) || opts.splitOutputs !== undefined;
         const msg = {
             recipient: toUniversal("Solana", encodedRecipient.toString()),
             redeemMode,
-            outputToken,
-        } as SwapLayerMessage;
+            outputToken: isV2 ? toOutputTokenV2ForTest(outputToken) : outputToken,
+            ...(isV2
+                ? {
+                      fallbackRecipient: { isSome: false },
+                      integratorFee: { isSome: false },
+                      splitOutputs: opts.splitOutputs ?? [],
+                  }
+                : {}),
+        } as SwapLayerMessage | SwapLayerMessageV2;
 
         // Override redeemer message if undefined.
         if (opts.redeemerMessage === undefined) {
-            opts.redeemerMessage = encodeSwapLayerMessage(msg);
+            opts.redeemerMessage = isV2
+                ? encodeSwapLayerMessageV2(msg as SwapLayerMessageV2)
+                : encodeSwapLayerMessage(msg as SwapLayerMessage);
         }
 
         const settleResult = await settleAuctionNoneLocalForTest(
@@ -3364,7 +4640,10 @@ describe("Swap Layer -- Jupiter V6", () => {
 
         const preparedFill = tokenRouter.preparedFillAddress(fastFill);
         const { redeemerMessage } = await tokenRouter.fetchPreparedFill(preparedFill);
-        assert.deepEqual(decodeSwapLayerMessage(Uint8Array.from(redeemerMessage)), msg);
+        const decoded = isV2
+            ? decodeAnySwapLayerMessage(Uint8Array.from(redeemerMessage))
+            : decodeSwapLayerMessage(Uint8Array.from(redeemerMessage));
+        assert.deepEqual(decoded, msg);
 
         return { preparedFill, recipient };
     }
@@ -3387,6 +4666,21 @@ describe("Swap Layer -- Jupiter V6", () => {
         );
     }
 
+    async function modifyUsdcToUsdtExactOutSwapResponseForTest(
+        tokenOwner: PublicKey,
+        opts: jupiterV6.ModifySharedAccountsRouteOpts,
+    ): Promise<jupiterV6.ModifiedSharedAccountsRoute> {
+        const modified = await modifyUsdcToUsdtSwapResponseForTest(tokenOwner, opts);
+
+        // The Swap Layer overrides the amounts with the limit amount and the fill amount.
+        const { instruction } = jupiterV6.toSharedAccountsExactOutRouteInstruction(
+            modified.instruction,
+            { outAmount: 0n, quotedInAmount: 0n },
+        );
+
+        return { ...modified, instruction };
+    }
+
     async function modifyUsdtToUsdcSwapResponseForTest(
         tokenOwner: PublicKey,
         opts: jupiterV6.ModifySharedAccountsRouteOpts,
@@ -3907,6 +5201,7 @@ describe("Swap Layer -- Jupiter V6", () => {
                 | null;
             outputToken?: OutputToken | null;
             transferType?: "sender" | "native";
+            isExactIn?: boolean;
         } = {},
     ): Promise<{
         amountIn: bigint;
@@ -3920,13 +5215,22 @@ describe("Swap Layer -- Jupiter V6", () => {
         const stagedOutboundSigner = Keypair.generate();
         const stagedOutbound = stagedOutboundSigner.publicKey;
 
-        let { amountIn, minAmountOut, targetChain, redeemOption, outputToken, transferType } = opts;
+        let {
+            amountIn,
+            minAmountOut,
+            targetChain,
+            redeemOption,
+            outputToken,
+            transferType,
+            isExactIn,
+        } = opts;
         amountIn ??= 690000n;
         minAmountOut ??= 680000n;
         targetChain ??= toChainId("Ethereum");
         redeemOption ??= null;
         outputToken ??= null;
         transferType ??= "sender";
+        isExactIn ??= true;
 
         const accInfo = await connection.getAccountInfo(accounts.srcMint);
         if (accInfo === null) {
@@ -3965,10 +5269,10 @@ describe("Swap Layer -- Jupiter V6", () => {
             {
                 transferType,
                 amountIn,
-                isExactIn: true,
+                isExactIn,
                 minAmountOut,
                 targetChain,
-                recipient: Array.from(Buffer.alloc(32, "deadbeef")),
+                recipient: Array.from(Buffer.alloc(32, "deadbeef", "hex").fill(0, 0, 12)),
                 redeemOption,
                 outputToken,
             },
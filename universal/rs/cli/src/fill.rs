@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use liquidity_layer_messages::Fill;
+use serde::{Deserialize, Serialize};
+use swap_layer_messages::{
+    error::Codec,
+    messages::SwapMessage,
+    types::UniversalAddress,
+    wormhole_io::{TypePrefixedPayload, WriteableBytes},
+};
+
+/// Liquidity layer fill whose redeemer message is a swap message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FillJson {
+    pub source_chain: u16,
+    pub order_sender: UniversalAddress,
+    pub redeemer: UniversalAddress,
+    pub redeemer_message: SwapMessage,
+}
+
+impl FillJson {
+    pub fn decode(encoded: &[u8]) -> Result<Self> {
+        let fill = Fill::read_slice(encoded).context("invalid fill")?;
+        let redeemer_message = SwapMessage::try_read_slice(&fill.redeemer_message)
+            .context("invalid redeemer message")?;
+
+        Ok(Self {
+            source_chain: fill.source_chain,
+            order_sender: fill.order_sender.into(),
+            redeemer: fill.redeemer.into(),
+            redeemer_message,
+        })
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        let redeemer_message = WriteableBytes::try_from(self.redeemer_message.try_encode()?)
+            .map_err(|_| anyhow::anyhow!("redeemer message too large"))?;
+
+        Ok(Fill {
+            source_chain: self.source_chain,
+            order_sender: self.order_sender.into(),
+            redeemer: self.redeemer.into(),
+            redeemer_message,
+        }
+        .to_vec())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use super::*;
+
+    #[test]
+    fn test_fill_json_roundtrip() {
+        let encoded = hex!("01f00f0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a0023010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0000");
+
+        let fill = FillJson::decode(&encoded).unwrap();
+        assert_eq!(fill.source_chain, 0xf00f);
+        assert_eq!(
+            fill.redeemer_message.recipient(),
+            &hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d")
+        );
+
+        let json = serde_json::to_string(&fill).unwrap();
+        let decoded = serde_json::from_str::<FillJson>(&json).unwrap();
+        assert_eq!(decoded, fill);
+        assert_eq!(decoded.encode().unwrap(), encoded);
+    }
+
+    #[test]
+    fn test_fill_invalid_redeemer_message() {
+        let encoded = hex!("01f00f0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a000103");
+
+        assert!(FillJson::decode(&encoded).is_err());
+    }
+}
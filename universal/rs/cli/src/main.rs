@@ -0,0 +1,130 @@
+//! Command line tool for swap layer messages: encodes and decodes fills and swap messages,
+//! computes relayer fees and derives the Solana program's PDAs.
+//!
+//! Inputs that are omitted on the command line are read from stdin.
+
+mod fill;
+use fill::*;
+
+mod pda;
+use pda::*;
+
+use std::io::Read;
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use solana_program::pubkey::Pubkey;
+use swap_layer_messages::{
+    error::Codec,
+    messages::SwapMessage,
+    relayer_fees::{calculate_relayer_fee, RelayParams},
+    types::OutputToken,
+};
+
+/// Swap layer program ID.
+const SWAP_LAYER_PROGRAM_ID: &str = "SwapLayer1111111111111111111111111111111111";
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "swap-layer-cli",
+    about = "Swap layer message and account utilities"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Decode a hex encoded liquidity layer fill (including its payload ID) into JSON.
+    DecodeFill { hex: Option<String> },
+    /// Decode a hex encoded swap message (a fill's redeemer message) into JSON.
+    DecodeMessage { hex: Option<String> },
+    /// Encode a JSON fill as hex.
+    EncodeFill { json: Option<String> },
+    /// Encode a JSON swap message as hex.
+    EncodeMessage { json: Option<String> },
+    /// Compute the relayer fee (atomic USDC) charged for a relay to a peer.
+    RelayerFee {
+        /// Relay parameters of the peer as JSON.
+        params: Option<String>,
+        /// Gas dropoff, normalized to 1e-6 of the target chain's native token.
+        #[arg(long, default_value_t = 0)]
+        gas_dropoff: u32,
+        /// Output token as JSON. Defaults to USDC.
+        #[arg(long)]
+        output_token: Option<String>,
+    },
+    /// Derive a PDA of the Solana program.
+    Pda {
+        #[arg(long, default_value = SWAP_LAYER_PROGRAM_ID)]
+        program_id: Pubkey,
+        #[command(subcommand)]
+        account: PdaCommand,
+    },
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::DecodeFill { hex } => {
+            let fill = FillJson::decode(&decode_hex(&read_input(hex)?)?)?;
+            println!("{}", serde_json::to_string_pretty(&fill)?);
+        }
+        Command::DecodeMessage { hex } => {
+            let swap_message = SwapMessage::try_read_slice(&decode_hex(&read_input(hex)?)?)?;
+            println!("{}", serde_json::to_string_pretty(&swap_message)?);
+        }
+        Command::EncodeFill { json } => {
+            let fill = serde_json::from_str::<FillJson>(&read_input(json)?)?;
+            println!("0x{}", hex::encode(fill.encode()?));
+        }
+        Command::EncodeMessage { json } => {
+            let swap_message = serde_json::from_str::<SwapMessage>(&read_input(json)?)?;
+            println!("0x{}", hex::encode(swap_message.try_encode()?));
+        }
+        Command::RelayerFee {
+            params,
+            gas_dropoff,
+            output_token,
+        } => {
+            let relay_params = serde_json::from_str::<RelayParams>(&read_input(params)?)
+                .context("invalid relay parameters")?;
+            let output_token = match output_token {
+                Some(json) => serde_json::from_str(&json).context("invalid output token")?,
+                None => OutputToken::Usdc,
+            };
+
+            println!(
+                "{}",
+                calculate_relayer_fee(&relay_params, gas_dropoff, &output_token)?
+            );
+        }
+        Command::Pda {
+            program_id,
+            account,
+        } => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&account.derive(&program_id))?
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn read_input(arg: Option<String>) -> Result<String> {
+    match arg {
+        Some(arg) => Ok(arg),
+        None => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
+
+fn decode_hex(input: &str) -> Result<Vec<u8>> {
+    let input = input.trim();
+    hex::decode(input.strip_prefix("0x").unwrap_or(input)).context("invalid hex")
+}
@@ -0,0 +1,117 @@
+//! Program derived addresses of the Solana swap layer program.
+
+use clap::Subcommand;
+use serde::Serialize;
+use solana_program::pubkey::Pubkey;
+use swap_layer_messages::seeds::{
+    COMPLETE_TOKEN_SEED_PREFIX, CUSTODIAN_SEED_PREFIX, PEER_SEED_PREFIX,
+    PREPARED_ORDER_SEED_PREFIX, STAGED_CUSTODY_TOKEN_SEED_PREFIX, STAGED_INBOUND_SEED_PREFIX,
+    STAGED_SPLIT_SEED_PREFIX, SWAP_AUTHORITY_SEED_PREFIX,
+};
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum PdaCommand {
+    /// Program config.
+    Custodian,
+    /// Registered peer for a Wormhole chain ID.
+    Peer { chain: u16 },
+    /// Token account holding the funds of a staged outbound, staged inbound or staged split.
+    StagedCustody { staged: Pubkey },
+    /// Token router prepared order for a staged outbound.
+    PreparedOrder { staged_outbound: Pubkey },
+    /// Authority of the swap for a prepared order (outbound) or prepared fill (inbound).
+    SwapAuthority { prepared: Pubkey },
+    /// Staged inbound for a prepared fill.
+    StagedInbound { prepared_fill: Pubkey },
+    /// Staged split for a prepared fill.
+    StagedSplit { prepared_fill: Pubkey },
+    /// Token account used to complete a relayed transfer of a prepared fill.
+    Complete { prepared_fill: Pubkey },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Pda {
+    #[serde(with = "display")]
+    pub address: Pubkey,
+    pub bump: u8,
+}
+
+impl PdaCommand {
+    pub fn derive(&self, program_id: &Pubkey) -> Pda {
+        let (address, bump) = match self {
+            Self::Custodian => Pubkey::find_program_address(&[CUSTODIAN_SEED_PREFIX], program_id),
+            Self::Peer { chain } => {
+                Pubkey::find_program_address(&[PEER_SEED_PREFIX, &chain.to_be_bytes()], program_id)
+            }
+            Self::StagedCustody { staged } => Pubkey::find_program_address(
+                &[STAGED_CUSTODY_TOKEN_SEED_PREFIX, staged.as_ref()],
+                program_id,
+            ),
+            Self::PreparedOrder { staged_outbound } => Pubkey::find_program_address(
+                &[PREPARED_ORDER_SEED_PREFIX, staged_outbound.as_ref()],
+                program_id,
+            ),
+            Self::SwapAuthority { prepared } => Pubkey::find_program_address(
+                &[SWAP_AUTHORITY_SEED_PREFIX, prepared.as_ref()],
+                program_id,
+            ),
+            Self::StagedInbound { prepared_fill } => Pubkey::find_program_address(
+                &[STAGED_INBOUND_SEED_PREFIX, prepared_fill.as_ref()],
+                program_id,
+            ),
+            Self::StagedSplit { prepared_fill } => Pubkey::find_program_address(
+                &[STAGED_SPLIT_SEED_PREFIX, prepared_fill.as_ref()],
+                program_id,
+            ),
+            Self::Complete { prepared_fill } => Pubkey::find_program_address(
+                &[COMPLETE_TOKEN_SEED_PREFIX, prepared_fill.as_ref()],
+                program_id,
+            ),
+        };
+
+        Pda { address, bump }
+    }
+}
+
+mod display {
+    use std::fmt::Display;
+
+    use serde::Serializer;
+
+    pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Display,
+    {
+        serializer.collect_str(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_peer_seeds_big_endian() {
+        let program_id = Pubkey::new_unique();
+        let pda = PdaCommand::Peer { chain: 2 }.derive(&program_id);
+
+        assert_eq!(
+            Pubkey::create_program_address(&[b"peer", &[0u8, 2], &[pda.bump]], &program_id),
+            Ok(pda.address)
+        );
+    }
+
+    #[test]
+    fn test_pda_json() {
+        let pda = Pda {
+            address: Pubkey::default(),
+            bump: 255,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&pda).unwrap(),
+            r#"{"address":"11111111111111111111111111111111","bump":255}"#
+        );
+    }
+}
@@ -0,0 +1,19 @@
+//! Read arbitrary bytes as an output token. Reading must never panic. Any output token that is
+//! read must encode back to the input and its written size must be the encoded length.
+//!
+//! Run with `cargo +nightly fuzz run output_token` from `universal/rs/messages`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use swap_layer_messages::{error::Codec, raw::OutputTokenRef, types::OutputToken};
+
+fuzz_target!(|data: &[u8]| {
+    let decoded = OutputToken::try_read_slice(data);
+    assert_eq!(decoded.is_ok(), OutputTokenRef::parse(data).is_ok());
+
+    if let Ok(output_token) = decoded {
+        assert_eq!(output_token.written_size(), data.len());
+        assert_eq!(output_token.try_encode().unwrap(), data);
+    }
+});
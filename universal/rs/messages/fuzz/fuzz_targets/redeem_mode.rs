@@ -0,0 +1,19 @@
+//! Read arbitrary bytes as a redeem mode. Reading must never panic. Any redeem mode that is read
+//! must encode back to the input and its written size must be the encoded length.
+//!
+//! Run with `cargo +nightly fuzz run redeem_mode` from `universal/rs/messages`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use swap_layer_messages::{error::Codec, raw::RedeemModeRef, types::RedeemMode};
+
+fuzz_target!(|data: &[u8]| {
+    let decoded = RedeemMode::try_read_slice(data);
+    assert_eq!(decoded.is_ok(), RedeemModeRef::parse(data).is_ok());
+
+    if let Ok(redeem_mode) = decoded {
+        assert_eq!(redeem_mode.written_size(), data.len());
+        assert_eq!(redeem_mode.try_encode().unwrap(), data);
+    }
+});
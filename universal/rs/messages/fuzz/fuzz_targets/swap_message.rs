@@ -0,0 +1,25 @@
+//! Read arbitrary bytes as a swap message. Reading must never panic. Any message that is read
+//! must encode back to the input, its written size must be the encoded length, and the zero-copy
+//! view must accept exactly the same inputs.
+//!
+//! Run with `cargo +nightly fuzz run swap_message` from `universal/rs/messages`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use swap_layer_messages::{
+    error::Codec, messages::SwapMessage, raw::SwapMessageRef, wormhole_io::TypePrefixedPayload,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let decoded = SwapMessage::try_read_slice(data).ok();
+    let viewed = SwapMessageRef::parse(data)
+        .and_then(|view| view.decode())
+        .ok();
+    assert_eq!(decoded, viewed);
+
+    if let Some(swap_message) = decoded {
+        assert_eq!(swap_message.written_size(), data.len());
+        assert_eq!(swap_message.try_encode().unwrap(), data);
+    }
+});
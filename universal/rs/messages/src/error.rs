@@ -0,0 +1,448 @@
+use core::fmt;
+
+use alloc::vec::Vec;
+
+use crate::{
+    io,
+    messages::{SwapMessage, SwapMessageV1, SwapMessageV2},
+    types::{OutputToken, RedeemMode, SplitOutput},
+    wormhole_io::{Readable, TypePrefixedPayload, Writeable},
+};
+
+/// Reasons why a swap message (or one of its components) could not be read or written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwapMessageError {
+    /// A discriminant byte does not match any known variant of the field.
+    UnknownDiscriminant { field: &'static str, value: u8 },
+
+    /// The input ended before the field starting at this offset could be read.
+    Truncated { offset: usize },
+
+    /// A swap path has more hops than can be encoded (the path length is encoded as one byte).
+    PathTooLong { field: &'static str, len: usize },
+
+    /// A swap path has a number of hops the swap type cannot execute (e.g. Orca Whirlpools swap
+    /// through one or two pools).
+    UnsupportedPathLength { field: &'static str, len: usize },
+
+    /// A packed path (e.g. for the Uniswap V3 router) is not a token address followed by one or
+    /// more pairs of a pool identifier and token address.
+    InvalidPackedPath { field: &'static str, len: usize },
+
+    /// The message was read successfully, but bytes remain after it.
+    TrailingBytes { offset: usize, remaining: usize },
+
+    /// [SwapType::Invalid](crate::types::SwapType::Invalid) has no encoding.
+    InvalidSwapType,
+
+    /// The swap type cannot be executed on the target chain.
+    SwapTypeNotSupported { chain: u16 },
+
+    /// The limit amount exceeds the largest token amount on the target chain.
+    LimitAmountTooLarge { chain: u16, limit_amount: u128 },
+
+    /// The address is not a 20-byte EVM address left-padded with zeros.
+    InvalidEvmAddress { field: &'static str },
+
+    /// The string is neither a 0x-prefixed hex (20 or 32 bytes) nor a base58 (32 bytes) address.
+    InvalidAddress,
+
+    /// The mixed-case hex address does not match its checksum (EIP-55).
+    InvalidAddressChecksum,
+
+    /// The value does not fit in the number of bytes used to encode the field.
+    ValueOutOfRange { field: &'static str, value: u64 },
+
+    /// The payload is longer than its u16 length prefix allows.
+    PayloadTooLarge { len: usize },
+
+    /// An output token other than USDC requires a swap route.
+    MissingSwapRoute,
+
+    /// A swap route was specified, but the output token is USDC.
+    UnexpectedSwapRoute,
+
+    /// A message has more split outputs than the maximum.
+    TooManySplitOutputs { len: usize },
+
+    /// Split outputs can only be delivered directly or by a relayer.
+    SplitRedeemModeNotSupported,
+
+    /// The redeem mode cannot be encoded in this version of the swap message (relaying with a
+    /// payload requires version 2).
+    RedeemModeNotSupported { version: u8 },
+
+    /// The swap mode cannot be encoded in this version of the swap message (exact out swaps
+    /// require version 2).
+    SwapModeNotSupported { version: u8 },
+
+    /// Any other I/O error.
+    Io(io::ErrorKind),
+}
+
+impl fmt::Display for SwapMessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownDiscriminant { field, value } => {
+                write!(f, "unknown {field} discriminant: {value}")
+            }
+            Self::Truncated { offset } => write!(f, "input truncated at offset {offset}"),
+            Self::PathTooLong { field, len } => {
+                write!(
+                    f,
+                    "{field} has {len} hops, exceeding the maximum of {}",
+                    u8::MAX
+                )
+            }
+            Self::UnsupportedPathLength { field, len } => {
+                write!(f, "{field} has unsupported number of hops: {len}")
+            }
+            Self::InvalidPackedPath { field, len } => {
+                write!(f, "{field} has invalid packed length {len}")
+            }
+            Self::TrailingBytes { offset, remaining } => {
+                write!(f, "{remaining} trailing bytes at offset {offset}")
+            }
+            Self::InvalidSwapType => write!(f, "invalid swap type cannot be encoded"),
+            Self::SwapTypeNotSupported { chain } => {
+                write!(f, "swap type not supported on chain {chain}")
+            }
+            Self::LimitAmountTooLarge {
+                chain,
+                limit_amount,
+            } => write!(f, "limit amount {limit_amount} too large for chain {chain}"),
+            Self::InvalidEvmAddress { field } => write!(f, "{field} is not an EVM address"),
+            Self::InvalidAddress => write!(f, "invalid hex or base58 address"),
+            Self::InvalidAddressChecksum => write!(f, "invalid address checksum"),
+            Self::ValueOutOfRange { field, value } => {
+                write!(f, "{field} value {value} is out of range")
+            }
+            Self::PayloadTooLarge { len } => {
+                write!(
+                    f,
+                    "payload length {len} exceeds the maximum of {}",
+                    u16::MAX
+                )
+            }
+            Self::MissingSwapRoute => write!(f, "output token requires a swap route"),
+            Self::UnexpectedSwapRoute => write!(f, "USDC output token cannot have a swap route"),
+            Self::TooManySplitOutputs { len } => {
+                write!(
+                    f,
+                    "{len} split outputs exceed the maximum of {}",
+                    SplitOutput::MAX_OUTPUTS
+                )
+            }
+            Self::SplitRedeemModeNotSupported => {
+                write!(f, "split outputs require a direct or relay redeem mode")
+            }
+            Self::RedeemModeNotSupported { version } => {
+                write!(
+                    f,
+                    "redeem mode not supported by swap message version {version}"
+                )
+            }
+            Self::SwapModeNotSupported { version } => {
+                write!(
+                    f,
+                    "swap mode not supported by swap message version {version}"
+                )
+            }
+            Self::Io(kind) => write!(f, "io error: {kind}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SwapMessageError {}
+
+impl From<SwapMessageError> for io::Error {
+    fn from(err: SwapMessageError) -> Self {
+        match err {
+            SwapMessageError::Truncated { .. } => io::Error::new(io::ErrorKind::UnexpectedEof, err),
+            SwapMessageError::Io(kind) => kind.into(),
+            _ => io::Error::new(io::ErrorKind::InvalidData, err),
+        }
+    }
+}
+
+impl From<io::Error> for SwapMessageError {
+    fn from(err: io::Error) -> Self {
+        #[cfg(feature = "std")]
+        let inner = err
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<SwapMessageError>());
+        #[cfg(not(feature = "std"))]
+        let inner = err.get_ref();
+
+        match inner {
+            Some(inner) => inner.clone(),
+            None => Self::Io(err.kind()),
+        }
+    }
+}
+
+/// Read and write swap message types, reporting failures as [SwapMessageError].
+pub trait Codec: Sized {
+    /// Read from a slice, which must contain exactly one encoded value.
+    fn try_read_slice(buf: &[u8]) -> Result<Self, SwapMessageError>;
+
+    /// Encode to bytes.
+    fn try_encode(&self) -> Result<Vec<u8>, SwapMessageError>;
+}
+
+/// Reader that keeps track of the offset into the slice so truncation can be reported.
+struct SliceReader<'a> {
+    buf: &'a [u8],
+    offset: usize,
+}
+
+impl SliceReader<'_> {
+    fn remaining(&self) -> &[u8] {
+        self.buf.get(self.offset..).unwrap_or_default()
+    }
+}
+
+impl io::Read for SliceReader<'_> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let len = self.remaining().len().min(out.len());
+        self.read_exact(out.get_mut(..len).unwrap_or_default())?;
+        Ok(len)
+    }
+
+    fn read_exact(&mut self, out: &mut [u8]) -> io::Result<()> {
+        match self.remaining().get(..out.len()) {
+            Some(bytes) => {
+                out.copy_from_slice(bytes);
+                self.offset = self.offset.saturating_add(out.len());
+                Ok(())
+            }
+            None => Err(SwapMessageError::Truncated {
+                offset: self.offset,
+            }
+            .into()),
+        }
+    }
+}
+
+fn read_slice_with<T, F>(buf: &[u8], read: F) -> Result<T, SwapMessageError>
+where
+    F: FnOnce(&mut SliceReader) -> io::Result<T>,
+{
+    let mut reader = SliceReader { buf, offset: 0 };
+    let out = read(&mut reader)?;
+
+    match reader.remaining().len() {
+        0 => Ok(out),
+        remaining => Err(SwapMessageError::TrailingBytes {
+            offset: reader.offset,
+            remaining,
+        }),
+    }
+}
+
+fn read_payload_slice<P: TypePrefixedPayload<1>>(buf: &[u8]) -> Result<P, SwapMessageError> {
+    read_slice_with(buf, |reader| {
+        if let Some([expected]) = P::TYPE {
+            let version = u8::read(reader)?;
+            if version != expected {
+                return Err(SwapMessageError::UnknownDiscriminant {
+                    field: "version",
+                    value: version,
+                }
+                .into());
+            }
+        }
+
+        P::read(reader)
+    })
+}
+
+fn encode_payload<P: TypePrefixedPayload<1>>(payload: &P) -> Result<Vec<u8>, SwapMessageError> {
+    let mut buf = Vec::with_capacity(payload.written_size().saturating_add(1));
+    payload.write_payload(&mut buf)?;
+    Ok(buf)
+}
+
+impl Codec for SwapMessage {
+    fn try_read_slice(buf: &[u8]) -> Result<Self, SwapMessageError> {
+        read_payload_slice(buf)
+    }
+
+    fn try_encode(&self) -> Result<Vec<u8>, SwapMessageError> {
+        encode_payload(self)
+    }
+}
+
+impl Codec for SwapMessageV1 {
+    fn try_read_slice(buf: &[u8]) -> Result<Self, SwapMessageError> {
+        read_payload_slice(buf)
+    }
+
+    fn try_encode(&self) -> Result<Vec<u8>, SwapMessageError> {
+        encode_payload(self)
+    }
+}
+
+impl Codec for SwapMessageV2 {
+    fn try_read_slice(buf: &[u8]) -> Result<Self, SwapMessageError> {
+        read_payload_slice(buf)
+    }
+
+    fn try_encode(&self) -> Result<Vec<u8>, SwapMessageError> {
+        encode_payload(self)
+    }
+}
+
+impl Codec for RedeemMode {
+    fn try_read_slice(buf: &[u8]) -> Result<Self, SwapMessageError> {
+        read_slice_with(buf, |reader| Readable::read(reader))
+    }
+
+    fn try_encode(&self) -> Result<Vec<u8>, SwapMessageError> {
+        let mut buf = Vec::with_capacity(self.written_size());
+        self.write(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+impl Codec for OutputToken {
+    fn try_read_slice(buf: &[u8]) -> Result<Self, SwapMessageError> {
+        read_slice_with(buf, |reader| Readable::read(reader))
+    }
+
+    fn try_encode(&self) -> Result<Vec<u8>, SwapMessageError> {
+        let mut buf = Vec::with_capacity(self.written_size());
+        self.write(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use crate::types::{
+        OutputSwap, SwapMode, SwapType, Uint24, UniswapSwapParameters, UniswapSwapPath,
+    };
+
+    use super::*;
+
+    const V1_USDC_DIRECT: [u8; 35] =
+        hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0000");
+
+    #[test]
+    fn test_read_unknown_discriminant() {
+        let mut encoded = V1_USDC_DIRECT;
+
+        encoded[0] = 3;
+        assert_eq!(
+            SwapMessage::try_read_slice(&encoded),
+            Err(SwapMessageError::UnknownDiscriminant {
+                field: "version",
+                value: 3
+            })
+        );
+        assert_eq!(
+            SwapMessageV1::try_read_slice(&encoded),
+            Err(SwapMessageError::UnknownDiscriminant {
+                field: "version",
+                value: 3
+            })
+        );
+
+        encoded[0] = 1;
+        encoded[33] = 7;
+        assert_eq!(
+            SwapMessageV1::try_read_slice(&encoded),
+            Err(SwapMessageError::UnknownDiscriminant {
+                field: "redeem_mode",
+                value: 7
+            })
+        );
+
+        encoded[33] = 0;
+        encoded[34] = 9;
+        assert_eq!(
+            SwapMessageV1::try_read_slice(&encoded),
+            Err(SwapMessageError::UnknownDiscriminant {
+                field: "output_token",
+                value: 9
+            })
+        );
+
+        assert_eq!(
+            OutputToken::try_read_slice(&hex!("010000000000000000000000000000000000000000ff")),
+            Err(SwapMessageError::UnknownDiscriminant {
+                field: "swap_type",
+                value: 255
+            })
+        );
+    }
+
+    #[test]
+    fn test_read_truncated_and_trailing_bytes() {
+        // Missing the output token.
+        assert_eq!(
+            SwapMessage::try_read_slice(&V1_USDC_DIRECT[..34]),
+            Err(SwapMessageError::Truncated { offset: 34 })
+        );
+
+        // Relay redeem mode is missing its relaying fee.
+        assert_eq!(
+            SwapMessageV1::try_read_slice(&hex!(
+                "010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d020000000000"
+            )),
+            Err(SwapMessageError::Truncated { offset: 38 })
+        );
+
+        let mut encoded = V1_USDC_DIRECT.to_vec();
+        encoded.extend_from_slice(&[0xde, 0xad]);
+        assert_eq!(
+            SwapMessage::try_read_slice(&encoded),
+            Err(SwapMessageError::TrailingBytes {
+                offset: 35,
+                remaining: 2
+            })
+        );
+
+        let swap_message = SwapMessage::try_read_slice(&V1_USDC_DIRECT).unwrap();
+        assert_eq!(swap_message.try_encode().unwrap(), V1_USDC_DIRECT);
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        let output_token = |swap_type| {
+            OutputToken::Gas(OutputSwap {
+                deadline: 0,
+                limit_amount: 0,
+                swap_type,
+                mode: SwapMode::ExactIn,
+            })
+        };
+
+        assert_eq!(
+            output_token(SwapType::Invalid).try_encode(),
+            Err(SwapMessageError::InvalidSwapType)
+        );
+
+        let path = vec![
+            UniswapSwapPath {
+                evm_address: [0; 20],
+                fee: Uint24::ZERO,
+            };
+            256
+        ];
+        assert_eq!(
+            output_token(SwapType::UniswapV3(UniswapSwapParameters {
+                first_leg_fee: Uint24::ZERO,
+                path,
+            }))
+            .try_encode(),
+            Err(SwapMessageError::PathTooLong {
+                field: "uniswap_v3.path",
+                len: 256
+            })
+        );
+    }
+}
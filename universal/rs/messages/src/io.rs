@@ -0,0 +1,175 @@
+//! I/O traits used to read and write swap messages.
+//!
+//! With the `std` feature these are re-exported from `std::io`. Otherwise this module defines the
+//! small subset of `std::io` that the encoder needs, so messages can be read and written in
+//! `no_std` environments (e.g. zkVM guests).
+
+#[cfg(feature = "std")]
+pub use std::io::{sink, Error, ErrorKind, Read, Result, Sink, Write};
+
+#[cfg(not(feature = "std"))]
+pub use self::core_io::*;
+
+#[cfg(not(feature = "std"))]
+mod core_io {
+    use core::fmt;
+
+    use alloc::vec::Vec;
+
+    use crate::error::SwapMessageError;
+
+    /// Subset of `std::io::ErrorKind` produced when reading and writing swap messages.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ErrorKind {
+        InvalidData,
+        InvalidInput,
+        UnexpectedEof,
+        WriteZero,
+        Other,
+    }
+
+    impl fmt::Display for ErrorKind {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::InvalidData => write!(f, "invalid data"),
+                Self::InvalidInput => write!(f, "invalid input parameter"),
+                Self::UnexpectedEof => write!(f, "unexpected end of file"),
+                Self::WriteZero => write!(f, "write zero"),
+                Self::Other => write!(f, "other error"),
+            }
+        }
+    }
+
+    /// Error returned by [Read] and [Write]. Like `std::io::Error`, it may wrap the
+    /// [SwapMessageError] that caused it.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Error {
+        kind: ErrorKind,
+        inner: Option<SwapMessageError>,
+    }
+
+    impl Error {
+        pub fn new(kind: ErrorKind, inner: SwapMessageError) -> Self {
+            Self {
+                kind,
+                inner: Some(inner),
+            }
+        }
+
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+
+        pub fn get_ref(&self) -> Option<&SwapMessageError> {
+            self.inner.as_ref()
+        }
+    }
+
+    impl From<ErrorKind> for Error {
+        fn from(kind: ErrorKind) -> Self {
+            Self { kind, inner: None }
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match &self.inner {
+                Some(inner) => inner.fmt(f),
+                None => self.kind.fmt(f),
+            }
+        }
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    pub trait Read {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+        fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.read(buf)? {
+                    0 => return Err(ErrorKind::UnexpectedEof.into()),
+                    len => buf = core::mem::take(&mut buf).get_mut(len..).unwrap_or_default(),
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    impl Read for &[u8] {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let len = self.len().min(buf.len());
+            let (head, tail) = self.split_at(len);
+            buf.get_mut(..len).unwrap_or_default().copy_from_slice(head);
+            *self = tail;
+            Ok(len)
+        }
+    }
+
+    impl<R: Read + ?Sized> Read for &mut R {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            (**self).read(buf)
+        }
+
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+            (**self).read_exact(buf)
+        }
+    }
+
+    pub trait Write {
+        fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+        fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.write(buf)? {
+                    0 => return Err(ErrorKind::WriteZero.into()),
+                    len => buf = buf.get(len..).unwrap_or_default(),
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    impl Write for Vec<u8> {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+    }
+
+    impl Write for &mut [u8] {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            let len = self.len().min(buf.len());
+            let (head, tail) = core::mem::take(self).split_at_mut(len);
+            head.copy_from_slice(buf.get(..len).unwrap_or_default());
+            *self = tail;
+            Ok(len)
+        }
+    }
+
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            (**self).write(buf)
+        }
+
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            (**self).write_all(buf)
+        }
+    }
+
+    /// Writer that discards everything written to it. See [sink].
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct Sink;
+
+    impl Write for Sink {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            Ok(buf.len())
+        }
+    }
+
+    pub fn sink() -> Sink {
+        Sink
+    }
+}
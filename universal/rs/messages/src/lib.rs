@@ -1,5 +1,39 @@
+//! Swap Layer message encoding.
+//!
+//! [relayer_fees] and [seeds] are shared by the Solana program and off-chain tools so that fees
+//! and derived addresses are computed the same way everywhere.
+//!
+//! The `std` feature (enabled by default) uses `std::io` and the `wormhole-io` crate. Without it,
+//! the crate only requires `alloc`, and [io] and [wormhole_io] provide equivalent traits.
+//!
+//! The `solana` feature adds conversions between [types::UniversalAddress] and Solana public keys.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod error;
+
+pub mod io;
+
 pub mod messages;
 
+pub mod raw;
+
+pub mod relayer_fees;
+
+pub mod seeds;
+
 pub mod types;
 
+#[cfg(feature = "std")]
 pub use wormhole_io;
+
+#[cfg(not(feature = "std"))]
+pub mod wormhole_io;
+
+#[cfg(feature = "serde")]
+mod serde_utils;
+
+#[cfg(feature = "proptest")]
+mod strategies;
@@ -0,0 +1,526 @@
+use alloc::vec::Vec;
+
+use crate::{
+    error::SwapMessageError,
+    io,
+    types::{
+        JupiterV6SwapParameters, OrcaWhirlpoolPool, OrcaWhirlpoolSwapParameters, OutputSwap,
+        OutputToken, RedeemMode, SwapMode, SwapType, TraderJoePoolId, TraderJoeSwapParameters,
+        TraderJoeSwapPath, Uint24, Uint48, UniswapSwapParameters, UniswapSwapPath,
+        UniversalAddress,
+    },
+    wormhole_io::Writeable,
+};
+
+use super::{SwapMessageV1, SwapMessageV2};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Output {
+    Usdc,
+    Gas,
+    Other([u8; 32]),
+}
+
+/// Builder for [SwapMessageV1] (or [SwapMessageV2] with [build_v2](Self::build_v2)). The message
+/// delivers USDC directly to the recipient unless configured otherwise. Any other output token
+/// requires a route (e.g. [uniswap_v3_route](Self::uniswap_v3_route) or
+/// [jupiter_v6](Self::jupiter_v6)).
+///
+/// Invalid arguments are not reported until [build](Self::build) is called, which also checks that
+/// the message can be encoded (and executed on the target chain if one is specified).
+#[derive(Debug, Clone)]
+pub struct SwapMessageBuilder {
+    recipient: [u8; 32],
+    redeem_mode: RedeemMode,
+    output: Output,
+    deadline: u32,
+    limit_amount: u128,
+    swap_type: Option<SwapType>,
+    mode: SwapMode,
+    target_chain: Option<u16>,
+    error: Option<SwapMessageError>,
+}
+
+impl SwapMessageBuilder {
+    /// Addresses (here and elsewhere in the builder) may be raw bytes or a [UniversalAddress], e.g.
+    /// one parsed from a string or converted with [UniversalAddress::from_evm].
+    pub fn new(recipient: impl Into<UniversalAddress>) -> Self {
+        Self {
+            recipient: recipient.into().to_bytes(),
+            redeem_mode: RedeemMode::Direct,
+            output: Output::Usdc,
+            deadline: 0,
+            limit_amount: 0,
+            swap_type: None,
+            mode: SwapMode::ExactIn,
+            target_chain: None,
+            error: None,
+        }
+    }
+
+    /// Redeem directly to the recipient. This is the default.
+    pub fn direct(mut self) -> Self {
+        self.redeem_mode = RedeemMode::Direct;
+        self
+    }
+
+    /// Redeem via relayer. The gas dropoff is in micro-units of the target chain's gas token
+    /// (1e-6) and the relaying fee is in atomic USDC units.
+    pub fn relay(mut self, gas_dropoff: u32, relaying_fee: u64) -> Self {
+        match Uint48::try_from(relaying_fee) {
+            Ok(relaying_fee) => {
+                self.redeem_mode = RedeemMode::Relay {
+                    gas_dropoff,
+                    relaying_fee,
+                };
+                self
+            }
+            Err(_) => self.fail(SwapMessageError::ValueOutOfRange {
+                field: "relay.relaying_fee",
+                value: relaying_fee,
+            }),
+        }
+    }
+
+    /// Redeem with an arbitrary payload for the recipient (a contract or program).
+    pub fn payload(mut self, sender: impl Into<UniversalAddress>, buf: impl Into<Vec<u8>>) -> Self {
+        let buf = buf.into();
+        let len = buf.len();
+        match buf.try_into() {
+            Ok(buf) => {
+                self.redeem_mode = RedeemMode::Payload {
+                    sender: sender.into().to_bytes(),
+                    buf,
+                };
+                self
+            }
+            Err(_) => self.fail(SwapMessageError::PayloadTooLarge { len }),
+        }
+    }
+
+    /// Deliver USDC. This is the default.
+    pub fn output_usdc(mut self) -> Self {
+        self.output = Output::Usdc;
+        self
+    }
+
+    /// Swap into the target chain's gas token.
+    pub fn output_gas(mut self) -> Self {
+        self.output = Output::Gas;
+        self
+    }
+
+    /// Swap into the token with this (universal) address.
+    pub fn output_other(mut self, address: impl Into<UniversalAddress>) -> Self {
+        self.output = Output::Other(address.into().to_bytes());
+        self
+    }
+
+    /// Unix timestamp after which the swap is no longer executed. Zero means no deadline.
+    pub fn deadline(mut self, deadline: u32) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Minimum amount of the output token the swap must produce (or the exact amount for
+    /// [exact_out](Self::exact_out)).
+    pub fn limit_amount(mut self, limit_amount: u128) -> Self {
+        self.limit_amount = limit_amount;
+        self
+    }
+
+    /// Swap for exactly the limit amount of the output token. Any USDC the swap does not use goes
+    /// to the recipient. Only Jupiter V6 swaps can be exact out and only [SwapMessageV2] can
+    /// encode them, so the message must be built with [build_v2](Self::build_v2).
+    pub fn exact_out(mut self) -> Self {
+        self.mode = SwapMode::ExactOut;
+        self
+    }
+
+    /// Swap through Uniswap V3 pools. Fees are in Uniswap's units of hundredths of a basis point
+    /// (e.g. 500 is 0.05%). Each hop of the path is the token swapped into next and the fee of the
+    /// pool used for the following swap.
+    pub fn uniswap_v3_route(
+        self,
+        first_fee: u32,
+        path: impl IntoIterator<Item = ([u8; 20], u32)>,
+    ) -> Self {
+        let first_leg_fee = match to_fee("uniswap_v3.first_leg_fee", first_fee) {
+            Ok(fee) => fee,
+            Err(err) => return self.fail(err),
+        };
+
+        let path = path
+            .into_iter()
+            .map(|(evm_address, fee)| {
+                to_fee("uniswap_v3.path", fee).map(|fee| UniswapSwapPath { evm_address, fee })
+            })
+            .collect::<Result<_, _>>();
+
+        match path {
+            Ok(path) => self.route(SwapType::UniswapV3(UniswapSwapParameters {
+                first_leg_fee,
+                path,
+            })),
+            Err(err) => self.fail(err),
+        }
+    }
+
+    /// Swap through Trader Joe pools.
+    pub fn trader_joe_route(
+        self,
+        first_pool_id: TraderJoePoolId,
+        path: impl IntoIterator<Item = ([u8; 20], TraderJoePoolId)>,
+    ) -> Self {
+        self.route(SwapType::TraderJoe(TraderJoeSwapParameters {
+            first_pool_id,
+            path: path
+                .into_iter()
+                .map(|(evm_address, pool_id)| TraderJoeSwapPath {
+                    evm_address,
+                    pool_id,
+                })
+                .collect(),
+        }))
+    }
+
+    /// Swap with Jupiter V6, optionally restricted to a single DEX program.
+    pub fn jupiter_v6(self, dex_program_id: Option<[u8; 32]>) -> Self {
+        self.route(SwapType::JupiterV6(JupiterV6SwapParameters {
+            dex_program_id,
+        }))
+    }
+
+    /// Swap through one or two Orca Whirlpools, each given as the whirlpool account and whether
+    /// its token A is swapped for token B.
+    pub fn orca_whirlpool(self, pools: impl IntoIterator<Item = ([u8; 32], bool)>) -> Self {
+        self.route(SwapType::OrcaWhirlpool(OrcaWhirlpoolSwapParameters {
+            pools: pools
+                .into_iter()
+                .map(|(whirlpool, a_to_b)| OrcaWhirlpoolPool { whirlpool, a_to_b })
+                .collect(),
+        }))
+    }
+
+    /// Set the route directly.
+    pub fn route(mut self, swap_type: SwapType) -> Self {
+        self.swap_type = Some(swap_type);
+        self
+    }
+
+    /// Check that the message can be executed on this chain (Wormhole chain ID) when it is built.
+    /// See [SwapMessageV1::validate_for_target].
+    pub fn target_chain(mut self, chain: u16) -> Self {
+        self.target_chain = Some(chain);
+        self
+    }
+
+    pub fn build(self) -> Result<SwapMessageV1, SwapMessageError> {
+        let target_chain = self.target_chain;
+        let msg = self.into_message()?;
+
+        // Catch anything that cannot be encoded (e.g. paths that are too long).
+        msg.write(&mut io::sink())?;
+
+        if let Some(chain) = target_chain {
+            msg.validate_for_target(chain)?;
+        }
+
+        Ok(msg)
+    }
+
+    /// Build a [SwapMessageV2] without any of the fields added by the second version. Exact out
+    /// swaps require this version.
+    pub fn build_v2(self) -> Result<SwapMessageV2, SwapMessageError> {
+        let target_chain = self.target_chain;
+        let msg = SwapMessageV2::from(self.into_message()?);
+
+        // Catch anything that cannot be encoded (e.g. paths that are too long).
+        msg.write(&mut io::sink())?;
+
+        if let Some(chain) = target_chain {
+            msg.validate_for_target(chain)?;
+        }
+
+        Ok(msg)
+    }
+
+    /// Fields shared by all versions of the message, which may not be encodable yet.
+    fn into_message(self) -> Result<SwapMessageV1, SwapMessageError> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+
+        let swap = |swap_type| OutputSwap {
+            deadline: self.deadline,
+            limit_amount: self.limit_amount,
+            swap_type,
+            mode: self.mode,
+        };
+
+        let output_token = match (self.output, self.swap_type) {
+            (Output::Usdc, None) => OutputToken::Usdc,
+            (Output::Usdc, Some(_)) => return Err(SwapMessageError::UnexpectedSwapRoute),
+            (Output::Gas | Output::Other(_), None) => {
+                return Err(SwapMessageError::MissingSwapRoute)
+            }
+            (Output::Gas, Some(swap_type)) => OutputToken::Gas(swap(swap_type)),
+            (Output::Other(address), Some(swap_type)) => OutputToken::Other {
+                address,
+                swap: swap(swap_type),
+            },
+        };
+
+        Ok(SwapMessageV1 {
+            recipient: self.recipient,
+            redeem_mode: self.redeem_mode,
+            output_token,
+        })
+    }
+
+    fn fail(mut self, err: SwapMessageError) -> Self {
+        self.error.get_or_insert(err);
+        self
+    }
+}
+
+fn to_fee(field: &'static str, fee: u32) -> Result<Uint24, SwapMessageError> {
+    Uint24::try_from(fee).map_err(|_| SwapMessageError::ValueOutOfRange {
+        field,
+        value: fee.into(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use crate::{
+        error::Codec,
+        types::{TraderJoeVersion, UniswapSwapPath, SOLANA_CHAIN},
+        wormhole_io::TypePrefixedPayload,
+    };
+
+    use super::*;
+
+    const RECIPIENT: [u8; 32] =
+        hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d");
+
+    #[test]
+    fn test_build_uniswap_v3_route() {
+        let swap_message = SwapMessageBuilder::new(RECIPIENT)
+            .output_gas()
+            .uniswap_v3_route(
+                500,
+                [(hex!("5991a2df15a8f6a256d3ec51e99254cd3fb576a9"), 500)],
+            )
+            .target_chain(2)
+            .build()
+            .unwrap();
+
+        let encoded = hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d00010000000000000000000000000000000000000000010001f4015991a2df15a8f6a256d3ec51e99254cd3fb576a90001f4");
+        assert_eq!(
+            swap_message,
+            SwapMessageV1::try_read_slice(&encoded).unwrap()
+        );
+        assert_eq!(swap_message.to_vec(), encoded);
+        assert_eq!(swap_message.written_size().saturating_add(1), encoded.len());
+    }
+
+    #[test]
+    fn test_build_written_size() {
+        let builders = [
+            SwapMessageBuilder::new(RECIPIENT),
+            SwapMessageBuilder::new(RECIPIENT).relay(42069, 1000000),
+            SwapMessageBuilder::new(RECIPIENT).payload([1; 32], hex!("deadbeef")),
+            SwapMessageBuilder::new(RECIPIENT)
+                .output_other([2; 32])
+                .deadline(69)
+                .limit_amount(420)
+                .jupiter_v6(Some([3; 32])),
+            SwapMessageBuilder::new(RECIPIENT)
+                .output_gas()
+                .trader_joe_route(
+                    TraderJoePoolId {
+                        version: TraderJoeVersion::V2_1,
+                        bin_size: 25,
+                    },
+                    [(
+                        [4; 20],
+                        TraderJoePoolId {
+                            version: TraderJoeVersion::V2,
+                            bin_size: 10,
+                        },
+                    )],
+                ),
+            SwapMessageBuilder::new(RECIPIENT)
+                .output_gas()
+                .orca_whirlpool([([7; 32], true), ([8; 32], false)]),
+        ];
+
+        for builder in builders {
+            let swap_message = builder.build().unwrap();
+            let encoded = swap_message.try_encode().unwrap();
+
+            // Encoded with the version byte.
+            assert_eq!(swap_message.written_size().saturating_add(1), encoded.len());
+        }
+    }
+
+    #[test]
+    fn test_build_errors() {
+        assert_eq!(
+            SwapMessageBuilder::new(RECIPIENT).output_gas().build(),
+            Err(SwapMessageError::MissingSwapRoute)
+        );
+        assert_eq!(
+            SwapMessageBuilder::new(RECIPIENT).jupiter_v6(None).build(),
+            Err(SwapMessageError::UnexpectedSwapRoute)
+        );
+        assert_eq!(
+            SwapMessageBuilder::new(RECIPIENT)
+                .relay(0, u64::MAX)
+                .build(),
+            Err(SwapMessageError::ValueOutOfRange {
+                field: "relay.relaying_fee",
+                value: u64::MAX
+            })
+        );
+        assert_eq!(
+            SwapMessageBuilder::new(RECIPIENT)
+                .payload([1; 32], vec![0; usize::from(u16::MAX).saturating_add(1)])
+                .build(),
+            Err(SwapMessageError::PayloadTooLarge { len: 65536 })
+        );
+        // The first error is reported.
+        assert_eq!(
+            SwapMessageBuilder::new(RECIPIENT)
+                .output_gas()
+                .uniswap_v3_route(1 << 24, [([0; 20], 1 << 24)])
+                .relay(0, u64::MAX)
+                .build(),
+            Err(SwapMessageError::ValueOutOfRange {
+                field: "uniswap_v3.first_leg_fee",
+                value: 1 << 24
+            })
+        );
+        assert_eq!(
+            SwapMessageBuilder::new(RECIPIENT)
+                .output_gas()
+                .uniswap_v3_route(500, [([0; 20], 1 << 24)])
+                .build(),
+            Err(SwapMessageError::ValueOutOfRange {
+                field: "uniswap_v3.path",
+                value: 1 << 24
+            })
+        );
+
+        assert_eq!(
+            SwapMessageBuilder::new(RECIPIENT)
+                .output_gas()
+                .route(SwapType::UniswapV3(UniswapSwapParameters {
+                    first_leg_fee: Uint24::ZERO,
+                    path: vec![
+                        UniswapSwapPath {
+                            evm_address: [0; 20],
+                            fee: Uint24::ZERO,
+                        };
+                        256
+                    ],
+                }))
+                .build(),
+            Err(SwapMessageError::PathTooLong {
+                field: "uniswap_v3.path",
+                len: 256
+            })
+        );
+
+        assert_eq!(
+            SwapMessageBuilder::new(RECIPIENT)
+                .output_gas()
+                .jupiter_v6(None)
+                .target_chain(2)
+                .build(),
+            Err(SwapMessageError::SwapTypeNotSupported { chain: 2 })
+        );
+        assert!(SwapMessageBuilder::new(RECIPIENT)
+            .output_gas()
+            .jupiter_v6(None)
+            .target_chain(SOLANA_CHAIN)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_build_exact_out() {
+        let builder = SwapMessageBuilder::new(RECIPIENT)
+            .output_other([2; 32])
+            .limit_amount(420)
+            .jupiter_v6(None)
+            .exact_out()
+            .target_chain(SOLANA_CHAIN);
+
+        // Only the second version encodes the swap mode.
+        assert_eq!(
+            builder.clone().build(),
+            Err(SwapMessageError::SwapModeNotSupported { version: 1 })
+        );
+
+        let swap_message = builder.build_v2().unwrap();
+        assert!(matches!(
+            &swap_message.output_token,
+            OutputToken::Other { swap, .. } if swap.is_exact_out()
+        ));
+
+        let encoded = swap_message.try_encode().unwrap();
+        assert!(encoded.ends_with(&[
+            1, // exact out
+            0, // no fallback recipient
+            0, // no integrator fee
+            0, // no split outputs
+        ]));
+        assert_eq!(swap_message.written_size().saturating_add(1), encoded.len());
+        assert_eq!(SwapMessageV2::try_read_slice(&encoded), Ok(swap_message));
+
+        assert_eq!(
+            SwapMessageBuilder::new(RECIPIENT)
+                .output_gas()
+                .orca_whirlpool([([7; 32], true)])
+                .exact_out()
+                .target_chain(SOLANA_CHAIN)
+                .build_v2(),
+            Err(SwapMessageError::SwapTypeNotSupported {
+                chain: SOLANA_CHAIN
+            })
+        );
+    }
+
+    #[test]
+    fn test_build_with_universal_addresses() {
+        let recipient = "0x6CA6d1e2D5347Bfab1d91e883F1915560e09129D"
+            .parse::<UniversalAddress>()
+            .unwrap();
+        assert_eq!(recipient.to_bytes(), RECIPIENT);
+
+        let token = UniversalAddress::from_evm(hex!("5991a2df15a8f6a256d3ec51e99254cd3fb576a9"));
+        let build = |recipient, token| {
+            SwapMessageBuilder::new(recipient)
+                .payload(recipient, hex!("deadbeef"))
+                .output_other(token)
+                .uniswap_v3_route(500, [])
+                .build()
+                .unwrap()
+        };
+        assert_eq!(
+            build(recipient, token),
+            build(recipient.to_bytes().into(), token.to_bytes().into())
+        );
+
+        let swap_message = build(recipient, token);
+        assert_eq!(swap_message.recipient, RECIPIENT);
+        assert!(matches!(
+            swap_message.output_token,
+            OutputToken::Other { address, .. } if address == token.to_bytes()
+        ));
+    }
+}
@@ -0,0 +1,434 @@
+use core::fmt::{self, Write};
+
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+use crate::types::{
+    is_evm_address, IntegratorFee, IntegratorFeeAmount, JupiterV6SwapParameters,
+    OrcaWhirlpoolSwapParameters, OutputSwap, OutputToken, RedeemMode, SplitOutput, SwapType,
+    TraderJoeSwapParameters, TraderJoeVersion, UniswapSwapParameters, UniversalAddress,
+};
+
+/// USDC has six decimals on every chain the Swap Layer is deployed to.
+const USDC_DECIMALS: usize = 6;
+
+/// Gas dropoff is normalized to millionths of the target chain's gas token.
+const GAS_DROPOFF_DECIMALS: usize = 6;
+
+/// Uniswap fees are in hundredths of a basis point, so this many decimals of a percent.
+const UNISWAP_FEE_PERCENT_DECIMALS: usize = 4;
+
+/// Basis points have two decimals of a percent.
+const BPS_PERCENT_DECIMALS: usize = 2;
+
+/// Fields of a swap message of any version, rendered as text by `explain`.
+pub(super) struct Explanation<'a> {
+    pub version: u8,
+    pub recipient: &'a [u8; 32],
+    pub redeem_mode: &'a RedeemMode,
+    pub output_token: &'a OutputToken,
+    pub fallback_recipient: Option<&'a [u8; 32]>,
+    pub integrator_fee: Option<&'a IntegratorFee>,
+    pub split_outputs: &'a [SplitOutput],
+}
+
+impl fmt::Display for Explanation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Swap message v{}", self.version)?;
+        writeln!(f, "Recipient: {}", Address(self.recipient))?;
+        write_redeem_mode(f, self.redeem_mode)?;
+        write_output_token(f, self.output_token, "")?;
+
+        if let Some(fallback_recipient) = self.fallback_recipient {
+            writeln!(f, "Fallback recipient: {}", Address(fallback_recipient))?;
+        }
+
+        if let Some(IntegratorFee { recipient, amount }) = self.integrator_fee {
+            match amount {
+                IntegratorFeeAmount::Bps { bps } => writeln!(
+                    f,
+                    "Integrator fee: {}% to {}",
+                    Decimal((*bps).into(), BPS_PERCENT_DECIMALS),
+                    Address(recipient)
+                )?,
+                IntegratorFeeAmount::Absolute { amount } => writeln!(
+                    f,
+                    "Integrator fee: {} USDC to {}",
+                    Decimal(u64::from(*amount).into(), USDC_DECIMALS),
+                    Address(recipient)
+                )?,
+            }
+        }
+
+        if !self.split_outputs.is_empty() {
+            writeln!(f, "Split outputs:")?;
+            for (i, split_output) in self.split_outputs.iter().enumerate() {
+                writeln!(
+                    f,
+                    "  {}. {}% to {}",
+                    i.saturating_add(1),
+                    Decimal(split_output.bps.into(), BPS_PERCENT_DECIMALS),
+                    Address(&split_output.recipient)
+                )?;
+                write_output_token(f, &split_output.output_token, "     ")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn write_redeem_mode(f: &mut fmt::Formatter<'_>, redeem_mode: &RedeemMode) -> fmt::Result {
+    let (name, relay, payload) = match redeem_mode {
+        RedeemMode::Direct => ("Direct", None, None),
+        RedeemMode::Payload { sender, buf } => ("Payload", None, Some((sender, buf))),
+        RedeemMode::Relay {
+            gas_dropoff,
+            relaying_fee,
+        } => ("Relay", Some((gas_dropoff, relaying_fee)), None),
+        RedeemMode::RelayWithPayload {
+            gas_dropoff,
+            relaying_fee,
+            sender,
+            buf,
+        } => (
+            "Relay with payload",
+            Some((gas_dropoff, relaying_fee)),
+            Some((sender, buf)),
+        ),
+    };
+
+    writeln!(f, "Redeem mode: {name}")?;
+    if let Some((gas_dropoff, relaying_fee)) = relay {
+        writeln!(
+            f,
+            "  Gas dropoff: {} gas token",
+            Decimal((*gas_dropoff).into(), GAS_DROPOFF_DECIMALS)
+        )?;
+        writeln!(
+            f,
+            "  Relaying fee: {} USDC",
+            Decimal(u64::from(*relaying_fee).into(), USDC_DECIMALS)
+        )?;
+    }
+    if let Some((sender, buf)) = payload {
+        writeln!(f, "  Sender: {}", Address(sender))?;
+        writeln!(f, "  Payload ({} bytes): {}", buf.len(), Hex(buf))?;
+    }
+
+    Ok(())
+}
+
+fn write_output_token(
+    f: &mut fmt::Formatter<'_>,
+    output_token: &OutputToken,
+    indent: &str,
+) -> fmt::Result {
+    let swap = match output_token {
+        OutputToken::Usdc => return writeln!(f, "{indent}Output token: USDC"),
+        OutputToken::Gas(swap) => {
+            writeln!(f, "{indent}Output token: Gas")?;
+            swap
+        }
+        OutputToken::Other { address, swap } => {
+            writeln!(f, "{indent}Output token: {}", Address(address))?;
+            swap
+        }
+    };
+
+    let OutputSwap {
+        deadline,
+        limit_amount,
+        swap_type,
+        mode: _,
+    } = swap;
+
+    match swap.is_exact_out() {
+        true => writeln!(
+            f,
+            "{indent}  Exact amount out: {limit_amount} (atomic units)"
+        )?,
+        false if *limit_amount == 0 => writeln!(f, "{indent}  Minimum amount out: none")?,
+        false => writeln!(
+            f,
+            "{indent}  Minimum amount out: {limit_amount} (atomic units)"
+        )?,
+    }
+    match deadline {
+        0 => writeln!(f, "{indent}  Deadline: none")?,
+        deadline => writeln!(f, "{indent}  Deadline: {deadline} (unix time)")?,
+    }
+
+    write!(f, "{indent}  Swap: ")?;
+    match swap_type {
+        SwapType::Invalid => writeln!(f, "Invalid"),
+        SwapType::UniswapV3(params) => write_uniswap_v3(f, params),
+        SwapType::TraderJoe(params) => write_trader_joe(f, params),
+        SwapType::JupiterV6(params) => write_jupiter_v6(f, params),
+        SwapType::OrcaWhirlpool(params) => write_orca_whirlpool(f, params),
+    }
+}
+
+/// Each hop is written as the token swapped into followed by the pool used to swap into it.
+fn write_uniswap_v3(f: &mut fmt::Formatter<'_>, params: &UniswapSwapParameters) -> fmt::Result {
+    let UniswapSwapParameters {
+        first_leg_fee,
+        path,
+    } = params;
+
+    write!(f, "Uniswap V3, USDC")?;
+    let mut fee = first_leg_fee;
+    for hop in path {
+        write!(
+            f,
+            " -> {} ({}%)",
+            EvmAddress(&hop.evm_address),
+            UniswapFee(fee)
+        )?;
+        fee = &hop.fee;
+    }
+    writeln!(f, " -> output token ({}%)", UniswapFee(fee))
+}
+
+fn write_trader_joe(f: &mut fmt::Formatter<'_>, params: &TraderJoeSwapParameters) -> fmt::Result {
+    let TraderJoeSwapParameters {
+        first_pool_id,
+        path,
+    } = params;
+
+    let write_pool = |f: &mut fmt::Formatter<'_>, version, bin_size: u16| -> fmt::Result {
+        let version = match version {
+            TraderJoeVersion::V1 => "v1",
+            TraderJoeVersion::V2 => "v2",
+            TraderJoeVersion::V2_1 => "v2.1",
+            TraderJoeVersion::V2_2 => "v2.2",
+        };
+        write!(
+            f,
+            "({version}, {}% bin step)",
+            Decimal(bin_size.into(), BPS_PERCENT_DECIMALS)
+        )
+    };
+
+    write!(f, "Trader Joe, USDC")?;
+    let mut pool_id = first_pool_id;
+    for hop in path {
+        write!(f, " -> {} ", EvmAddress(&hop.evm_address))?;
+        write_pool(f, pool_id.version, pool_id.bin_size)?;
+        pool_id = &hop.pool_id;
+    }
+    write!(f, " -> output token ")?;
+    write_pool(f, pool_id.version, pool_id.bin_size)?;
+    writeln!(f)
+}
+
+fn write_jupiter_v6(f: &mut fmt::Formatter<'_>, params: &JupiterV6SwapParameters) -> fmt::Result {
+    match &params.dex_program_id {
+        Some(dex_program_id) => writeln!(
+            f,
+            "Jupiter V6, direct route through {}",
+            UniversalAddress::new(*dex_program_id).to_base58()
+        ),
+        None => writeln!(f, "Jupiter V6, any route"),
+    }
+}
+
+fn write_orca_whirlpool(
+    f: &mut fmt::Formatter<'_>,
+    params: &OrcaWhirlpoolSwapParameters,
+) -> fmt::Result {
+    write!(f, "Orca Whirlpool, USDC")?;
+    for pool in &params.pools {
+        let direction = match pool.a_to_b {
+            true => "A to B",
+            false => "B to A",
+        };
+        write!(
+            f,
+            " -> {} ({direction})",
+            UniversalAddress::new(pool.whirlpool).to_base58()
+        )?;
+    }
+    writeln!(f, " -> output token")
+}
+
+/// Universal address, displayed like an EVM address if it is padded like one. Otherwise it is a
+/// Solana address, which is displayed in base58.
+struct Address<'a>(&'a [u8; 32]);
+
+impl fmt::Display for Address<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let address = UniversalAddress::new(*self.0);
+        match is_evm_address(self.0) {
+            true => address.fmt(f),
+            false => f.write_str(&address.to_base58()),
+        }
+    }
+}
+
+struct EvmAddress<'a>(&'a [u8; 20]);
+
+impl fmt::Display for EvmAddress<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        UniversalAddress::from_evm(*self.0).fmt(f)
+    }
+}
+
+/// Uniswap fee as a percentage.
+struct UniswapFee<'a>(&'a crate::types::Uint24);
+
+impl fmt::Display for UniswapFee<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Decimal(u64::from(*self.0).into(), UNISWAP_FEE_PERCENT_DECIMALS).fmt(f)
+    }
+}
+
+/// Integer amount with this many decimals, written without trailing zeros.
+struct Decimal(u128, usize);
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(value, decimals) = *self;
+
+        let digits = format!("{value:0>width$}", width = decimals.saturating_add(1));
+        let (whole, fraction) = digits.split_at(digits.len().saturating_sub(decimals));
+        let fraction = fraction.trim_end_matches('0');
+
+        f.write_str(whole)?;
+        if !fraction.is_empty() {
+            f.write_char('.')?;
+            f.write_str(fraction)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("0x")?;
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl Explanation<'_> {
+    pub fn to_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use crate::{
+        error::Codec,
+        messages::{SwapMessage, SwapMessageV1, SwapMessageV2},
+        types::{
+            IntegratorFee, IntegratorFeeAmount, OrcaWhirlpoolPool, OrcaWhirlpoolSwapParameters,
+            OutputSwap, OutputToken, RedeemMode, SwapMode, SwapType, Uint48,
+        },
+    };
+
+    #[test]
+    fn test_explain_v1() {
+        let swap_message = SwapMessageV1::try_read_slice(&hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d00010000000000000000000000000000000000000000010001f4015991a2df15a8f6a256d3ec51e99254cd3fb576a90001f4")).unwrap();
+        let expected = "\
+Swap message v1
+Recipient: 0x6CA6d1e2D5347Bfab1d91e883F1915560e09129D
+Redeem mode: Direct
+Output token: Gas
+  Minimum amount out: none
+  Deadline: none
+  Swap: Uniswap V3, USDC -> 0x5991A2dF15A8F6A256D3Ec51E99254Cd3fb576A9 (0.05%) -> output token (0.05%)
+";
+        assert_eq!(swap_message.explain(), expected);
+        assert_eq!(SwapMessage::V1(swap_message).explain(), expected);
+
+        let swap_message = SwapMessageV1::try_read_slice(&hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0100000000000000000000000000000000000000000000000000000000000ba5ed0004deadbeef00")).unwrap();
+        assert_eq!(
+            swap_message.explain(),
+            "\
+Swap message v1
+Recipient: 0x6CA6d1e2D5347Bfab1d91e883F1915560e09129D
+Redeem mode: Payload
+  Sender: 0x00000000000000000000000000000000000BA5ED
+  Payload (4 bytes): 0xdeadbeef
+Output token: USDC
+"
+        );
+    }
+
+    #[test]
+    fn test_explain_v2() {
+        let swap_message = SwapMessageV2::try_read_slice(&hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d02000000000000000f42400000000200000000000000000000000000000000000000000000000000000000000ba5ed09c400000000000000000000000000000000000000000000000000000000000000f00d03e8010000000000000000000000000000000000000000100000")).unwrap();
+        assert_eq!(
+            swap_message.explain(),
+            "\
+Swap message v2
+Recipient: 0x6CA6d1e2D5347Bfab1d91e883F1915560e09129D
+Redeem mode: Relay
+  Gas dropoff: 0 gas token
+  Relaying fee: 1 USDC
+Output token: USDC
+Split outputs:
+  1. 25% to 0x00000000000000000000000000000000000BA5ED
+     Output token: USDC
+  2. 10% to 0x000000000000000000000000000000000000F00D
+     Output token: Gas
+       Minimum amount out: none
+       Deadline: none
+       Swap: Jupiter V6, any route
+"
+        );
+
+        // Solana addresses are displayed in base58.
+        let swap_message = SwapMessageV2 {
+            recipient: [1; 32],
+            redeem_mode: RedeemMode::Relay {
+                gas_dropoff: 1_500_000,
+                relaying_fee: Uint48::try_from(250_000u32).unwrap(),
+            },
+            output_token: OutputToken::Other {
+                address: [2; 32],
+                swap: OutputSwap {
+                    deadline: 1_700_000_000,
+                    limit_amount: 42_000_000,
+                    swap_type: SwapType::OrcaWhirlpool(OrcaWhirlpoolSwapParameters {
+                        pools: vec![OrcaWhirlpoolPool {
+                            whirlpool: [3; 32],
+                            a_to_b: false,
+                        }],
+                    }),
+                    mode: SwapMode::ExactIn,
+                },
+            },
+            fallback_recipient: Some([4; 32]),
+            integrator_fee: Some(IntegratorFee {
+                recipient: [5; 32],
+                amount: IntegratorFeeAmount::Bps { bps: 25 },
+            }),
+            split_outputs: vec![],
+        };
+        assert_eq!(
+            swap_message.explain(),
+            "\
+Swap message v2
+Recipient: 4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi
+Redeem mode: Relay
+  Gas dropoff: 1.5 gas token
+  Relaying fee: 0.25 USDC
+Output token: 8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR
+  Minimum amount out: 42000000 (atomic units)
+  Deadline: 1700000000 (unix time)
+  Swap: Orca Whirlpool, USDC -> CktRuQ2mttgRGkXJtyksdKHjUdc2C4TgDzyB98oEzy8 (B to A) -> output token
+Fallback recipient: GgBaCs3NCBuZN12kCJgAW63ydqohFkHEdfdEXBPzLHq
+Integrator fee: 0.25% to LbUiWL3xVV8hTFYBVdbTNrpDo41NKS6o3LHHuDzjfcY
+"
+        );
+    }
+}
@@ -1,50 +1,205 @@
-use std::io;
+mod builder;
+pub use builder::*;
+
+mod explain;
+
+mod v1;
+pub use v1::*;
+
+mod v2;
+pub use v2::*;
+
+#[cfg(all(test, feature = "serde"))]
+mod vectors;
+
+use alloc::string::String;
+use sha3::{Digest, Keccak256};
 
 use crate::{
-    types::{OutputToken, RedeemMode},
+    error::{Codec, SwapMessageError},
+    io,
+    types::{IntegratorFee, OutputToken, RedeemMode, SplitOutput},
     wormhole_io::{Readable, TypePrefixedPayload, Writeable},
 };
 
+/// Swap message of any supported version. The first byte of an encoded swap message is its
+/// version, which determines how the rest of the message is read.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct SwapMessageV1 {
-    pub recipient: [u8; 32],
-    pub redeem_mode: RedeemMode,
-    pub output_token: OutputToken,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "version"))]
+pub enum SwapMessage {
+    V1(SwapMessageV1),
+    V2(SwapMessageV2),
+}
+
+impl SwapMessage {
+    pub const VERSION_1: u8 = 1;
+    pub const VERSION_2: u8 = 2;
+
+    pub fn version(&self) -> u8 {
+        match self {
+            Self::V1(_) => Self::VERSION_1,
+            Self::V2(_) => Self::VERSION_2,
+        }
+    }
+
+    pub fn recipient(&self) -> &[u8; 32] {
+        match self {
+            Self::V1(msg) => &msg.recipient,
+            Self::V2(msg) => &msg.recipient,
+        }
+    }
+
+    pub fn redeem_mode(&self) -> &RedeemMode {
+        match self {
+            Self::V1(msg) => &msg.redeem_mode,
+            Self::V2(msg) => &msg.redeem_mode,
+        }
+    }
+
+    pub fn output_token(&self) -> &OutputToken {
+        match self {
+            Self::V1(msg) => &msg.output_token,
+            Self::V2(msg) => &msg.output_token,
+        }
+    }
+
+    /// Recipient of the USDC if the swap cannot be executed. Only V2 messages may specify one.
+    pub fn fallback_recipient(&self) -> Option<&[u8; 32]> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(msg) => msg.fallback_recipient.as_ref(),
+        }
+    }
+
+    /// Fee paid to an integrator out of the USDC. Only V2 messages may specify one.
+    pub fn integrator_fee(&self) -> Option<&IntegratorFee> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(msg) => msg.integrator_fee.as_ref(),
+        }
+    }
+
+    /// Shares of the USDC delivered to other recipients. Only V2 messages may specify them.
+    pub fn split_outputs(&self) -> &[SplitOutput] {
+        match self {
+            Self::V1(_) => &[],
+            Self::V2(msg) => &msg.split_outputs,
+        }
+    }
+
+    /// Check that the message can be executed on the target chain (Wormhole chain ID).
+    pub fn validate_for_target(&self, chain: u16) -> Result<(), SwapMessageError> {
+        match self {
+            Self::V1(msg) => msg.validate_for_target(chain),
+            Self::V2(msg) => msg.validate_for_target(chain),
+        }
+    }
+
+    /// Identifier of this message delivered by a fill. See [SwapMessageV1::id].
+    pub fn id(
+        &self,
+        source_chain: u16,
+        order_sender: &[u8; 32],
+    ) -> Result<[u8; 32], SwapMessageError> {
+        self.try_encode()
+            .map(|encoded| message_id(source_chain, order_sender, &encoded))
+    }
+
+    /// Describe the message as text. See [SwapMessageV1::explain].
+    pub fn explain(&self) -> String {
+        explain::Explanation {
+            version: self.version(),
+            recipient: self.recipient(),
+            redeem_mode: self.redeem_mode(),
+            output_token: self.output_token(),
+            fallback_recipient: self.fallback_recipient(),
+            integrator_fee: self.integrator_fee(),
+            split_outputs: self.split_outputs(),
+        }
+        .to_text()
+    }
+}
+
+/// Keccak-256 hash of the fill's source chain (big-endian), the fill's order sender and the
+/// encoded swap message.
+fn message_id(source_chain: u16, order_sender: &[u8; 32], encoded: &[u8]) -> [u8; 32] {
+    Keccak256::new()
+        .chain_update(source_chain.to_be_bytes())
+        .chain_update(order_sender)
+        .chain_update(encoded)
+        .finalize()
+        .into()
 }
 
-impl Readable for SwapMessageV1 {
+impl Readable for SwapMessage {
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
         Self: Sized,
         R: io::Read,
     {
-        Ok(Self {
-            recipient: Readable::read(reader)?,
-            redeem_mode: Readable::read(reader)?,
-            output_token: Readable::read(reader)?,
-        })
+        match u8::read(reader)? {
+            Self::VERSION_1 => Ok(Self::V1(Readable::read(reader)?)),
+            Self::VERSION_2 => Ok(Self::V2(Readable::read(reader)?)),
+            value => Err(SwapMessageError::UnknownDiscriminant {
+                field: "version",
+                value,
+            }
+            .into()),
+        }
     }
 }
 
-impl Writeable for SwapMessageV1 {
+impl Writeable for SwapMessage {
     fn write<W>(&self, writer: &mut W) -> io::Result<()>
     where
         W: io::Write,
     {
-        self.recipient.write(writer)?;
-        self.redeem_mode.write(writer)?;
-        self.output_token.write(writer)
+        match self {
+            Self::V1(msg) => {
+                Self::VERSION_1.write(writer)?;
+                msg.write(writer)
+            }
+            Self::V2(msg) => {
+                Self::VERSION_2.write(writer)?;
+                msg.write(writer)
+            }
+        }
     }
 }
 
-impl TypePrefixedPayload<1> for SwapMessageV1 {
-    const TYPE: Option<[u8; 1]> = Some([1]);
+impl TypePrefixedPayload<1> for SwapMessage {
+    // The version byte is written as part of the payload.
+    const TYPE: Option<[u8; 1]> = None;
 
     fn written_size(&self) -> usize {
-        self.redeem_mode
-            .written_size()
-            .saturating_add(self.output_token.written_size())
-            .saturating_add(32) // recipient
+        match self {
+            Self::V1(msg) => msg.written_size(),
+            Self::V2(msg) => msg.written_size(),
+        }
+        .saturating_add(1) // version
+    }
+}
+
+impl From<SwapMessageV1> for SwapMessage {
+    fn from(msg: SwapMessageV1) -> Self {
+        Self::V1(msg)
+    }
+}
+
+impl From<SwapMessageV2> for SwapMessage {
+    fn from(msg: SwapMessageV2) -> Self {
+        Self::V2(msg)
+    }
+}
+
+/// Normalize any swap message version into the latest version.
+impl From<SwapMessage> for SwapMessageV2 {
+    fn from(msg: SwapMessage) -> Self {
+        match msg {
+            SwapMessage::V1(msg) => msg.into(),
+            SwapMessage::V2(msg) => msg,
+        }
     }
 }
 
@@ -52,152 +207,43 @@ impl TypePrefixedPayload<1> for SwapMessageV1 {
 mod test {
     use hex_literal::hex;
 
-    use crate::types::{
-        OutputSwap, OutputToken, RedeemMode, SwapType, Uint24, Uint48, UniswapSwapParameters,
-        UniswapSwapPath,
-    };
+    use crate::types::{OutputToken, RedeemMode};
 
     use super::*;
 
     #[test]
-    pub fn test_swap_message_v1_usdc_direct() {
-        let encoded_fill = hex!("01f00f0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a0023010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0000");
-
-        let redeemer_message =
+    pub fn test_swap_message_versions() {
+        let v1_message =
             hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0000");
-
-        let fill = liquidity_layer_messages::Fill::read_slice(&encoded_fill).unwrap();
-        assert_eq!(
-            fill,
-            liquidity_layer_messages::Fill {
-                source_chain: 0xf00f,
-                order_sender: hex!(
-                    "0000000000000000000000000000000000000000000000000000000000000002"
-                ),
-                redeemer: hex!("000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a"),
-                redeemer_message: redeemer_message.to_vec().try_into().unwrap()
-            }
-        );
-
-        let swap_message = SwapMessageV1::read_slice(&redeemer_message).unwrap();
-        assert_eq!(
-            swap_message,
-            SwapMessageV1 {
-                recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
-                redeem_mode: RedeemMode::Direct,
-                output_token: OutputToken::Usdc,
-            }
-        );
-    }
-
-    #[test]
-    pub fn test_swap_message_v1_eth_swap() {
-        let encoded_fill = hex!("01f00f0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a0053010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d00010000000000000000000000000000000000000000010001f4015991a2df15a8f6a256d3ec51e99254cd3fb576a90001f4");
-
-        let redeemer_message =
-            hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d00010000000000000000000000000000000000000000010001f4015991a2df15a8f6a256d3ec51e99254cd3fb576a90001f4");
-
-        let fill = liquidity_layer_messages::Fill::read_slice(&encoded_fill).unwrap();
-        assert_eq!(
-            fill,
-            liquidity_layer_messages::Fill {
-                source_chain: 0xf00f,
-                order_sender: hex!(
-                    "0000000000000000000000000000000000000000000000000000000000000002"
-                ),
-                redeemer: hex!("000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a"),
-                redeemer_message: redeemer_message.to_vec().try_into().unwrap()
-            }
-        );
-
-        let swap_message = SwapMessageV1::read_slice(&redeemer_message).unwrap();
-        assert_eq!(
-            swap_message,
-            SwapMessageV1 {
-                recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
-                redeem_mode: RedeemMode::Direct,
-                output_token: OutputToken::Gas(OutputSwap {
-                    deadline: 0,
-                    limit_amount: 0,
-                    swap_type: SwapType::UniswapV3(UniswapSwapParameters {
-                        first_leg_fee: Uint24::from(500),
-                        path: vec![UniswapSwapPath {
-                            evm_address: hex!("5991a2df15a8f6a256d3ec51e99254cd3fb576a9"),
-                            fee: Uint24::from(500),
-                        },]
-                    })
-                }),
-            }
-        );
+        let v2_message =
+            hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0000000000");
+
+        let expected = SwapMessageV2 {
+            recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+            redeem_mode: RedeemMode::Direct,
+            output_token: OutputToken::Usdc,
+            fallback_recipient: None,
+            integrator_fee: None,
+            split_outputs: vec![],
+        };
+
+        let swap_message = SwapMessage::read_slice(&v1_message).unwrap();
+        assert_eq!(swap_message.version(), SwapMessage::VERSION_1);
+        assert_eq!(swap_message.written_size(), v1_message.len());
+        assert_eq!(swap_message.to_vec(), v1_message);
+        assert_eq!(SwapMessageV2::from(swap_message), expected);
+
+        let swap_message = SwapMessage::read_slice(&v2_message).unwrap();
+        assert_eq!(swap_message.version(), SwapMessage::VERSION_2);
+        assert_eq!(swap_message.written_size(), v2_message.len());
+        assert_eq!(swap_message.to_vec(), v2_message);
+        assert_eq!(SwapMessageV2::from(swap_message), expected);
     }
 
     #[test]
-    pub fn test_swap_message_v1_usdc_relay() {
-        let encoded_fill = hex!("01f00f0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a002d010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d02000000000000000f424000");
-
-        let redeemer_message =
-            hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d02000000000000000f424000");
-
-        let fill = liquidity_layer_messages::Fill::read_slice(&encoded_fill).unwrap();
-        assert_eq!(
-            fill,
-            liquidity_layer_messages::Fill {
-                source_chain: 0xf00f,
-                order_sender: hex!(
-                    "0000000000000000000000000000000000000000000000000000000000000002"
-                ),
-                redeemer: hex!("000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a"),
-                redeemer_message: redeemer_message.to_vec().try_into().unwrap()
-            }
-        );
-
-        let swap_message = SwapMessageV1::read_slice(&redeemer_message).unwrap();
-        assert_eq!(
-            swap_message,
-            SwapMessageV1 {
-                recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
-                redeem_mode: RedeemMode::Relay {
-                    gas_dropoff: 0,
-                    relaying_fee: Uint48::from(1000000u32),
-                },
-                output_token: OutputToken::Usdc,
-            }
-        );
-    }
-
-    #[test]
-    pub fn test_swap_message_v1_usdc_payload() {
-        let encoded_fill = hex!("01f00f0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a0049010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0100000000000000000000000000000000000000000000000000000000000ba5ed0004deadbeef00");
-
-        let redeemer_message =
-            hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0100000000000000000000000000000000000000000000000000000000000ba5ed0004deadbeef00");
-
-        let fill = liquidity_layer_messages::Fill::read_slice(&encoded_fill).unwrap();
-        assert_eq!(
-            fill,
-            liquidity_layer_messages::Fill {
-                source_chain: 0xf00f,
-                order_sender: hex!(
-                    "0000000000000000000000000000000000000000000000000000000000000002"
-                ),
-                redeemer: hex!("000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a"),
-                redeemer_message: redeemer_message.to_vec().try_into().unwrap()
-            }
-        );
-
-        let swap_message = SwapMessageV1::read_slice(&redeemer_message).unwrap();
-        assert_eq!(
-            swap_message,
-            SwapMessageV1 {
-                recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
-                redeem_mode: RedeemMode::Payload {
-                    sender: hex!(
-                        "00000000000000000000000000000000000000000000000000000000000ba5ed"
-                    ),
-                    buf: hex!("deadbeef").to_vec().try_into().unwrap()
-                },
-                output_token: OutputToken::Usdc,
-            }
-        );
+    pub fn test_swap_message_invalid_version() {
+        let encoded =
+            hex!("030000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0000");
+        assert!(SwapMessage::read_slice(&encoded).is_err());
     }
 }
@@ -0,0 +1,448 @@
+use alloc::string::String;
+
+use crate::{
+    error::{Codec, SwapMessageError},
+    io,
+    types::{validate_recipient_for_target, OutputToken, RedeemMode},
+    wormhole_io::{Readable, TypePrefixedPayload, Writeable},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SwapMessageV1 {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_array"))]
+    pub recipient: [u8; 32],
+    pub redeem_mode: RedeemMode,
+    pub output_token: OutputToken,
+}
+
+impl SwapMessageV1 {
+    /// Check that the message can be executed on the target chain (Wormhole chain ID). See
+    /// [OutputToken::validate_for_target].
+    pub fn validate_for_target(&self, chain: u16) -> Result<(), SwapMessageError> {
+        validate_recipient_for_target(&self.recipient, chain)?;
+        self.output_token.validate_for_target(chain)
+    }
+
+    /// Stable identifier of this message delivered by a fill, e.g. to deduplicate relay jobs. This
+    /// is the Keccak-256 hash of the fill's source chain (big-endian), the fill's order sender and
+    /// the canonical encoding of this message, including its version.
+    pub fn id(
+        &self,
+        source_chain: u16,
+        order_sender: &[u8; 32],
+    ) -> Result<[u8; 32], SwapMessageError> {
+        self.try_encode()
+            .map(|encoded| super::message_id(source_chain, order_sender, &encoded))
+    }
+
+    /// Describe the message as text for people triaging a redeemer message. USDC and gas dropoff
+    /// amounts are in decimal units, fees are percentages and swaps show their route.
+    pub fn explain(&self) -> String {
+        super::explain::Explanation {
+            version: super::SwapMessage::VERSION_1,
+            recipient: &self.recipient,
+            redeem_mode: &self.redeem_mode,
+            output_token: &self.output_token,
+            fallback_recipient: None,
+            integrator_fee: None,
+            split_outputs: &[],
+        }
+        .to_text()
+    }
+}
+
+impl Readable for SwapMessageV1 {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        let recipient = Readable::read(reader)?;
+
+        let redeem_mode = RedeemMode::read(reader)?;
+        if !redeem_mode.is_supported_in_v1() {
+            return Err(SwapMessageError::UnknownDiscriminant {
+                field: "redeem_mode",
+                value: RedeemMode::RELAY_WITH_PAYLOAD,
+            }
+            .into());
+        }
+
+        Ok(Self {
+            recipient,
+            redeem_mode,
+            output_token: Readable::read(reader)?,
+        })
+    }
+}
+
+impl Writeable for SwapMessageV1 {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        if !self.redeem_mode.is_supported_in_v1() {
+            return Err(SwapMessageError::RedeemModeNotSupported {
+                version: super::SwapMessage::VERSION_1,
+            }
+            .into());
+        }
+
+        self.recipient.write(writer)?;
+        self.redeem_mode.write(writer)?;
+        self.output_token.write(writer)
+    }
+}
+
+impl TypePrefixedPayload<1> for SwapMessageV1 {
+    const TYPE: Option<[u8; 1]> = Some([1]);
+
+    fn written_size(&self) -> usize {
+        self.redeem_mode
+            .written_size()
+            .saturating_add(self.output_token.written_size())
+            .saturating_add(32) // recipient
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+    // Fill implements the wormhole-io traits, which are only re-exported with the `std` feature.
+    use ::wormhole_io::TypePrefixedPayload as _;
+
+    use crate::types::{
+        JupiterV6SwapParameters, OutputSwap, OutputToken, RedeemMode, SwapMode, SwapType, Uint24,
+        Uint48, UniswapSwapParameters, UniswapSwapPath,
+    };
+
+    use super::*;
+
+    #[test]
+    pub fn test_swap_message_v1_usdc_direct() {
+        let encoded_fill = hex!("01f00f0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a0023010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0000");
+
+        let redeemer_message =
+            hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0000");
+
+        let fill = liquidity_layer_messages::Fill::read_slice(&encoded_fill).unwrap();
+        assert_eq!(
+            fill,
+            liquidity_layer_messages::Fill {
+                source_chain: 0xf00f,
+                order_sender: hex!(
+                    "0000000000000000000000000000000000000000000000000000000000000002"
+                ),
+                redeemer: hex!("000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a"),
+                redeemer_message: redeemer_message.to_vec().try_into().unwrap()
+            }
+        );
+
+        let swap_message = SwapMessageV1::read_slice(&redeemer_message).unwrap();
+        assert_eq!(
+            swap_message,
+            SwapMessageV1 {
+                recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+                redeem_mode: RedeemMode::Direct,
+                output_token: OutputToken::Usdc,
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_swap_message_v1_eth_swap() {
+        let encoded_fill = hex!("01f00f0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a0053010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d00010000000000000000000000000000000000000000010001f4015991a2df15a8f6a256d3ec51e99254cd3fb576a90001f4");
+
+        let redeemer_message =
+            hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d00010000000000000000000000000000000000000000010001f4015991a2df15a8f6a256d3ec51e99254cd3fb576a90001f4");
+
+        let fill = liquidity_layer_messages::Fill::read_slice(&encoded_fill).unwrap();
+        assert_eq!(
+            fill,
+            liquidity_layer_messages::Fill {
+                source_chain: 0xf00f,
+                order_sender: hex!(
+                    "0000000000000000000000000000000000000000000000000000000000000002"
+                ),
+                redeemer: hex!("000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a"),
+                redeemer_message: redeemer_message.to_vec().try_into().unwrap()
+            }
+        );
+
+        let swap_message = SwapMessageV1::read_slice(&redeemer_message).unwrap();
+        assert_eq!(
+            swap_message,
+            SwapMessageV1 {
+                recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+                redeem_mode: RedeemMode::Direct,
+                output_token: OutputToken::Gas(OutputSwap {
+                    deadline: 0,
+                    limit_amount: 0,
+                    swap_type: SwapType::UniswapV3(UniswapSwapParameters {
+                        first_leg_fee: Uint24::from(500),
+                        path: vec![UniswapSwapPath {
+                            evm_address: hex!("5991a2df15a8f6a256d3ec51e99254cd3fb576a9"),
+                            fee: Uint24::from(500),
+                        },]
+                    }),
+                    mode: SwapMode::ExactIn,
+                }),
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_swap_message_v1_usdc_relay() {
+        let encoded_fill = hex!("01f00f0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a002d010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d02000000000000000f424000");
+
+        let redeemer_message =
+            hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d02000000000000000f424000");
+
+        let fill = liquidity_layer_messages::Fill::read_slice(&encoded_fill).unwrap();
+        assert_eq!(
+            fill,
+            liquidity_layer_messages::Fill {
+                source_chain: 0xf00f,
+                order_sender: hex!(
+                    "0000000000000000000000000000000000000000000000000000000000000002"
+                ),
+                redeemer: hex!("000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a"),
+                redeemer_message: redeemer_message.to_vec().try_into().unwrap()
+            }
+        );
+
+        let swap_message = SwapMessageV1::read_slice(&redeemer_message).unwrap();
+        assert_eq!(
+            swap_message,
+            SwapMessageV1 {
+                recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+                redeem_mode: RedeemMode::Relay {
+                    gas_dropoff: 0,
+                    relaying_fee: Uint48::try_from(1000000u32).unwrap(),
+                },
+                output_token: OutputToken::Usdc,
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_swap_message_v1_usdc_payload() {
+        let encoded_fill = hex!("01f00f0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a0049010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0100000000000000000000000000000000000000000000000000000000000ba5ed0004deadbeef00");
+
+        let redeemer_message =
+            hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0100000000000000000000000000000000000000000000000000000000000ba5ed0004deadbeef00");
+
+        let fill = liquidity_layer_messages::Fill::read_slice(&encoded_fill).unwrap();
+        assert_eq!(
+            fill,
+            liquidity_layer_messages::Fill {
+                source_chain: 0xf00f,
+                order_sender: hex!(
+                    "0000000000000000000000000000000000000000000000000000000000000002"
+                ),
+                redeemer: hex!("000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a"),
+                redeemer_message: redeemer_message.to_vec().try_into().unwrap()
+            }
+        );
+
+        let swap_message = SwapMessageV1::read_slice(&redeemer_message).unwrap();
+        assert_eq!(
+            swap_message,
+            SwapMessageV1 {
+                recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+                redeem_mode: RedeemMode::Payload {
+                    sender: hex!(
+                        "00000000000000000000000000000000000000000000000000000000000ba5ed"
+                    ),
+                    buf: hex!("deadbeef").to_vec().try_into().unwrap()
+                },
+                output_token: OutputToken::Usdc,
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_swap_message_v1_id() {
+        use sha3::{Digest, Keccak256};
+
+        let encoded_fill = hex!("01f00f0000000000000000000000000000000000000000000000000000000000000002000000000000000000000000f62849f9a0b5bf2913b396098f7c7019b51a820a0023010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0000");
+
+        let fill = liquidity_layer_messages::Fill::read_slice(&encoded_fill).unwrap();
+        let swap_message = SwapMessageV1::read_slice(&fill.redeemer_message).unwrap();
+
+        let id = swap_message
+            .id(fill.source_chain, &fill.order_sender)
+            .unwrap();
+        assert_eq!(
+            id,
+            hex!("b6d143e2eae167dda41fbf75d57d11a7977abd392db325c4ddc67be5c227abad")
+        );
+        assert_eq!(
+            id,
+            <[u8; 32]>::from(
+                Keccak256::new()
+                    .chain_update(fill.source_chain.to_be_bytes())
+                    .chain_update(fill.order_sender)
+                    .chain_update(&fill.redeemer_message[..])
+                    .finalize()
+            )
+        );
+
+        // The ID does not depend on how the message is wrapped.
+        assert_eq!(
+            crate::messages::SwapMessage::V1(swap_message.clone())
+                .id(fill.source_chain, &fill.order_sender)
+                .unwrap(),
+            id
+        );
+
+        // The same message delivered from another chain or sender is a different job.
+        assert_ne!(swap_message.id(2, &fill.order_sender).unwrap(), id);
+        assert_ne!(swap_message.id(fill.source_chain, &[0; 32]).unwrap(), id);
+    }
+
+    #[test]
+    pub fn test_swap_message_v1_exact_out_not_supported() {
+        let swap_message = SwapMessageV1 {
+            recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+            redeem_mode: RedeemMode::Direct,
+            output_token: OutputToken::Gas(OutputSwap {
+                deadline: 0,
+                limit_amount: 1000000,
+                swap_type: SwapType::JupiterV6(JupiterV6SwapParameters {
+                    dex_program_id: None,
+                }),
+                mode: SwapMode::ExactOut,
+            }),
+        };
+        assert_eq!(
+            swap_message.try_encode(),
+            Err(SwapMessageError::SwapModeNotSupported { version: 1 })
+        );
+
+        // The same message can be encoded as V2, where the swap is followed by its mode.
+        let swap_message = crate::messages::SwapMessage::V2(swap_message.into());
+        let encoded = hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d000100000000000000000000000000000000000f4240100001000000");
+        assert_eq!(swap_message.try_encode().unwrap(), encoded);
+        assert!(matches!(
+            crate::raw::SwapMessageRef::parse(&encoded).unwrap().output_token(),
+            crate::raw::OutputTokenRef::Gas(swap) if swap.is_exact_out()
+        ));
+
+        let mut encoded = encoded.to_vec();
+        encoded[57] = 2;
+        assert_eq!(
+            crate::messages::SwapMessage::try_read_slice(&encoded),
+            Err(SwapMessageError::UnknownDiscriminant {
+                field: "swap_mode",
+                value: 2
+            })
+        );
+
+        // V1 messages are always exact in.
+        let swap_message = SwapMessageV1::try_read_slice(&hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d000100000000000000000000000000000000000f42401000")).unwrap();
+        assert!(matches!(
+            swap_message.output_token,
+            OutputToken::Gas(swap) if !swap.is_exact_out()
+        ));
+    }
+
+    #[test]
+    pub fn test_swap_message_v1_relay_with_payload_not_supported() {
+        // Same as the V2 relay with payload message, but with the V1 version byte.
+        let redeemer_message =
+            hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d030000a4550000000f424000000000000000000000000000000000000000000000000000000000000ba5ed0004deadbeef00");
+
+        assert_eq!(
+            SwapMessageV1::try_read_slice(&redeemer_message),
+            Err(SwapMessageError::UnknownDiscriminant {
+                field: "redeem_mode",
+                value: 3
+            })
+        );
+        assert_eq!(
+            crate::raw::SwapMessageRef::parse(&redeemer_message),
+            Err(SwapMessageError::UnknownDiscriminant {
+                field: "redeem_mode",
+                value: 3
+            })
+        );
+
+        let swap_message = SwapMessageV1 {
+            recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+            redeem_mode: RedeemMode::RelayWithPayload {
+                gas_dropoff: 42069,
+                relaying_fee: Uint48::try_from(1000000u32).unwrap(),
+                sender: hex!("00000000000000000000000000000000000000000000000000000000000ba5ed"),
+                buf: hex!("deadbeef").to_vec().try_into().unwrap(),
+            },
+            output_token: OutputToken::Usdc,
+        };
+        assert_eq!(
+            swap_message.try_encode(),
+            Err(SwapMessageError::RedeemModeNotSupported { version: 1 })
+        );
+
+        // The same message can be encoded as V2.
+        let swap_message = crate::messages::SwapMessage::V2(swap_message.into());
+        assert_eq!(
+            swap_message.try_encode().unwrap(),
+            hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d030000a4550000000f424000000000000000000000000000000000000000000000000000000000000ba5ed0004deadbeef00000000")
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    pub fn test_swap_message_v1_serde_json() {
+        let redeemer_message =
+            hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d00010000000000000000000000000000000000000000010001f4015991a2df15a8f6a256d3ec51e99254cd3fb576a90001f4");
+
+        let swap_message = SwapMessageV1::read_slice(&redeemer_message).unwrap();
+        let json = serde_json::to_value(&swap_message).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "recipient": "0x0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d",
+                "redeemMode": { "mode": "Direct" },
+                "outputToken": {
+                    "type": "Gas",
+                    "deadline": 0,
+                    "limitAmount": "0",
+                    "swapType": {
+                        "type": "UniswapV3",
+                        "firstLegFee": 500,
+                        "path": [{
+                            "evmAddress": "0x5991a2df15a8f6a256d3ec51e99254cd3fb576a9",
+                            "fee": 500
+                        }]
+                    },
+                    "mode": "ExactIn"
+                }
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<SwapMessageV1>(json).unwrap(),
+            swap_message
+        );
+
+        let swap_message = SwapMessageV1 {
+            recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+            redeem_mode: RedeemMode::Payload {
+                sender: hex!("00000000000000000000000000000000000000000000000000000000000ba5ed"),
+                buf: hex!("deadbeef").to_vec().try_into().unwrap(),
+            },
+            output_token: OutputToken::Usdc,
+        };
+        let json = serde_json::to_string(&swap_message).unwrap();
+        assert_eq!(
+            json,
+            r#"{"recipient":"0x0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d","redeemMode":{"mode":"Payload","sender":"0x00000000000000000000000000000000000000000000000000000000000ba5ed","buf":"0xdeadbeef"},"outputToken":{"type":"Usdc"}}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<SwapMessageV1>(&json).unwrap(),
+            swap_message
+        );
+    }
+}
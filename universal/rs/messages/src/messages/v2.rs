@@ -0,0 +1,470 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    error::{Codec, SwapMessageError},
+    io,
+    types::{
+        encoded_split_outputs_len, validate_recipient_for_target,
+        validate_split_outputs_for_target, IntegratorFee, OutputToken, RedeemMode, SplitOutput,
+    },
+    wormhole_io::{Readable, TypePrefixedPayload, Writeable},
+};
+
+use super::{SwapMessage, SwapMessageV1};
+
+/// Second version of the swap message. This version begins with the same fields as
+/// [SwapMessageV1], except that output swaps are followed by their
+/// [SwapMode](crate::types::SwapMode). New fields are appended after the output token, so
+/// consumers of this version only need to know how to read the fields that exist in the version
+/// they support.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SwapMessageV2 {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_array"))]
+    pub recipient: [u8; 32],
+    pub redeem_mode: RedeemMode,
+    pub output_token: OutputToken,
+    /// Recipient of the USDC if the swap cannot be executed (e.g. its deadline has passed), in
+    /// case the recipient cannot hold USDC. If not specified, the USDC goes to the recipient.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::hex_array_option", default)
+    )]
+    pub fallback_recipient: Option<[u8; 32]>,
+    /// Fee paid to an integrator out of the USDC delivered by the fill. The destination Swap Layer
+    /// caps this fee at the maximum it has configured for the source chain.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub integrator_fee: Option<IntegratorFee>,
+    /// Shares of the USDC delivered to other recipients, each with its own output token. The
+    /// recipient above receives whatever is left. See [SplitOutput].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub split_outputs: Vec<SplitOutput>,
+}
+
+impl SwapMessageV2 {
+    /// Check that the message can be executed on the target chain (Wormhole chain ID). See
+    /// [OutputToken::validate_for_target].
+    pub fn validate_for_target(&self, chain: u16) -> Result<(), SwapMessageError> {
+        validate_recipient_for_target(&self.recipient, chain)?;
+        if let Some(fallback_recipient) = &self.fallback_recipient {
+            validate_recipient_for_target(fallback_recipient, chain).map_err(|_| {
+                SwapMessageError::InvalidEvmAddress {
+                    field: "fallback_recipient",
+                }
+            })?;
+        }
+        if let Some(integrator_fee) = &self.integrator_fee {
+            integrator_fee.validate_for_target(chain)?;
+        }
+        if !self.split_outputs.is_empty() {
+            // Payloads are delivered to one recipient, so they cannot be split.
+            if !matches!(
+                self.redeem_mode,
+                RedeemMode::Direct | RedeemMode::Relay { .. }
+            ) {
+                return Err(SwapMessageError::SplitRedeemModeNotSupported);
+            }
+            validate_split_outputs_for_target(&self.split_outputs, chain)?;
+        }
+        self.output_token.validate_for_target(chain)
+    }
+
+    /// Identifier of this message delivered by a fill. See [SwapMessageV1::id].
+    pub fn id(
+        &self,
+        source_chain: u16,
+        order_sender: &[u8; 32],
+    ) -> Result<[u8; 32], SwapMessageError> {
+        self.try_encode()
+            .map(|encoded| super::message_id(source_chain, order_sender, &encoded))
+    }
+
+    /// Describe the message as text. See [SwapMessageV1::explain].
+    pub fn explain(&self) -> String {
+        super::explain::Explanation {
+            version: SwapMessage::VERSION_2,
+            recipient: &self.recipient,
+            redeem_mode: &self.redeem_mode,
+            output_token: &self.output_token,
+            fallback_recipient: self.fallback_recipient.as_ref(),
+            integrator_fee: self.integrator_fee.as_ref(),
+            split_outputs: &self.split_outputs,
+        }
+        .to_text()
+    }
+}
+
+impl Readable for SwapMessageV2 {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        Ok(Self {
+            recipient: Readable::read(reader)?,
+            redeem_mode: Readable::read(reader)?,
+            output_token: OutputToken::read_v2(reader)?,
+            fallback_recipient: match u8::read(reader)? {
+                0 => None,
+                1 => Some(Readable::read(reader)?),
+                value => {
+                    return Err(SwapMessageError::UnknownDiscriminant {
+                        field: "fallback_recipient",
+                        value,
+                    }
+                    .into())
+                }
+            },
+            integrator_fee: match u8::read(reader)? {
+                0 => None,
+                1 => Some(Readable::read(reader)?),
+                value => {
+                    return Err(SwapMessageError::UnknownDiscriminant {
+                        field: "integrator_fee",
+                        value,
+                    }
+                    .into())
+                }
+            },
+            split_outputs: {
+                let len = usize::from(u8::read(reader)?);
+                if len > SplitOutput::MAX_OUTPUTS {
+                    return Err(SwapMessageError::TooManySplitOutputs { len }.into());
+                }
+
+                let mut split_outputs = Vec::with_capacity(len);
+                for _ in 0..len {
+                    split_outputs.push(Readable::read(reader)?);
+                }
+                split_outputs
+            },
+        })
+    }
+}
+
+impl Writeable for SwapMessageV2 {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.recipient.write(writer)?;
+        self.redeem_mode.write(writer)?;
+        self.output_token.write_v2(writer)?;
+        self.fallback_recipient.write(writer)?;
+        self.integrator_fee.write(writer)?;
+        encoded_split_outputs_len(&self.split_outputs)?.write(writer)?;
+        for split_output in &self.split_outputs {
+            split_output.write(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl TypePrefixedPayload<1> for SwapMessageV2 {
+    const TYPE: Option<[u8; 1]> = Some([2]);
+
+    fn written_size(&self) -> usize {
+        self.redeem_mode
+            .written_size()
+            .saturating_add(self.output_token.written_size_v2())
+            .saturating_add(32) // recipient
+            .saturating_add(match self.fallback_recipient {
+                Some(_) => 33,
+                None => 1,
+            })
+            .saturating_add(match &self.integrator_fee {
+                Some(integrator_fee) => integrator_fee.written_size().saturating_add(1),
+                None => 1,
+            })
+            .saturating_add(
+                self.split_outputs
+                    .iter()
+                    .map(SplitOutput::written_size)
+                    .fold(1, usize::saturating_add), // split_outputs len
+            )
+    }
+}
+
+impl From<SwapMessageV1> for SwapMessageV2 {
+    fn from(msg: SwapMessageV1) -> Self {
+        let SwapMessageV1 {
+            recipient,
+            redeem_mode,
+            output_token,
+        } = msg;
+
+        Self {
+            recipient,
+            redeem_mode,
+            output_token,
+            fallback_recipient: None,
+            integrator_fee: None,
+            split_outputs: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use crate::types::{
+        IntegratorFeeAmount, JupiterV6SwapParameters, OutputSwap, OutputToken, RedeemMode,
+        SwapMode, SwapType, Uint48,
+    };
+
+    use super::*;
+
+    #[test]
+    pub fn test_swap_message_v2_usdc_relay() {
+        let redeemer_message =
+            hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d02000000000000000f424000000000");
+
+        let swap_message = SwapMessageV2::read_slice(&redeemer_message).unwrap();
+        assert_eq!(
+            swap_message,
+            SwapMessageV2 {
+                recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+                redeem_mode: RedeemMode::Relay {
+                    gas_dropoff: 0,
+                    relaying_fee: Uint48::try_from(1000000u32).unwrap(),
+                },
+                output_token: OutputToken::Usdc,
+                fallback_recipient: None,
+                integrator_fee: None,
+                split_outputs: vec![],
+            }
+        );
+
+        assert_eq!(swap_message.to_vec(), redeemer_message);
+    }
+
+    #[test]
+    pub fn test_swap_message_v2_usdc_relay_with_payload() {
+        let redeemer_message =
+            hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d030000a4550000000f424000000000000000000000000000000000000000000000000000000000000ba5ed0004deadbeef00000000");
+
+        let swap_message = SwapMessageV2::read_slice(&redeemer_message).unwrap();
+        assert_eq!(
+            swap_message,
+            SwapMessageV2 {
+                recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+                redeem_mode: RedeemMode::RelayWithPayload {
+                    gas_dropoff: 42069,
+                    relaying_fee: Uint48::try_from(1000000u32).unwrap(),
+                    sender: hex!(
+                        "00000000000000000000000000000000000000000000000000000000000ba5ed"
+                    ),
+                    buf: hex!("deadbeef").to_vec().try_into().unwrap(),
+                },
+                output_token: OutputToken::Usdc,
+                fallback_recipient: None,
+                integrator_fee: None,
+                split_outputs: vec![],
+            }
+        );
+
+        assert_eq!(swap_message.to_vec(), redeemer_message);
+        assert_eq!(
+            swap_message.written_size(),
+            redeemer_message.len().saturating_sub(1)
+        );
+    }
+
+    #[test]
+    pub fn test_swap_message_v2_fallback_recipient() {
+        let mut redeemer_message =
+            hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d00000100000000000000000000000000000000000000000000000000000000000ba5ed0000");
+
+        let swap_message = SwapMessageV2::read_slice(&redeemer_message).unwrap();
+        assert_eq!(
+            swap_message,
+            SwapMessageV2 {
+                recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+                redeem_mode: RedeemMode::Direct,
+                output_token: OutputToken::Usdc,
+                fallback_recipient: Some(hex!(
+                    "00000000000000000000000000000000000000000000000000000000000ba5ed"
+                )),
+                integrator_fee: None,
+                split_outputs: vec![],
+            }
+        );
+        assert_eq!(swap_message.to_vec(), redeemer_message);
+        assert_eq!(
+            swap_message.written_size(),
+            redeemer_message.len().saturating_sub(1)
+        );
+
+        // The fallback recipient must be a valid recipient on the target chain.
+        let swap_message = SwapMessageV2 {
+            fallback_recipient: Some([1; 32]),
+            ..swap_message
+        };
+        assert_eq!(swap_message.validate_for_target(1), Ok(()));
+        assert_eq!(
+            swap_message.validate_for_target(2),
+            Err(SwapMessageError::InvalidEvmAddress {
+                field: "fallback_recipient"
+            })
+        );
+
+        redeemer_message[35] = 2;
+        assert_eq!(
+            SwapMessageError::from(SwapMessageV2::read_slice(&redeemer_message).unwrap_err()),
+            SwapMessageError::UnknownDiscriminant {
+                field: "fallback_recipient",
+                value: 2
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_swap_message_v2_integrator_fee() {
+        let mut redeemer_message =
+            hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0000000100000000000000000000000000000000000000000000000000000000000ba5ed00001900");
+
+        let swap_message = SwapMessageV2::read_slice(&redeemer_message).unwrap();
+        assert_eq!(
+            swap_message,
+            SwapMessageV2 {
+                recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+                redeem_mode: RedeemMode::Direct,
+                output_token: OutputToken::Usdc,
+                fallback_recipient: None,
+                integrator_fee: Some(IntegratorFee {
+                    recipient: hex!(
+                        "00000000000000000000000000000000000000000000000000000000000ba5ed"
+                    ),
+                    amount: IntegratorFeeAmount::Bps { bps: 25 },
+                }),
+                split_outputs: vec![],
+            }
+        );
+        assert_eq!(swap_message.to_vec(), redeemer_message);
+        assert_eq!(
+            swap_message.written_size(),
+            redeemer_message.len().saturating_sub(1)
+        );
+        assert_eq!(swap_message.validate_for_target(2), Ok(()));
+
+        // The integrator fee cannot exceed 100%.
+        let invalid = SwapMessageV2 {
+            integrator_fee: Some(IntegratorFee {
+                recipient: [0; 32],
+                amount: IntegratorFeeAmount::Bps { bps: 10_001 },
+            }),
+            ..swap_message
+        };
+        assert_eq!(
+            invalid.validate_for_target(2),
+            Err(SwapMessageError::ValueOutOfRange {
+                field: "integrator_fee.bps",
+                value: 10_001
+            })
+        );
+
+        redeemer_message[36] = 2;
+        assert_eq!(
+            SwapMessageError::from(SwapMessageV2::read_slice(&redeemer_message).unwrap_err()),
+            SwapMessageError::UnknownDiscriminant {
+                field: "integrator_fee",
+                value: 2
+            }
+        );
+    }
+
+    #[test]
+    pub fn test_swap_message_v2_split_outputs() {
+        let redeemer_message = hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d02000000000000000f42400000000200000000000000000000000000000000000000000000000000000000000ba5ed09c400000000000000000000000000000000000000000000000000000000000000f00d03e8010000000000000000000000000000000000000000100000");
+
+        let swap_message = SwapMessageV2::read_slice(&redeemer_message).unwrap();
+        assert_eq!(
+            swap_message,
+            SwapMessageV2 {
+                recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+                redeem_mode: RedeemMode::Relay {
+                    gas_dropoff: 0,
+                    relaying_fee: Uint48::try_from(1000000u32).unwrap(),
+                },
+                output_token: OutputToken::Usdc,
+                fallback_recipient: None,
+                integrator_fee: None,
+                split_outputs: vec![
+                    SplitOutput {
+                        recipient: hex!(
+                            "00000000000000000000000000000000000000000000000000000000000ba5ed"
+                        ),
+                        bps: 2500,
+                        output_token: OutputToken::Usdc,
+                    },
+                    SplitOutput {
+                        recipient: hex!(
+                            "000000000000000000000000000000000000000000000000000000000000f00d"
+                        ),
+                        bps: 1000,
+                        output_token: OutputToken::Gas(OutputSwap {
+                            deadline: 0,
+                            limit_amount: 0,
+                            swap_type: SwapType::JupiterV6(JupiterV6SwapParameters {
+                                dex_program_id: None,
+                            }),
+                            mode: SwapMode::ExactIn,
+                        }),
+                    },
+                ],
+            }
+        );
+        assert_eq!(swap_message.to_vec(), redeemer_message);
+        assert_eq!(
+            swap_message.written_size(),
+            redeemer_message.len().saturating_sub(1)
+        );
+        assert_eq!(swap_message.validate_for_target(1), Ok(()));
+
+        // Payloads cannot be split.
+        let payload = SwapMessageV2 {
+            redeem_mode: RedeemMode::Payload {
+                sender: [0; 32],
+                buf: Vec::new().try_into().unwrap(),
+            },
+            ..swap_message.clone()
+        };
+        assert_eq!(
+            payload.validate_for_target(1),
+            Err(SwapMessageError::SplitRedeemModeNotSupported)
+        );
+
+        // Too many split outputs cannot be written or read.
+        let too_many = SwapMessageV2 {
+            split_outputs: vec![
+                swap_message.split_outputs[0].clone();
+                SplitOutput::MAX_OUTPUTS.saturating_add(1)
+            ],
+            ..swap_message
+        };
+        let err = SwapMessageError::TooManySplitOutputs { len: 9 };
+        assert_eq!(
+            SwapMessageError::from(too_many.write(&mut Vec::new()).unwrap_err()),
+            err
+        );
+
+        let mut encoded = Vec::new();
+        SwapMessageV2 {
+            split_outputs: vec![],
+            ..too_many.clone()
+        }
+        .write(&mut encoded)
+        .unwrap();
+        encoded.pop();
+        encoded.push(9);
+        for split_output in &too_many.split_outputs {
+            split_output.write(&mut encoded).unwrap();
+        }
+        assert_eq!(
+            SwapMessageError::from(SwapMessageV2::read(&mut &encoded[..]).unwrap_err()),
+            err
+        );
+    }
+}
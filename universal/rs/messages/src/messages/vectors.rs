@@ -0,0 +1,85 @@
+//! Golden test vectors shared with the EVM encoder tests (see evm/test/SwapMessageVectors.t.sol).
+//! Each vector is a structured swap message (in this crate's serde representation) and its
+//! encoding, which both implementations must agree on.
+
+use serde::Deserialize;
+
+use crate::{
+    error::Codec,
+    messages::SwapMessage,
+    raw::SwapMessageRef,
+    wormhole_io::{TypePrefixedPayload, Writeable},
+};
+
+const SWAP_MESSAGE_VECTORS: &str = include_str!("../../../../vectors/swap_messages.json");
+
+#[derive(Debug, Deserialize)]
+struct TestVectors {
+    vectors: Vec<TestVector>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TestVector {
+    description: String,
+    message: SwapMessage,
+    encoded: String,
+}
+
+impl TestVector {
+    fn encoded(&self) -> Vec<u8> {
+        let encoded = self.encoded.strip_prefix("0x").unwrap_or(&self.encoded);
+        hex::decode(encoded).unwrap()
+    }
+}
+
+fn load_vectors() -> Vec<TestVector> {
+    serde_json::from_str::<TestVectors>(SWAP_MESSAGE_VECTORS)
+        .unwrap()
+        .vectors
+}
+
+#[test]
+fn test_swap_message_vectors() {
+    let vectors = load_vectors();
+    assert!(!vectors.is_empty());
+
+    for vector in vectors {
+        let TestVector {
+            description,
+            message,
+            ..
+        } = &vector;
+        let encoded = vector.encoded();
+
+        assert_eq!(
+            message.try_encode().unwrap(),
+            encoded,
+            "encode: {description}"
+        );
+        assert_eq!(
+            message.written_size(),
+            encoded.len(),
+            "written size: {description}"
+        );
+
+        let mut written = Vec::with_capacity(encoded.len());
+        message.write(&mut written).unwrap();
+        assert_eq!(written, encoded, "write: {description}");
+
+        assert_eq!(
+            &SwapMessage::try_read_slice(&encoded).unwrap(),
+            message,
+            "decode: {description}"
+        );
+        assert_eq!(
+            &SwapMessage::read_slice(&encoded).unwrap(),
+            message,
+            "read: {description}"
+        );
+        assert_eq!(
+            &SwapMessageRef::parse(&encoded).unwrap().decode().unwrap(),
+            message,
+            "zero-copy view: {description}"
+        );
+    }
+}
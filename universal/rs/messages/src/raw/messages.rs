@@ -0,0 +1,549 @@
+use crate::{
+    error::SwapMessageError,
+    messages::{SwapMessage, SwapMessageV1, SwapMessageV2},
+    types::RedeemMode,
+    wormhole_io::TypePrefixedPayload,
+};
+
+use super::{Cursor, IntegratorFeeRef, OutputTokenRef, RedeemModeRef, SplitOutputsRef};
+
+/// Fields shared by all swap message versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SwapMessageFields<'a> {
+    recipient: &'a [u8; 32],
+    redeem_mode: RedeemModeRef<'a>,
+    output_token: OutputTokenRef<'a>,
+}
+
+impl<'a> SwapMessageFields<'a> {
+    /// Read the fields of a message whose version byte is expected to be `version`. Fields added
+    /// by later versions follow these.
+    fn read(cursor: &mut Cursor<'a>, version: u8) -> Result<Self, SwapMessageError> {
+        match cursor.read_u8()? {
+            value if value == version => {}
+            value => {
+                return Err(SwapMessageError::UnknownDiscriminant {
+                    field: "version",
+                    value,
+                })
+            }
+        }
+
+        let recipient = cursor.take_array()?;
+
+        // Relaying with a payload was added by the second version.
+        let redeem_mode = RedeemModeRef::read(cursor)?;
+        if version == SwapMessage::VERSION_1
+            && matches!(redeem_mode, RedeemModeRef::RelayWithPayload { .. })
+        {
+            return Err(SwapMessageError::UnknownDiscriminant {
+                field: "redeem_mode",
+                value: RedeemMode::RELAY_WITH_PAYLOAD,
+            });
+        }
+
+        // Output swaps are followed by their mode since the second version.
+        let output_token = OutputTokenRef::read(cursor, version != SwapMessage::VERSION_1)?;
+
+        Ok(Self {
+            recipient,
+            redeem_mode,
+            output_token,
+        })
+    }
+}
+
+/// Zero-copy view of an encoded [SwapMessageV1], including its version byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapMessageV1Ref<'a> {
+    span: &'a [u8],
+    fields: SwapMessageFields<'a>,
+}
+
+impl<'a> SwapMessageV1Ref<'a> {
+    pub fn parse(span: &'a [u8]) -> Result<Self, SwapMessageError> {
+        let mut cursor = Cursor::new(span);
+        let fields = SwapMessageFields::read(&mut cursor, SwapMessage::VERSION_1)?;
+        cursor.finish()?;
+        Ok(Self { span, fields })
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.span
+    }
+
+    pub fn recipient(&self) -> &'a [u8; 32] {
+        self.fields.recipient
+    }
+
+    pub fn redeem_mode(&self) -> RedeemModeRef<'a> {
+        self.fields.redeem_mode
+    }
+
+    pub fn output_token(&self) -> OutputTokenRef<'a> {
+        self.fields.output_token
+    }
+
+    /// Decode into an owned message. The view has already been validated, so this only fails if
+    /// the encoding cannot be read by [SwapMessageV1].
+    pub fn decode(&self) -> Result<SwapMessageV1, SwapMessageError> {
+        SwapMessageV1::read_slice(self.span).map_err(Into::into)
+    }
+}
+
+/// Zero-copy view of an encoded [SwapMessageV2], including its version byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapMessageV2Ref<'a> {
+    span: &'a [u8],
+    fields: SwapMessageFields<'a>,
+    fallback_recipient: Option<&'a [u8; 32]>,
+    integrator_fee: Option<IntegratorFeeRef<'a>>,
+    split_outputs: SplitOutputsRef<'a>,
+}
+
+impl<'a> SwapMessageV2Ref<'a> {
+    pub fn parse(span: &'a [u8]) -> Result<Self, SwapMessageError> {
+        let mut cursor = Cursor::new(span);
+        let fields = SwapMessageFields::read(&mut cursor, SwapMessage::VERSION_2)?;
+        let fallback_recipient = cursor.read_option_array("fallback_recipient")?;
+        let integrator_fee = IntegratorFeeRef::read_option(&mut cursor)?;
+        let split_outputs = SplitOutputsRef::read(&mut cursor)?;
+        cursor.finish()?;
+        Ok(Self {
+            span,
+            fields,
+            fallback_recipient,
+            integrator_fee,
+            split_outputs,
+        })
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.span
+    }
+
+    pub fn recipient(&self) -> &'a [u8; 32] {
+        self.fields.recipient
+    }
+
+    pub fn redeem_mode(&self) -> RedeemModeRef<'a> {
+        self.fields.redeem_mode
+    }
+
+    pub fn output_token(&self) -> OutputTokenRef<'a> {
+        self.fields.output_token
+    }
+
+    pub fn fallback_recipient(&self) -> Option<&'a [u8; 32]> {
+        self.fallback_recipient
+    }
+
+    pub fn integrator_fee(&self) -> Option<IntegratorFeeRef<'a>> {
+        self.integrator_fee
+    }
+
+    pub fn split_outputs(&self) -> SplitOutputsRef<'a> {
+        self.split_outputs
+    }
+
+    /// Decode into an owned message. The view has already been validated, so this only fails if
+    /// the encoding cannot be read by [SwapMessageV2].
+    pub fn decode(&self) -> Result<SwapMessageV2, SwapMessageError> {
+        SwapMessageV2::read_slice(self.span).map_err(Into::into)
+    }
+}
+
+/// Zero-copy view of an encoded [SwapMessage] of any supported version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapMessageRef<'a> {
+    V1(SwapMessageV1Ref<'a>),
+    V2(SwapMessageV2Ref<'a>),
+}
+
+impl<'a> SwapMessageRef<'a> {
+    pub fn parse(span: &'a [u8]) -> Result<Self, SwapMessageError> {
+        match span.first() {
+            Some(&SwapMessage::VERSION_1) => SwapMessageV1Ref::parse(span).map(Self::V1),
+            Some(&SwapMessage::VERSION_2) => SwapMessageV2Ref::parse(span).map(Self::V2),
+            Some(&value) => Err(SwapMessageError::UnknownDiscriminant {
+                field: "version",
+                value,
+            }),
+            None => Err(SwapMessageError::Truncated { offset: 0 }),
+        }
+    }
+
+    pub fn version(&self) -> u8 {
+        match self {
+            Self::V1(_) => SwapMessage::VERSION_1,
+            Self::V2(_) => SwapMessage::VERSION_2,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        match self {
+            Self::V1(msg) => msg.as_bytes(),
+            Self::V2(msg) => msg.as_bytes(),
+        }
+    }
+
+    pub fn recipient(&self) -> &'a [u8; 32] {
+        match self {
+            Self::V1(msg) => msg.recipient(),
+            Self::V2(msg) => msg.recipient(),
+        }
+    }
+
+    pub fn redeem_mode(&self) -> RedeemModeRef<'a> {
+        match self {
+            Self::V1(msg) => msg.redeem_mode(),
+            Self::V2(msg) => msg.redeem_mode(),
+        }
+    }
+
+    pub fn output_token(&self) -> OutputTokenRef<'a> {
+        match self {
+            Self::V1(msg) => msg.output_token(),
+            Self::V2(msg) => msg.output_token(),
+        }
+    }
+
+    /// Recipient of the USDC if the swap cannot be executed. Only V2 messages may specify one.
+    pub fn fallback_recipient(&self) -> Option<&'a [u8; 32]> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(msg) => msg.fallback_recipient(),
+        }
+    }
+
+    /// Fee paid to an integrator out of the USDC. Only V2 messages may specify one.
+    pub fn integrator_fee(&self) -> Option<IntegratorFeeRef<'a>> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(msg) => msg.integrator_fee(),
+        }
+    }
+
+    /// Shares of the USDC delivered to other recipients. Only V2 messages may specify them.
+    pub fn split_outputs(&self) -> SplitOutputsRef<'a> {
+        match self {
+            Self::V1(_) => Default::default(),
+            Self::V2(msg) => msg.split_outputs(),
+        }
+    }
+
+    /// Decode into an owned message.
+    pub fn decode(&self) -> Result<SwapMessage, SwapMessageError> {
+        SwapMessage::read_slice(self.as_bytes()).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use crate::{
+        error::Codec,
+        raw::SwapTypeRef,
+        types::{
+            IntegratorFeeAmount, OutputSwap, OutputToken, RedeemMode, SwapMode, SwapType,
+            TraderJoePoolId, TraderJoeSwapParameters, TraderJoeSwapPath, TraderJoeVersion, Uint24,
+            Uint48, UniswapSwapPath,
+        },
+    };
+
+    use super::*;
+
+    const V1_ETH_SWAP: [u8; 83] = hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d00010000000000000000000000000000000000000000010001f4015991a2df15a8f6a256d3ec51e99254cd3fb576a90001f4");
+
+    #[test]
+    fn test_swap_message_ref_uniswap_v3() {
+        let msg = SwapMessageRef::parse(&V1_ETH_SWAP).unwrap();
+        assert_eq!(msg.version(), SwapMessage::VERSION_1);
+        assert_eq!(msg.as_bytes(), V1_ETH_SWAP);
+        assert_eq!(
+            msg.recipient(),
+            &hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d")
+        );
+        assert_eq!(msg.redeem_mode(), RedeemModeRef::Direct);
+
+        let swap = *msg.output_token().swap().unwrap();
+        assert_eq!((swap.deadline, swap.limit_amount), (0, 0));
+        assert!(!swap.is_exact_out());
+
+        let (first_leg_fee, path) = match swap.swap_type {
+            SwapTypeRef::UniswapV3 {
+                first_leg_fee,
+                path,
+            } => Some((first_leg_fee, path)),
+            _ => None,
+        }
+        .unwrap();
+        assert_eq!(first_leg_fee, Uint24::from(500));
+        assert_eq!(path.len(), 1);
+        assert_eq!(
+            path.iter().collect::<Vec<_>>(),
+            vec![UniswapSwapPath {
+                evm_address: hex!("5991a2df15a8f6a256d3ec51e99254cd3fb576a9"),
+                fee: Uint24::from(500),
+            }]
+        );
+
+        assert_eq!(
+            msg.decode().unwrap(),
+            SwapMessage::read_slice(&V1_ETH_SWAP).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_swap_message_ref_payload_and_relay() {
+        let encoded =
+            hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0100000000000000000000000000000000000000000000000000000000000ba5ed0004deadbeef00000000");
+
+        let msg = SwapMessageV2Ref::parse(&encoded).unwrap();
+        assert_eq!(
+            msg.redeem_mode(),
+            RedeemModeRef::Payload {
+                sender: &hex!("00000000000000000000000000000000000000000000000000000000000ba5ed"),
+                buf: &hex!("deadbeef"),
+            }
+        );
+        assert_eq!(msg.output_token(), OutputTokenRef::Usdc);
+        assert_eq!(
+            SwapMessageV1Ref::parse(&encoded),
+            Err(SwapMessageError::UnknownDiscriminant {
+                field: "version",
+                value: 2
+            })
+        );
+
+        let encoded =
+            hex!("010000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d02000000000000000f424000");
+        assert_eq!(
+            SwapMessageRef::parse(&encoded).unwrap().redeem_mode(),
+            RedeemModeRef::Relay {
+                gas_dropoff: 0,
+                relaying_fee: Uint48::try_from(1000000u32).unwrap(),
+            }
+        );
+
+        let encoded =
+            hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d030000a4550000000f424000000000000000000000000000000000000000000000000000000000000ba5ed0004deadbeef00000000");
+        assert_eq!(
+            SwapMessageRef::parse(&encoded).unwrap().redeem_mode(),
+            RedeemModeRef::RelayWithPayload {
+                gas_dropoff: 42069,
+                relaying_fee: Uint48::try_from(1000000u32).unwrap(),
+                sender: &hex!("00000000000000000000000000000000000000000000000000000000000ba5ed"),
+                buf: &hex!("deadbeef"),
+            }
+        );
+        assert_eq!(
+            SwapMessageRef::parse(&encoded[..encoded.len().saturating_sub(5)]),
+            Err(SwapMessageError::Truncated { offset: 78 })
+        );
+
+        let encoded =
+            hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d00000100000000000000000000000000000000000000000000000000000000000ba5ed0000");
+        let msg = SwapMessageRef::parse(&encoded).unwrap();
+        assert_eq!(
+            msg.fallback_recipient(),
+            Some(&hex!(
+                "00000000000000000000000000000000000000000000000000000000000ba5ed"
+            ))
+        );
+        assert_eq!(
+            msg.decode().unwrap().fallback_recipient(),
+            msg.fallback_recipient()
+        );
+        assert_eq!(
+            SwapMessageRef::parse(&encoded[..35]),
+            Err(SwapMessageError::Truncated { offset: 35 })
+        );
+        assert_eq!(msg.integrator_fee(), None);
+
+        let encoded =
+            hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d0000000100000000000000000000000000000000000000000000000000000000000ba5ed010000000f424000");
+        let msg = SwapMessageRef::parse(&encoded).unwrap();
+        assert_eq!(
+            msg.integrator_fee(),
+            Some(IntegratorFeeRef {
+                recipient: &hex!(
+                    "00000000000000000000000000000000000000000000000000000000000ba5ed"
+                ),
+                amount: IntegratorFeeAmount::Absolute {
+                    amount: Uint48::try_from(1000000u32).unwrap(),
+                },
+            })
+        );
+        assert_eq!(
+            msg.decode().unwrap().integrator_fee().map(|fee| fee.amount),
+            msg.integrator_fee().map(|fee| fee.amount)
+        );
+        assert_eq!(
+            SwapMessageRef::parse(&encoded[..encoded.len().saturating_sub(2)]),
+            Err(SwapMessageError::Truncated { offset: 70 })
+        );
+
+        assert!(msg.split_outputs().is_empty());
+
+        let encoded = hex!("020000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d02000000000000000f42400000000200000000000000000000000000000000000000000000000000000000000ba5ed09c400000000000000000000000000000000000000000000000000000000000000f00d03e8010000000000000000000000000000000000000000100000");
+        let msg = SwapMessageRef::parse(&encoded).unwrap();
+        let split_outputs = msg.split_outputs();
+        assert_eq!(split_outputs.len(), 2);
+        assert_eq!(
+            split_outputs
+                .iter()
+                .map(|split_output| (split_output.bps, split_output.amount(1_000_000)))
+                .collect::<Vec<_>>(),
+            vec![(2500, 250_000), (1000, 100_000)]
+        );
+        let gas = split_outputs.iter().nth(1).unwrap();
+        assert_eq!(
+            gas.recipient,
+            &hex!("000000000000000000000000000000000000000000000000000000000000f00d")
+        );
+        assert!(matches!(gas.output_token, OutputTokenRef::Gas(_)));
+        assert_eq!(
+            msg.decode().unwrap().split_outputs().len(),
+            split_outputs.len()
+        );
+        assert_eq!(
+            SwapMessageRef::parse(&encoded[..encoded.len().saturating_sub(1)]),
+            Err(SwapMessageError::Truncated { offset: 140 })
+        );
+    }
+
+    #[test]
+    fn test_swap_message_ref_trader_joe() {
+        let path = vec![
+            TraderJoeSwapPath {
+                evm_address: [3; 20],
+                pool_id: TraderJoePoolId {
+                    version: TraderJoeVersion::V2,
+                    bin_size: 10,
+                },
+            },
+            TraderJoeSwapPath {
+                evm_address: [4; 20],
+                pool_id: TraderJoePoolId {
+                    version: TraderJoeVersion::V1,
+                    bin_size: 0,
+                },
+            },
+        ];
+        let swap_message = SwapMessageV1 {
+            recipient: [1; 32],
+            redeem_mode: RedeemMode::Direct,
+            output_token: OutputToken::Other {
+                address: [2; 32],
+                swap: OutputSwap {
+                    deadline: 69,
+                    limit_amount: 420,
+                    swap_type: SwapType::TraderJoe(TraderJoeSwapParameters {
+                        first_pool_id: TraderJoePoolId {
+                            version: TraderJoeVersion::V2_1,
+                            bin_size: 25,
+                        },
+                        path: path.clone(),
+                    }),
+                    mode: SwapMode::ExactIn,
+                },
+            },
+        };
+        let encoded = swap_message.try_encode().unwrap();
+
+        let msg = SwapMessageV1Ref::parse(&encoded).unwrap();
+        let (address, swap) = match msg.output_token() {
+            OutputTokenRef::Other { address, swap } => Some((address, swap)),
+            _ => None,
+        }
+        .unwrap();
+        assert_eq!(address, &[2; 32]);
+        assert_eq!((swap.deadline, swap.limit_amount), (69, 420));
+
+        let (first_pool_id, path_ref) = match swap.swap_type {
+            SwapTypeRef::TraderJoe {
+                first_pool_id,
+                path,
+            } => Some((first_pool_id, path)),
+            _ => None,
+        }
+        .unwrap();
+        assert_eq!(
+            first_pool_id,
+            TraderJoePoolId {
+                version: TraderJoeVersion::V2_1,
+                bin_size: 25
+            }
+        );
+        assert_eq!(path_ref.len(), 2);
+        assert_eq!(path_ref.iter().collect::<Vec<_>>(), path);
+
+        assert_eq!(msg.decode().unwrap(), swap_message);
+
+        // An unknown version in any hop is rejected up front, like the owned decoder rejects it.
+        let mut unknown = encoded.clone();
+        let version_offset = unknown.len().saturating_sub(3);
+        unknown[version_offset] = 7;
+        let err = SwapMessageError::UnknownDiscriminant {
+            field: "trader_joe.version",
+            value: 7,
+        };
+        assert_eq!(SwapMessageV1Ref::parse(&unknown), Err(err.clone()));
+        assert_eq!(SwapMessageV1::try_read_slice(&unknown), Err(err));
+    }
+
+    #[test]
+    fn test_swap_message_ref_errors() {
+        // Any truncation is rejected, just like the owned decoder rejects it.
+        for len in 0..V1_ETH_SWAP.len() {
+            let truncated = &V1_ETH_SWAP[..len];
+            assert!(matches!(
+                SwapMessageRef::parse(truncated),
+                Err(SwapMessageError::Truncated { .. })
+            ));
+            assert!(SwapMessage::try_read_slice(truncated).is_err());
+        }
+
+        // Same offsets as the owned decoder for fixed-size fields.
+        assert_eq!(
+            SwapMessageRef::parse(&V1_ETH_SWAP[..34]),
+            Err(SwapMessageError::Truncated { offset: 34 })
+        );
+        assert_eq!(
+            SwapMessageRef::parse(&V1_ETH_SWAP[..40]).map(|_| ()),
+            SwapMessage::try_read_slice(&V1_ETH_SWAP[..40]).map(|_| ())
+        );
+
+        let mut trailing = V1_ETH_SWAP.to_vec();
+        trailing.push(0);
+        assert_eq!(
+            SwapMessageRef::parse(&trailing),
+            Err(SwapMessageError::TrailingBytes {
+                offset: V1_ETH_SWAP.len(),
+                remaining: 1
+            })
+        );
+
+        let mut unknown = V1_ETH_SWAP;
+        unknown[0] = 3;
+        assert_eq!(
+            SwapMessageRef::parse(&unknown),
+            Err(SwapMessageError::UnknownDiscriminant {
+                field: "version",
+                value: 3
+            })
+        );
+
+        unknown[0] = 1;
+        unknown[55] = 9;
+        assert_eq!(
+            SwapMessageRef::parse(&unknown),
+            Err(SwapMessageError::UnknownDiscriminant {
+                field: "swap_type",
+                value: 9
+            })
+        );
+    }
+}
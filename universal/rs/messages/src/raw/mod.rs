@@ -0,0 +1,82 @@
+//! Zero-copy views of swap messages. Each view validates the encoded message once when it is
+//! parsed. Afterwards fields are read directly out of the borrowed slice, so no heap allocations
+//! are needed to inspect swap paths or payloads.
+
+mod messages;
+pub use messages::*;
+
+mod types;
+pub use types::*;
+
+use crate::error::SwapMessageError;
+
+/// Reads fields from a slice while keeping track of the offset so truncation can be reported.
+struct Cursor<'a> {
+    span: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(span: &'a [u8]) -> Self {
+        Self { span, offset: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SwapMessageError> {
+        let bytes = self
+            .offset
+            .checked_add(len)
+            .and_then(|end| self.span.get(self.offset..end))
+            .ok_or(SwapMessageError::Truncated {
+                offset: self.offset,
+            })?;
+        self.offset = self.offset.saturating_add(len);
+        Ok(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<&'a [u8; N], SwapMessageError> {
+        let offset = self.offset;
+        self.take(N)?
+            .try_into()
+            .map_err(|_| SwapMessageError::Truncated { offset })
+    }
+
+    fn read_u8(&mut self) -> Result<u8, SwapMessageError> {
+        self.take_array().map(|[value]| *value)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, SwapMessageError> {
+        self.take_array().copied().map(u16::from_be_bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, SwapMessageError> {
+        self.take_array().copied().map(u32::from_be_bytes)
+    }
+
+    fn read_u128(&mut self) -> Result<u128, SwapMessageError> {
+        self.take_array().copied().map(u128::from_be_bytes)
+    }
+
+    /// Read an optional array, which is encoded with a leading byte indicating whether it is
+    /// present.
+    fn read_option_array<const N: usize>(
+        &mut self,
+        field: &'static str,
+    ) -> Result<Option<&'a [u8; N]>, SwapMessageError> {
+        match self.read_u8()? {
+            0 => Ok(None),
+            1 => self.take_array().map(Some),
+            value => Err(SwapMessageError::UnknownDiscriminant { field, value }),
+        }
+    }
+
+    /// Consume the cursor, returning the slice that was read. Fails if any bytes remain.
+    fn finish(self) -> Result<&'a [u8], SwapMessageError> {
+        match self.span.len().saturating_sub(self.offset) {
+            0 => Ok(self.span),
+            remaining => Err(SwapMessageError::TrailingBytes {
+                offset: self.offset,
+                remaining,
+            }),
+        }
+    }
+}
@@ -0,0 +1,378 @@
+use core::{iter, marker::PhantomData};
+
+use crate::{
+    error::SwapMessageError,
+    types::{
+        IntegratorFeeAmount, OrcaWhirlpoolPool, SplitOutput, SwapMode, SwapType, TraderJoePoolId,
+        TraderJoeSwapPath, Uint24, Uint48, UniswapSwapPath,
+    },
+    wormhole_io::Readable,
+};
+
+use super::Cursor;
+
+/// Zero-copy view of [RedeemMode](crate::types::RedeemMode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedeemModeRef<'a> {
+    Direct,
+    Payload {
+        sender: &'a [u8; 32],
+        buf: &'a [u8],
+    },
+    Relay {
+        gas_dropoff: u32,
+        relaying_fee: Uint48,
+    },
+    RelayWithPayload {
+        gas_dropoff: u32,
+        relaying_fee: Uint48,
+        sender: &'a [u8; 32],
+        buf: &'a [u8],
+    },
+}
+
+impl<'a> RedeemModeRef<'a> {
+    /// Parse a redeem mode, which must span the entire slice.
+    pub fn parse(span: &'a [u8]) -> Result<Self, SwapMessageError> {
+        let mut cursor = Cursor::new(span);
+        let redeem_mode = Self::read(&mut cursor)?;
+        cursor.finish()?;
+        Ok(redeem_mode)
+    }
+
+    pub(super) fn read(cursor: &mut Cursor<'a>) -> Result<Self, SwapMessageError> {
+        match cursor.read_u8()? {
+            0 => Ok(Self::Direct),
+            1 => {
+                let sender = cursor.take_array()?;
+                let len = cursor.read_u16()?;
+                Ok(Self::Payload {
+                    sender,
+                    buf: cursor.take(len.into())?,
+                })
+            }
+            2 => Ok(Self::Relay {
+                gas_dropoff: cursor.read_u32()?,
+                relaying_fee: Uint48::from_be_bytes(*cursor.take_array()?),
+            }),
+            3 => {
+                let gas_dropoff = cursor.read_u32()?;
+                let relaying_fee = Uint48::from_be_bytes(*cursor.take_array()?);
+                let sender = cursor.take_array()?;
+                let len = cursor.read_u16()?;
+                Ok(Self::RelayWithPayload {
+                    gas_dropoff,
+                    relaying_fee,
+                    sender,
+                    buf: cursor.take(len.into())?,
+                })
+            }
+            value => Err(SwapMessageError::UnknownDiscriminant {
+                field: "redeem_mode",
+                value,
+            }),
+        }
+    }
+}
+
+/// Zero-copy view of [OutputToken](crate::types::OutputToken).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputTokenRef<'a> {
+    Usdc,
+    Gas(OutputSwapRef<'a>),
+    Other {
+        address: &'a [u8; 32],
+        swap: OutputSwapRef<'a>,
+    },
+}
+
+impl<'a> OutputTokenRef<'a> {
+    /// Parse an output token, which must span the entire slice.
+    pub fn parse(span: &'a [u8]) -> Result<Self, SwapMessageError> {
+        let mut cursor = Cursor::new(span);
+        let output_token = Self::read(&mut cursor, false)?;
+        cursor.finish()?;
+        Ok(output_token)
+    }
+
+    /// Parse an output token encoded as in a [SwapMessageV2](crate::messages::SwapMessageV2),
+    /// which must span the entire slice. See
+    /// [OutputToken::write_v2](crate::types::OutputToken::write_v2).
+    pub fn parse_v2(span: &'a [u8]) -> Result<Self, SwapMessageError> {
+        let mut cursor = Cursor::new(span);
+        let output_token = Self::read(&mut cursor, true)?;
+        cursor.finish()?;
+        Ok(output_token)
+    }
+
+    /// Swap to perform on the target network, if the output token is not USDC.
+    pub fn swap(&self) -> Option<&OutputSwapRef<'a>> {
+        match self {
+            Self::Usdc => None,
+            Self::Gas(swap) | Self::Other { address: _, swap } => Some(swap),
+        }
+    }
+
+    /// Read an output token. Output swaps are followed by their mode in swap message V2.
+    pub(super) fn read(cursor: &mut Cursor<'a>, with_mode: bool) -> Result<Self, SwapMessageError> {
+        match cursor.read_u8()? {
+            0 => Ok(Self::Usdc),
+            1 => Ok(Self::Gas(OutputSwapRef::read(cursor, with_mode)?)),
+            2 => Ok(Self::Other {
+                address: cursor.take_array()?,
+                swap: OutputSwapRef::read(cursor, with_mode)?,
+            }),
+            value => Err(SwapMessageError::UnknownDiscriminant {
+                field: "output_token",
+                value,
+            }),
+        }
+    }
+}
+
+/// Zero-copy view of [OutputSwap](crate::types::OutputSwap).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputSwapRef<'a> {
+    pub deadline: u32,
+    pub limit_amount: u128,
+    pub swap_type: SwapTypeRef<'a>,
+    pub mode: SwapMode,
+}
+
+impl<'a> OutputSwapRef<'a> {
+    /// Whether the limit amount is the exact amount of the output token to deliver. Otherwise the
+    /// limit amount is the minimum amount out.
+    pub fn is_exact_out(&self) -> bool {
+        self.mode == SwapMode::ExactOut
+    }
+
+    fn read(cursor: &mut Cursor<'a>, with_mode: bool) -> Result<Self, SwapMessageError> {
+        Ok(Self {
+            deadline: cursor.read_u32()?,
+            limit_amount: cursor.read_u128()?,
+            swap_type: SwapTypeRef::read(cursor)?,
+            mode: match with_mode {
+                true => match cursor.read_u8()? {
+                    0 => SwapMode::ExactIn,
+                    1 => SwapMode::ExactOut,
+                    value => {
+                        return Err(SwapMessageError::UnknownDiscriminant {
+                            field: "swap_mode",
+                            value,
+                        })
+                    }
+                },
+                false => SwapMode::ExactIn,
+            },
+        })
+    }
+}
+
+/// Zero-copy view of [IntegratorFee](crate::types::IntegratorFee).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegratorFeeRef<'a> {
+    pub recipient: &'a [u8; 32],
+    pub amount: IntegratorFeeAmount,
+}
+
+impl<'a> IntegratorFeeRef<'a> {
+    /// Read an optional integrator fee, which is encoded with a leading byte indicating whether it
+    /// is present.
+    pub(super) fn read_option(cursor: &mut Cursor<'a>) -> Result<Option<Self>, SwapMessageError> {
+        match cursor.read_u8()? {
+            0 => Ok(None),
+            1 => {
+                let recipient = cursor.take_array()?;
+                let amount = match cursor.read_u8()? {
+                    0 => IntegratorFeeAmount::Bps {
+                        bps: cursor.read_u16()?,
+                    },
+                    1 => IntegratorFeeAmount::Absolute {
+                        amount: Uint48::from_be_bytes(*cursor.take_array()?),
+                    },
+                    value => {
+                        return Err(SwapMessageError::UnknownDiscriminant {
+                            field: "integrator_fee.amount",
+                            value,
+                        })
+                    }
+                };
+                Ok(Some(Self { recipient, amount }))
+            }
+            value => Err(SwapMessageError::UnknownDiscriminant {
+                field: "integrator_fee",
+                value,
+            }),
+        }
+    }
+}
+
+/// Zero-copy view of [SplitOutput].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitOutputRef<'a> {
+    pub recipient: &'a [u8; 32],
+    pub bps: u16,
+    pub output_token: OutputTokenRef<'a>,
+}
+
+impl<'a> SplitOutputRef<'a> {
+    /// Amount of USDC this split output receives out of the amount being split. See
+    /// [SplitOutput::amount].
+    pub fn amount(&self, usdc_amount: u64) -> u64 {
+        crate::types::apply_bps(usdc_amount, self.bps)
+    }
+
+    fn read(cursor: &mut Cursor<'a>) -> Result<Self, SwapMessageError> {
+        Ok(Self {
+            recipient: cursor.take_array()?,
+            bps: cursor.read_u16()?,
+            output_token: OutputTokenRef::read(cursor, true)?,
+        })
+    }
+}
+
+/// Zero-copy view of the split outputs of a swap message. Split outputs vary in size (because of
+/// their output tokens), so they are validated when read and decoded again when iterated over.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SplitOutputsRef<'a> {
+    span: &'a [u8],
+    len: u8,
+}
+
+impl<'a> SplitOutputsRef<'a> {
+    pub(super) fn read(cursor: &mut Cursor<'a>) -> Result<Self, SwapMessageError> {
+        let len = cursor.read_u8()?;
+        if usize::from(len) > SplitOutput::MAX_OUTPUTS {
+            return Err(SwapMessageError::TooManySplitOutputs { len: len.into() });
+        }
+
+        let start = cursor.offset;
+        for _ in 0..len {
+            SplitOutputRef::read(cursor)?;
+        }
+
+        Ok(Self {
+            span: cursor.span.get(start..cursor.offset).unwrap_or_default(),
+            len,
+        })
+    }
+
+    /// Number of split outputs.
+    pub fn len(&self) -> usize {
+        self.len.into()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = SplitOutputRef<'a>> + 'a {
+        let mut cursor = Cursor::new(self.span);
+        iter::from_fn(move || SplitOutputRef::read(&mut cursor).ok())
+    }
+}
+
+/// Zero-copy view of [SwapType].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapTypeRef<'a> {
+    UniswapV3 {
+        first_leg_fee: Uint24,
+        path: PathRef<'a, UniswapSwapPath>,
+    },
+    TraderJoe {
+        first_pool_id: TraderJoePoolId,
+        path: PathRef<'a, TraderJoeSwapPath>,
+    },
+    JupiterV6 {
+        dex_program_id: Option<&'a [u8; 32]>,
+    },
+    OrcaWhirlpool {
+        pools: PathRef<'a, OrcaWhirlpoolPool>,
+    },
+}
+
+impl<'a> SwapTypeRef<'a> {
+    fn read(cursor: &mut Cursor<'a>) -> Result<Self, SwapMessageError> {
+        match cursor.read_u8()? {
+            SwapType::UNISWAP_V3 => Ok(Self::UniswapV3 {
+                first_leg_fee: Uint24::from_be_bytes(*cursor.take_array()?),
+                path: PathRef::read(cursor, UniswapSwapPath::ENCODED_SIZE)?,
+            }),
+            SwapType::TRADER_JOE => Ok(Self::TraderJoe {
+                first_pool_id: TraderJoePoolId {
+                    version: cursor.read_u8()?.try_into()?,
+                    bin_size: cursor.read_u16()?,
+                },
+                path: PathRef::read(cursor, TraderJoeSwapPath::ENCODED_SIZE)?,
+            }),
+            SwapType::JUPITER_V6 => Ok(Self::JupiterV6 {
+                dex_program_id: cursor.read_option_array("dex_program_id")?,
+            }),
+            SwapType::ORCA_WHIRLPOOL => Ok(Self::OrcaWhirlpool {
+                pools: PathRef::read(cursor, OrcaWhirlpoolPool::ENCODED_SIZE)?,
+            }),
+            value => Err(SwapMessageError::UnknownDiscriminant {
+                field: "swap_type",
+                value,
+            }),
+        }
+    }
+}
+
+/// Zero-copy view of the hops of a swap path. Each hop is only decoded when iterated over.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PathRef<'a, T> {
+    span: &'a [u8],
+    len: u8,
+    hop_size: usize,
+    _hop: PhantomData<T>,
+}
+
+// Derived impls would require the hop type to be Copy.
+impl<T> Clone for PathRef<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for PathRef<'_, T> {}
+
+impl<'a, T: Readable> PathRef<'a, T> {
+    fn read(cursor: &mut Cursor<'a>, hop_size: usize) -> Result<Self, SwapMessageError> {
+        let len = cursor.read_u8()?;
+        let span = cursor.take(hop_size.saturating_mul(len.into()))?;
+
+        // Hops are decoded lazily, so reject any invalid hop (e.g. an unknown Trader Joe version)
+        // now rather than skipping it when iterating.
+        for mut hop in span.chunks_exact(hop_size) {
+            T::read(&mut hop)?;
+        }
+
+        Ok(Self {
+            span,
+            len,
+            hop_size,
+            _hop: PhantomData,
+        })
+    }
+
+    /// Number of hops in the path.
+    pub fn len(&self) -> usize {
+        self.len.into()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Encoded hops, excluding the path length.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.span
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+        self.span
+            .chunks_exact(self.hop_size)
+            .filter_map(|mut hop| T::read(&mut hop).ok())
+    }
+}
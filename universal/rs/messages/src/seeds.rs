@@ -0,0 +1,11 @@
+//! Seed prefixes of the Solana Swap Layer program's derived addresses.
+
+pub const CUSTODIAN_SEED_PREFIX: &[u8] = b"custodian";
+pub const PEER_SEED_PREFIX: &[u8] = b"peer";
+pub const STAGED_INBOUND_SEED_PREFIX: &[u8] = b"staged-inbound";
+pub const STAGED_SPLIT_SEED_PREFIX: &[u8] = b"staged-split";
+pub const STAGED_CUSTODY_TOKEN_SEED_PREFIX: &[u8] = b"staged-custody";
+pub const PREPARED_ORDER_SEED_PREFIX: &[u8] = b"prepared-order";
+pub const COMPLETE_TOKEN_SEED_PREFIX: &[u8] = b"complete";
+pub const SWAP_AUTHORITY_SEED_PREFIX: &[u8] = b"swap-authority";
+pub const TRANSFER_AUTHORITY_SEED_PREFIX: &[u8] = b"transfer-authority";
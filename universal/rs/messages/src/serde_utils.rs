@@ -0,0 +1,116 @@
+//! Helpers for (de)serializing swap message types with serde. Addresses and payloads are
+//! represented as 0x-prefixed hex strings. Amounts that may not fit in a JSON number (u128) are
+//! represented as decimal strings.
+
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+fn decode_hex<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let encoded = String::deserialize(deserializer)?;
+    let encoded = encoded.strip_prefix("0x").unwrap_or(&encoded);
+    hex::decode(encoded).map_err(de::Error::custom)
+}
+
+fn encode_hex<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+}
+
+/// Fixed-size byte arrays (e.g. addresses) as hex strings.
+pub mod hex_array {
+    use super::*;
+
+    pub fn serialize<S, const N: usize>(bytes: &[u8; N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        encode_hex(bytes, serializer)
+    }
+
+    pub fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        decode_hex(deserializer)?
+            .try_into()
+            .map_err(|bytes: Vec<u8>| {
+                de::Error::invalid_length(bytes.len(), &format!("{N} bytes").as_str())
+            })
+    }
+}
+
+/// Optional fixed-size byte arrays as hex strings or null.
+pub mod hex_array_option {
+    use super::*;
+
+    pub fn serialize<S, const N: usize>(
+        bytes: &Option<[u8; N]>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match bytes {
+            Some(bytes) => encode_hex(bytes, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<Option<[u8; N]>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Wrapper<const N: usize>(#[serde(with = "hex_array")] [u8; N]);
+
+        Ok(Option::<Wrapper<N>>::deserialize(deserializer)?.map(|Wrapper(bytes)| bytes))
+    }
+}
+
+/// Length-prefixed payloads as hex strings.
+pub mod hex_payload {
+    use crate::wormhole_io::WriteableBytes;
+
+    use super::*;
+
+    pub fn serialize<S>(buf: &WriteableBytes<u16>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        encode_hex(buf, serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<WriteableBytes<u16>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        decode_hex(deserializer)?
+            .try_into()
+            .map_err(|_| de::Error::custom("payload exceeds u16::MAX bytes"))
+    }
+}
+
+/// u128 amounts as decimal strings.
+pub mod u128_string {
+    use super::*;
+
+    pub fn serialize<S>(value: &u128, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u128, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,505 @@
+//! [Arbitrary] implementations so property tests can generate swap messages with `any::<T>()`.
+//!
+//! Generated values are always encodable: [SwapType::Invalid] is never generated and swap paths
+//! are kept short.
+
+use proptest::{
+    arbitrary::{any, Arbitrary},
+    collection::vec,
+    option, prop_oneof,
+    strategy::{BoxedStrategy, Just, Strategy},
+};
+
+use crate::{
+    messages::{SwapMessage, SwapMessageV1, SwapMessageV2},
+    types::{
+        IntegratorFee, IntegratorFeeAmount, JupiterV6SwapParameters, OrcaWhirlpoolPool,
+        OrcaWhirlpoolSwapParameters, OutputSwap, OutputToken, RedeemMode, SplitOutput, SwapMode,
+        SwapType, TraderJoePoolId, TraderJoeSwapParameters, TraderJoeSwapPath, TraderJoeVersion,
+        Uint24, Uint48, UniswapSwapParameters, UniswapSwapPath,
+    },
+    wormhole_io::WriteableBytes,
+};
+
+/// Largest number of hops generated for a swap path.
+const MAX_PATH_LEN: usize = 8;
+
+/// Largest payload generated for [RedeemMode::Payload] and [RedeemMode::RelayWithPayload].
+const MAX_PAYLOAD_LEN: usize = 512;
+
+impl Arbitrary for Uint24 {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        any::<[u8; Uint24::BYTES]>()
+            .prop_map(Uint24::from_be_bytes)
+            .boxed()
+    }
+}
+
+impl Arbitrary for Uint48 {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        any::<[u8; Uint48::BYTES]>()
+            .prop_map(Uint48::from_be_bytes)
+            .boxed()
+    }
+}
+
+impl Arbitrary for UniswapSwapPath {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<[u8; 20]>(), any::<Uint24>())
+            .prop_map(|(evm_address, fee)| Self { evm_address, fee })
+            .boxed()
+    }
+}
+
+impl Arbitrary for UniswapSwapParameters {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            any::<Uint24>(),
+            vec(any::<UniswapSwapPath>(), 0..=MAX_PATH_LEN),
+        )
+            .prop_map(|(first_leg_fee, path)| Self {
+                first_leg_fee,
+                path,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for OrcaWhirlpoolSwapParameters {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        // Orca Whirlpools swap through one or two pools.
+        vec((any::<[u8; 32]>(), any::<bool>()), 1..=2)
+            .prop_map(|pools| Self {
+                pools: pools
+                    .into_iter()
+                    .map(|(whirlpool, a_to_b)| OrcaWhirlpoolPool { whirlpool, a_to_b })
+                    .collect(),
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for TraderJoeVersion {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            Just(Self::V1),
+            Just(Self::V2),
+            Just(Self::V2_1),
+            Just(Self::V2_2),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for TraderJoePoolId {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<TraderJoeVersion>(), any::<u16>())
+            .prop_map(|(version, bin_size)| Self { version, bin_size })
+            .boxed()
+    }
+}
+
+impl Arbitrary for TraderJoeSwapPath {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<[u8; 20]>(), any::<TraderJoePoolId>())
+            .prop_map(|(evm_address, pool_id)| Self {
+                evm_address,
+                pool_id,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for TraderJoeSwapParameters {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            any::<TraderJoePoolId>(),
+            vec(any::<TraderJoeSwapPath>(), 0..=MAX_PATH_LEN),
+        )
+            .prop_map(|(first_pool_id, path)| Self {
+                first_pool_id,
+                path,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for JupiterV6SwapParameters {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        option::of(any::<[u8; 32]>())
+            .prop_map(|dex_program_id| Self { dex_program_id })
+            .boxed()
+    }
+}
+
+impl Arbitrary for SwapType {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            any::<UniswapSwapParameters>().prop_map(Self::UniswapV3),
+            any::<TraderJoeSwapParameters>().prop_map(Self::TraderJoe),
+            any::<OrcaWhirlpoolSwapParameters>().prop_map(Self::OrcaWhirlpool),
+            any::<JupiterV6SwapParameters>().prop_map(Self::JupiterV6),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for OutputSwap {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            any::<u32>(),
+            any::<u128>(),
+            any::<SwapType>(),
+            any::<SwapMode>(),
+        )
+            .prop_map(|(deadline, limit_amount, swap_type, mode)| Self {
+                deadline,
+                limit_amount,
+                swap_type,
+                mode,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for SwapMode {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![Just(Self::ExactIn), Just(Self::ExactOut)].boxed()
+    }
+}
+
+impl Arbitrary for OutputToken {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            Just(Self::Usdc),
+            any::<OutputSwap>().prop_map(Self::Gas),
+            (any::<[u8; 32]>(), any::<OutputSwap>())
+                .prop_map(|(address, swap)| Self::Other { address, swap }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for RedeemMode {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            Just(Self::Direct),
+            (any::<[u8; 32]>(), vec(any::<u8>(), 0..=MAX_PAYLOAD_LEN)).prop_map(|(sender, buf)| {
+                Self::Payload {
+                    sender,
+                    buf: WriteableBytes::new(buf),
+                }
+            }),
+            (any::<u32>(), any::<Uint48>()).prop_map(|(gas_dropoff, relaying_fee)| {
+                Self::Relay {
+                    gas_dropoff,
+                    relaying_fee,
+                }
+            }),
+            (
+                any::<u32>(),
+                any::<Uint48>(),
+                any::<[u8; 32]>(),
+                vec(any::<u8>(), 0..=MAX_PAYLOAD_LEN)
+            )
+                .prop_map(|(gas_dropoff, relaying_fee, sender, buf)| {
+                    Self::RelayWithPayload {
+                        gas_dropoff,
+                        relaying_fee,
+                        sender,
+                        buf: WriteableBytes::new(buf),
+                    }
+                }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for IntegratorFeeAmount {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            any::<u16>().prop_map(|bps| Self::Bps { bps }),
+            any::<Uint48>().prop_map(|amount| Self::Absolute { amount }),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for IntegratorFee {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<[u8; 32]>(), any::<IntegratorFeeAmount>())
+            .prop_map(|(recipient, amount)| Self { recipient, amount })
+            .boxed()
+    }
+}
+
+impl Arbitrary for SplitOutput {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<[u8; 32]>(), any::<u16>(), any::<OutputToken>())
+            .prop_map(|(recipient, bps, output_token)| Self {
+                recipient,
+                bps,
+                output_token,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for SwapMessageV1 {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            any::<[u8; 32]>(),
+            any::<RedeemMode>().prop_filter("requires swap message V2", |redeem_mode| {
+                redeem_mode.is_supported_in_v1()
+            }),
+            any::<OutputToken>().prop_filter("requires swap message V2", |output_token| {
+                output_token.is_supported_in_v1()
+            }),
+        )
+            .prop_map(|(recipient, redeem_mode, output_token)| Self {
+                recipient,
+                redeem_mode,
+                output_token,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for SwapMessageV2 {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            any::<SwapMessageV1>(),
+            any::<RedeemMode>(),
+            any::<OutputToken>(),
+            any::<Option<[u8; 32]>>(),
+            any::<Option<IntegratorFee>>(),
+            vec(any::<SplitOutput>(), 0..=SplitOutput::MAX_OUTPUTS),
+        )
+            .prop_map(
+                |(
+                    msg,
+                    redeem_mode,
+                    output_token,
+                    fallback_recipient,
+                    integrator_fee,
+                    split_outputs,
+                )| Self {
+                    redeem_mode,
+                    output_token,
+                    fallback_recipient,
+                    integrator_fee,
+                    split_outputs,
+                    ..msg.into()
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for SwapMessage {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            any::<SwapMessageV1>().prop_map(Self::V1),
+            any::<SwapMessageV2>().prop_map(Self::V2),
+        ]
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fmt;
+
+    use proptest::{prelude::*, sample::Index};
+
+    use crate::{
+        error::Codec,
+        raw::SwapMessageRef,
+        wormhole_io::{Readable, TypePrefixedPayload, Writeable},
+    };
+
+    use super::*;
+
+    /// Write the value, check that its written size is the encoded length and read it back.
+    fn check_roundtrip<T>(value: &T, written_size: usize) -> Result<(), TestCaseError>
+    where
+        T: Readable + Writeable + PartialEq + fmt::Debug,
+    {
+        let mut encoded = Vec::with_capacity(written_size);
+        value
+            .write(&mut encoded)
+            .map_err(|err| TestCaseError::fail(err.to_string()))?;
+        prop_assert_eq!(encoded.len(), written_size);
+
+        let mut reader = encoded.as_slice();
+        let decoded = T::read(&mut reader).map_err(|err| TestCaseError::fail(err.to_string()))?;
+        prop_assert!(reader.is_empty());
+        prop_assert_eq!(&decoded, value);
+        Ok(())
+    }
+
+    /// Whatever the input, reading either fails or yields a message that encodes to the input.
+    /// The zero-copy view must accept the same inputs (errors may report different offsets).
+    fn check_read(buf: &[u8]) -> Result<(), TestCaseError> {
+        let decoded = SwapMessage::try_read_slice(buf).ok();
+        prop_assert_eq!(
+            SwapMessageRef::parse(buf)
+                .and_then(|view| view.decode())
+                .ok(),
+            decoded.clone()
+        );
+
+        if let Some(swap_message) = decoded {
+            prop_assert_eq!(swap_message.try_encode(), Ok(buf.to_vec()));
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_swap_path_roundtrip(
+            uniswap in any::<UniswapSwapPath>(),
+            trader_joe in any::<TraderJoeSwapPath>(),
+        ) {
+            check_roundtrip(&uniswap, UniswapSwapPath::ENCODED_SIZE)?;
+            check_roundtrip(&trader_joe, TraderJoeSwapPath::ENCODED_SIZE)?;
+        }
+
+        #[test]
+        fn test_uniswap_v3_packed_path_roundtrip(
+            parameters in any::<UniswapSwapParameters>(),
+            input in any::<[u8; 20]>(),
+            output in any::<[u8; 20]>(),
+        ) {
+            let expected = Ok((parameters.clone(), input, output));
+            prop_assert_eq!(
+                UniswapSwapParameters::from_uniswap_v3_path(
+                    &parameters.to_uniswap_v3_path(input, output)
+                ),
+                expected.clone()
+            );
+            prop_assert_eq!(
+                UniswapSwapParameters::from_uniswap_v3_exact_output_path(
+                    &parameters.to_uniswap_v3_exact_output_path(input, output)
+                ),
+                expected
+            );
+        }
+
+        #[test]
+        fn test_swap_type_roundtrip(swap_type in any::<SwapType>()) {
+            check_roundtrip(&swap_type, swap_type.written_size())?;
+        }
+
+        #[test]
+        fn test_output_token_roundtrip(output_token in any::<OutputToken>()) {
+            if output_token.is_supported_in_v1() {
+                check_roundtrip(&output_token, output_token.written_size())?;
+            }
+
+            let mut encoded = Vec::with_capacity(output_token.written_size_v2());
+            output_token.write_v2(&mut encoded).unwrap();
+            prop_assert_eq!(encoded.len(), output_token.written_size_v2());
+            prop_assert_eq!(OutputToken::read_v2(&mut encoded.as_slice()).unwrap(), output_token);
+        }
+
+        #[test]
+        fn test_redeem_mode_roundtrip(redeem_mode in any::<RedeemMode>()) {
+            check_roundtrip(&redeem_mode, redeem_mode.written_size())?;
+        }
+
+        #[test]
+        fn test_swap_message_roundtrip(swap_message in any::<SwapMessage>()) {
+            check_roundtrip(&swap_message, swap_message.written_size())?;
+
+            let encoded = swap_message.try_encode().unwrap();
+            prop_assert_eq!(SwapMessage::try_read_slice(&encoded), Ok(swap_message.clone()));
+            prop_assert_eq!(SwapMessageRef::parse(&encoded).unwrap().decode(), Ok(swap_message));
+        }
+
+        #[test]
+        fn test_read_arbitrary_bytes(buf in vec(any::<u8>(), 0..=256)) {
+            check_read(&buf)?;
+        }
+
+        #[test]
+        fn test_read_corrupted_message(
+            swap_message in any::<SwapMessage>(),
+            index in any::<Index>(),
+            byte in any::<u8>(),
+            len in any::<Index>(),
+        ) {
+            let mut encoded = swap_message.try_encode().unwrap();
+
+            let index = index.index(encoded.len());
+            encoded[index] = byte;
+            check_read(&encoded)?;
+
+            encoded.truncate(len.index(encoded.len()));
+            check_read(&encoded)?;
+        }
+    }
+}
@@ -0,0 +1,402 @@
+use core::{fmt, str::FromStr};
+
+use alloc::string::String;
+use sha3::{Digest, Keccak256};
+
+use crate::{
+    error::SwapMessageError,
+    io,
+    wormhole_io::{Readable, Writeable},
+};
+
+use super::is_evm_address;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// 32-byte address that Wormhole uses to identify an account on any chain. An EVM address is
+/// left-padded with zeros and a Solana address is the public key itself.
+///
+/// Addresses are displayed as 0x-prefixed hex with a mixed-case checksum (EIP-55). EVM addresses
+/// are displayed without their padding (20 bytes). Any other address is displayed with all 32
+/// bytes, checksummed the same way. Parsing accepts either hex form (mixed-case input must match
+/// the checksum) or a base58 encoded Solana address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct UniversalAddress([u8; 32]);
+
+impl UniversalAddress {
+    pub const ZERO: UniversalAddress = Self([0; 32]);
+
+    pub const fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Left-pad a 20-byte EVM address.
+    pub fn from_evm(address: [u8; 20]) -> Self {
+        let mut bytes = [0; 32];
+        bytes[12..].copy_from_slice(&address);
+        Self(bytes)
+    }
+
+    /// The 20-byte EVM address. Fails unless the first 12 bytes are zero, since truncating them
+    /// would deliver to a different account.
+    pub fn to_evm(&self) -> Result<[u8; 20], SwapMessageError> {
+        if !self.is_evm() {
+            return Err(SwapMessageError::InvalidEvmAddress { field: "address" });
+        }
+
+        let mut address = [0; 20];
+        address.copy_from_slice(&self.0[12..]);
+        Ok(address)
+    }
+
+    /// Whether this is a 20-byte EVM address left-padded with zeros.
+    pub fn is_evm(&self) -> bool {
+        is_evm_address(&self.0)
+    }
+
+    #[cfg(feature = "solana")]
+    pub fn from_pubkey(pubkey: &solana_program::pubkey::Pubkey) -> Self {
+        Self(pubkey.to_bytes())
+    }
+
+    /// Any 32 bytes are a valid Solana public key, so this conversion cannot fail.
+    #[cfg(feature = "solana")]
+    pub fn to_pubkey(&self) -> solana_program::pubkey::Pubkey {
+        solana_program::pubkey::Pubkey::new_from_array(self.0)
+    }
+
+    /// Base58 encoding, which is how Solana displays addresses.
+    pub fn to_base58(&self) -> String {
+        bs58::encode(self.0).into_string()
+    }
+
+    pub const fn to_bytes(self) -> [u8; 32] {
+        self.0
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    fn parse_hex(digits: &str) -> Result<Self, SwapMessageError> {
+        let digits = digits.as_bytes();
+        let len = match digits.len() {
+            40 => 20,
+            64 => 32,
+            _ => return Err(SwapMessageError::InvalidAddress),
+        };
+
+        let mut bytes = [0; 32];
+        let out = bytes
+            .get_mut(32usize.saturating_sub(len)..)
+            .unwrap_or_default();
+        for (byte, pair) in out.iter_mut().zip(digits.chunks_exact(2)) {
+            let (high, low) = match pair {
+                [high, low] => (from_hex_digit(*high)?, from_hex_digit(*low)?),
+                _ => return Err(SwapMessageError::InvalidAddress),
+            };
+            *byte = high << 4 | low;
+        }
+
+        // Like EIP-55, the checksum is only enforced for mixed-case input.
+        let has_lower = digits.iter().any(u8::is_ascii_lowercase);
+        let has_upper = digits.iter().any(u8::is_ascii_uppercase);
+        if has_lower && has_upper {
+            let checksummed = checksummed_hex(out);
+            if checksummed.get(..digits.len()) != Some(digits) {
+                return Err(SwapMessageError::InvalidAddressChecksum);
+            }
+        }
+
+        Ok(Self(bytes))
+    }
+
+    fn parse_base58(encoded: &str) -> Result<Self, SwapMessageError> {
+        let mut bytes = [0; 32];
+        match bs58::decode(encoded).onto(&mut bytes) {
+            Ok(32) => Ok(Self(bytes)),
+            _ => Err(SwapMessageError::InvalidAddress),
+        }
+    }
+}
+
+fn from_hex_digit(digit: u8) -> Result<u8, SwapMessageError> {
+    match digit {
+        b'0'..=b'9' => Ok(digit.saturating_sub(b'0')),
+        b'a'..=b'f' => Ok(digit.saturating_sub(b'a').saturating_add(10)),
+        b'A'..=b'F' => Ok(digit.saturating_sub(b'A').saturating_add(10)),
+        _ => Err(SwapMessageError::InvalidAddress),
+    }
+}
+
+/// Hex digits of up to 32 bytes, uppercased where the corresponding nibble of the Keccak-256 hash
+/// of the lowercase digits is at least 8 (EIP-55). Only the first `2 * bytes.len()` digits are
+/// used.
+fn checksummed_hex(bytes: &[u8]) -> [u8; 64] {
+    let mut digits = [0; 64];
+    for (pair, byte) in digits.chunks_exact_mut(2).zip(bytes) {
+        pair[0] = HEX_DIGITS[usize::from(byte >> 4)];
+        pair[1] = HEX_DIGITS[usize::from(byte & 0xf)];
+    }
+
+    let len = bytes.len().saturating_mul(2);
+    let hash = Keccak256::digest(digits.get(..len).unwrap_or_default());
+    for (pair, hash_byte) in digits.chunks_exact_mut(2).zip(hash) {
+        if hash_byte >= 0x80 {
+            pair[0].make_ascii_uppercase();
+        }
+        if hash_byte & 0xf >= 0x8 {
+            pair[1].make_ascii_uppercase();
+        }
+    }
+
+    digits
+}
+
+impl From<[u8; 32]> for UniversalAddress {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<UniversalAddress> for [u8; 32] {
+    fn from(address: UniversalAddress) -> Self {
+        address.0
+    }
+}
+
+#[cfg(feature = "solana")]
+impl From<solana_program::pubkey::Pubkey> for UniversalAddress {
+    fn from(pubkey: solana_program::pubkey::Pubkey) -> Self {
+        Self::from_pubkey(&pubkey)
+    }
+}
+
+#[cfg(feature = "solana")]
+impl From<UniversalAddress> for solana_program::pubkey::Pubkey {
+    fn from(address: UniversalAddress) -> Self {
+        address.to_pubkey()
+    }
+}
+
+impl AsRef<[u8]> for UniversalAddress {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for UniversalAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = match self.is_evm() {
+            true => self.0.get(12..).unwrap_or_default(),
+            false => &self.0,
+        };
+        let digits = checksummed_hex(bytes);
+        let digits = digits
+            .get(..bytes.len().saturating_mul(2))
+            .unwrap_or_default();
+
+        // Hex digits are always ASCII.
+        f.write_str("0x")?;
+        f.write_str(core::str::from_utf8(digits).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl FromStr for UniversalAddress {
+    type Err = SwapMessageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("0x") {
+            Some(digits) => Self::parse_hex(digits),
+            None => Self::parse_base58(s),
+        }
+    }
+}
+
+impl Readable for UniversalAddress {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        R: io::Read,
+    {
+        Ok(Self(Readable::read(reader)?))
+    }
+}
+
+impl Writeable for UniversalAddress {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.0.write(writer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for UniversalAddress {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for UniversalAddress {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let encoded = <String as serde::Deserialize>::deserialize(deserializer)?;
+        encoded.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::ToString;
+
+    use hex_literal::hex;
+
+    use super::*;
+
+    #[test]
+    fn test_evm_address() {
+        let evm_address = hex!("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed");
+        let address = UniversalAddress::from_evm(evm_address);
+        assert_eq!(
+            address.to_bytes(),
+            hex!("0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed")
+        );
+        assert!(address.is_evm());
+        assert_eq!(address.to_evm(), Ok(evm_address));
+
+        let address = UniversalAddress::new([1; 32]);
+        assert!(!address.is_evm());
+        assert_eq!(
+            address.to_evm(),
+            Err(SwapMessageError::InvalidEvmAddress { field: "address" })
+        );
+    }
+
+    #[test]
+    fn test_display_checksum() {
+        // Examples from EIP-55.
+        for expected in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let address = expected.to_lowercase().parse::<UniversalAddress>().unwrap();
+            assert_eq!(address.to_string(), expected);
+            assert_eq!(expected.parse(), Ok(address));
+        }
+
+        // Addresses from other chains are displayed with all 32 bytes.
+        let address = UniversalAddress::new(hex!(
+            "6b2e3c3e3e4a8c1a4f0b6f0d23e5d8a7f4b2c1d0e9f8a7b6c5d4e3f2a1b0c9d8"
+        ));
+        let displayed = address.to_string();
+        assert_eq!(displayed.len(), 66);
+        assert_eq!(
+            displayed.to_lowercase(),
+            "0x6b2e3c3e3e4a8c1a4f0b6f0d23e5d8a7f4b2c1d0e9f8a7b6c5d4e3f2a1b0c9d8"
+        );
+        assert_eq!(displayed.parse(), Ok(address));
+    }
+
+    #[test]
+    fn test_from_str() {
+        let address = UniversalAddress::from_evm(hex!("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"));
+
+        // Single-case hex is not checksummed.
+        assert_eq!(
+            "0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED".parse(),
+            Ok(address)
+        );
+        assert_eq!(
+            "0x0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse(),
+            Ok(address)
+        );
+        assert_eq!(
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD".parse::<UniversalAddress>(),
+            Err(SwapMessageError::InvalidAddressChecksum)
+        );
+
+        for invalid in [
+            "",
+            "0x",
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1bea",
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaedff",
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaeg",
+            "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+            "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV40",
+            "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTa",
+        ] {
+            assert_eq!(
+                invalid.parse::<UniversalAddress>(),
+                Err(SwapMessageError::InvalidAddress),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_base58() {
+        let address = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"
+            .parse::<UniversalAddress>()
+            .unwrap();
+        assert_eq!(
+            address.to_bytes(),
+            hex!("0479d55bf231c06eee74c56ece681507fdb1b2dea3f48e5102b1cda256bc138f")
+        );
+        assert_eq!(
+            address.to_base58(),
+            "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"
+        );
+
+        // Leading zeros are encoded as ones.
+        assert_eq!(
+            "11111111111111111111111111111111".parse(),
+            Ok(UniversalAddress::ZERO)
+        );
+        assert_eq!(
+            UniversalAddress::ZERO.to_base58(),
+            "11111111111111111111111111111111"
+        );
+    }
+
+    #[cfg(feature = "solana")]
+    #[test]
+    fn test_pubkey() {
+        use solana_program::pubkey::Pubkey;
+
+        let pubkey = Pubkey::new_from_array([7; 32]);
+        let address = UniversalAddress::from_pubkey(&pubkey);
+        assert_eq!(address.to_bytes(), [7; 32]);
+        assert_eq!(address.to_pubkey(), pubkey);
+        assert_eq!(Pubkey::from(UniversalAddress::from(pubkey)), pubkey);
+        assert_eq!(address.to_base58(), pubkey.to_string());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let address = UniversalAddress::from_evm(hex!("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"));
+        let encoded = serde_json::to_string(&address).unwrap();
+        assert_eq!(encoded, r#""0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed""#);
+        assert_eq!(
+            serde_json::from_str::<UniversalAddress>(&encoded).unwrap(),
+            address
+        );
+        assert_eq!(
+            serde_json::from_str::<UniversalAddress>(
+                r#""JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4""#
+            )
+            .unwrap()
+            .to_base58(),
+            "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"
+        );
+    }
+}
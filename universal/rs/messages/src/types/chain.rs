@@ -0,0 +1,188 @@
+use crate::error::SwapMessageError;
+
+/// Wormhole chain ID of Solana. The Swap Layer is deployed to Solana and EVM networks, so every
+/// other target chain is treated as an EVM network.
+pub const SOLANA_CHAIN: u16 = 1;
+
+/// Whether the universal address is a 20-byte EVM address left-padded with zeros.
+pub fn is_evm_address(address: &[u8; 32]) -> bool {
+    address.starts_with(&[0; 12])
+}
+
+/// Check that the recipient can receive tokens on the target chain (Wormhole chain ID).
+pub fn validate_recipient_for_target(
+    recipient: &[u8; 32],
+    chain: u16,
+) -> Result<(), SwapMessageError> {
+    if chain != SOLANA_CHAIN && !is_evm_address(recipient) {
+        return Err(SwapMessageError::InvalidEvmAddress { field: "recipient" });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use crate::{
+        error::SwapMessageError,
+        messages::SwapMessageV1,
+        types::{
+            JupiterV6SwapParameters, OrcaWhirlpoolPool, OrcaWhirlpoolSwapParameters, OutputSwap,
+            OutputToken, RedeemMode, SwapMode, SwapType, Uint24, UniswapSwapParameters,
+            UniswapSwapPath,
+        },
+    };
+
+    use super::*;
+
+    const EVM_CHAIN: u16 = 2;
+
+    fn gas(limit_amount: u128, swap_type: SwapType) -> OutputToken {
+        OutputToken::Gas(OutputSwap {
+            deadline: 0,
+            limit_amount,
+            swap_type,
+            mode: SwapMode::ExactIn,
+        })
+    }
+
+    fn uniswap_v3(path_len: usize) -> SwapType {
+        SwapType::UniswapV3(UniswapSwapParameters {
+            first_leg_fee: Uint24::from(500),
+            path: vec![
+                UniswapSwapPath {
+                    evm_address: hex!("5991a2df15a8f6a256d3ec51e99254cd3fb576a9"),
+                    fee: Uint24::from(500),
+                };
+                path_len
+            ],
+        })
+    }
+
+    fn jupiter_v6() -> SwapType {
+        SwapType::JupiterV6(JupiterV6SwapParameters {
+            dex_program_id: None,
+        })
+    }
+
+    fn orca_whirlpool(pools_len: usize) -> SwapType {
+        SwapType::OrcaWhirlpool(OrcaWhirlpoolSwapParameters {
+            pools: vec![
+                OrcaWhirlpoolPool {
+                    whirlpool: [1; 32],
+                    a_to_b: true,
+                };
+                pools_len
+            ],
+        })
+    }
+
+    #[test]
+    fn test_output_token_validate_for_target() {
+        assert_eq!(OutputToken::Usdc.validate_for_target(EVM_CHAIN), Ok(()));
+        assert_eq!(OutputToken::Usdc.validate_for_target(SOLANA_CHAIN), Ok(()));
+
+        // A path without any hops is a swap through a single pool.
+        assert_eq!(gas(0, uniswap_v3(0)).validate_for_target(EVM_CHAIN), Ok(()));
+        assert_eq!(
+            gas(0, uniswap_v3(usize::from(u8::MAX).saturating_add(1)))
+                .validate_for_target(EVM_CHAIN),
+            Err(SwapMessageError::PathTooLong {
+                field: "uniswap_v3.path",
+                len: 256
+            })
+        );
+        assert_eq!(
+            gas(0, uniswap_v3(1)).validate_for_target(SOLANA_CHAIN),
+            Err(SwapMessageError::SwapTypeNotSupported {
+                chain: SOLANA_CHAIN
+            })
+        );
+
+        assert_eq!(
+            gas(u64::MAX.into(), jupiter_v6()).validate_for_target(SOLANA_CHAIN),
+            Ok(())
+        );
+        assert_eq!(
+            gas(u128::from(u64::MAX).saturating_add(1), jupiter_v6())
+                .validate_for_target(SOLANA_CHAIN),
+            Err(SwapMessageError::LimitAmountTooLarge {
+                chain: SOLANA_CHAIN,
+                limit_amount: u128::from(u64::MAX).saturating_add(1)
+            })
+        );
+        assert_eq!(
+            gas(0, jupiter_v6()).validate_for_target(EVM_CHAIN),
+            Err(SwapMessageError::SwapTypeNotSupported { chain: EVM_CHAIN })
+        );
+
+        // Orca Whirlpools swap through one or two pools.
+        assert_eq!(
+            gas(0, orca_whirlpool(2)).validate_for_target(SOLANA_CHAIN),
+            Ok(())
+        );
+        assert_eq!(
+            gas(0, orca_whirlpool(3)).validate_for_target(SOLANA_CHAIN),
+            Err(SwapMessageError::UnsupportedPathLength {
+                field: "orca_whirlpool.pools",
+                len: 3
+            })
+        );
+        assert_eq!(
+            gas(0, orca_whirlpool(1)).validate_for_target(EVM_CHAIN),
+            Err(SwapMessageError::SwapTypeNotSupported { chain: EVM_CHAIN })
+        );
+
+        assert_eq!(
+            gas(0, SwapType::Invalid).validate_for_target(EVM_CHAIN),
+            Err(SwapMessageError::InvalidSwapType)
+        );
+
+        let other = |address| OutputToken::Other {
+            address,
+            swap: OutputSwap {
+                deadline: 0,
+                limit_amount: 0,
+                swap_type: uniswap_v3(1),
+                mode: SwapMode::ExactIn,
+            },
+        };
+        assert_eq!(
+            other(hex!(
+                "0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"
+            ))
+            .validate_for_target(EVM_CHAIN),
+            Ok(())
+        );
+        assert_eq!(
+            other([1; 32]).validate_for_target(EVM_CHAIN),
+            Err(SwapMessageError::InvalidEvmAddress {
+                field: "output_token.address"
+            })
+        );
+    }
+
+    #[test]
+    fn test_swap_message_validate_for_target() {
+        let swap_message = SwapMessageV1 {
+            recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+            redeem_mode: RedeemMode::Direct,
+            output_token: gas(0, uniswap_v3(1)),
+        };
+        assert_eq!(swap_message.validate_for_target(EVM_CHAIN), Ok(()));
+
+        // Any 32-byte address can be a recipient on Solana.
+        let swap_message = SwapMessageV1 {
+            recipient: [1; 32],
+            output_token: gas(0, jupiter_v6()),
+            ..swap_message
+        };
+        assert_eq!(swap_message.validate_for_target(SOLANA_CHAIN), Ok(()));
+        assert_eq!(
+            swap_message.validate_for_target(EVM_CHAIN),
+            Err(SwapMessageError::InvalidEvmAddress { field: "recipient" })
+        );
+    }
+}
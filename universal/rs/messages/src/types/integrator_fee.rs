@@ -0,0 +1,254 @@
+use crate::{
+    error::SwapMessageError,
+    io,
+    types::{validate_recipient_for_target, Uint48},
+    wormhole_io::{Readable, Writeable},
+};
+
+#[cfg(feature = "anchor")]
+use anchor_lang::prelude::{borsh, AnchorDeserialize, AnchorSerialize};
+
+/// Fee paid to an integrator (e.g. a front-end or wallet) out of the USDC delivered by the fill,
+/// before the relaying fee is deducted and before any swap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct IntegratorFee {
+    /// Owner of the USDC token account receiving the fee on the target chain.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_array"))]
+    pub recipient: [u8; 32],
+    pub amount: IntegratorFeeAmount,
+}
+
+impl IntegratorFee {
+    pub fn written_size(&self) -> usize {
+        self.amount.written_size().saturating_add(32) // recipient
+    }
+
+    /// Check that the fee can be paid on the target chain (Wormhole chain ID).
+    pub fn validate_for_target(&self, chain: u16) -> Result<(), SwapMessageError> {
+        validate_recipient_for_target(&self.recipient, chain).map_err(|_| {
+            SwapMessageError::InvalidEvmAddress {
+                field: "integrator_fee.recipient",
+            }
+        })?;
+
+        match self.amount {
+            IntegratorFeeAmount::Bps { bps } if bps > IntegratorFeeAmount::MAX_BPS => {
+                Err(SwapMessageError::ValueOutOfRange {
+                    field: "integrator_fee.bps",
+                    value: bps.into(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Readable for IntegratorFee {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        Ok(Self {
+            recipient: Readable::read(reader)?,
+            amount: Readable::read(reader)?,
+        })
+    }
+}
+
+impl Writeable for IntegratorFee {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.recipient.write(writer)?;
+        self.amount.write(writer)
+    }
+}
+
+/// How the integrator fee is computed from the USDC amount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+pub enum IntegratorFeeAmount {
+    /// Basis points (hundredths of a percent) of the USDC amount.
+    Bps { bps: u16 },
+    /// Atomic USDC units.
+    Absolute { amount: Uint48 },
+}
+
+impl IntegratorFeeAmount {
+    /// Basis points equal to 100%.
+    pub const MAX_BPS: u16 = 10_000;
+
+    const BPS: u8 = 0;
+    const ABSOLUTE: u8 = 1;
+
+    pub fn written_size(&self) -> usize {
+        match self {
+            Self::Bps { .. } => 3,
+            Self::Absolute { .. } => Uint48::BYTES.saturating_add(1),
+        }
+    }
+
+    /// Fee owed on this amount of USDC, which never exceeds the amount itself.
+    pub fn compute(&self, usdc_amount: u64) -> u64 {
+        let fee = match self {
+            Self::Bps { bps } => apply_bps(usdc_amount, *bps),
+            Self::Absolute { amount } => (*amount).into(),
+        };
+
+        fee.min(usdc_amount)
+    }
+}
+
+/// Basis points of the amount, rounded down.
+pub(crate) fn apply_bps(amount: u64, bps: u16) -> u64 {
+    u128::from(amount)
+        .saturating_mul(bps.into())
+        .checked_div(IntegratorFeeAmount::MAX_BPS.into())
+        .and_then(|value| value.try_into().ok())
+        .unwrap_or(u64::MAX)
+}
+
+impl Readable for IntegratorFeeAmount {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        match u8::read(reader)? {
+            Self::BPS => Ok(Self::Bps {
+                bps: Readable::read(reader)?,
+            }),
+            Self::ABSOLUTE => Ok(Self::Absolute {
+                amount: Readable::read(reader)?,
+            }),
+            value => Err(SwapMessageError::UnknownDiscriminant {
+                field: "integrator_fee.amount",
+                value,
+            }
+            .into()),
+        }
+    }
+}
+
+impl Writeable for IntegratorFeeAmount {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        match self {
+            Self::Bps { bps } => {
+                Self::BPS.write(writer)?;
+                bps.write(writer)
+            }
+            Self::Absolute { amount } => {
+                Self::ABSOLUTE.write(writer)?;
+                amount.write(writer)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use super::*;
+
+    #[test]
+    fn test_integrator_fee() {
+        let fee = IntegratorFee {
+            recipient: hex!("0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d"),
+            amount: IntegratorFeeAmount::Bps { bps: 25 },
+        };
+
+        let encoded = hex!(
+            "0000000000000000000000006ca6d1e2d5347bfab1d91e883f1915560e09129d" // recipient
+            "00" // bps
+            "0019" // 25 bps
+        );
+
+        let mut written = Vec::with_capacity(fee.written_size());
+        fee.write(&mut written).unwrap();
+        assert_eq!(written, encoded);
+        assert_eq!(fee.written_size(), encoded.len());
+        assert_eq!(IntegratorFee::read(&mut &encoded[..]).unwrap(), fee);
+
+        let fee = IntegratorFee {
+            amount: IntegratorFeeAmount::Absolute {
+                amount: Uint48::try_from(1000000u32).unwrap(),
+            },
+            ..fee
+        };
+        let mut written = Vec::with_capacity(fee.written_size());
+        fee.write(&mut written).unwrap();
+        assert_eq!(written[32..], hex!("01" "0000000f4240"));
+        assert_eq!(fee.written_size(), written.len());
+        assert_eq!(IntegratorFee::read(&mut &written[..]).unwrap(), fee);
+
+        let mut invalid = encoded;
+        invalid[32] = 2;
+        assert_eq!(
+            SwapMessageError::from(IntegratorFee::read(&mut &invalid[..]).unwrap_err()),
+            SwapMessageError::UnknownDiscriminant {
+                field: "integrator_fee.amount",
+                value: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_integrator_fee_compute() {
+        let bps = IntegratorFeeAmount::Bps { bps: 25 };
+        assert_eq!(bps.compute(1_000_000), 2_500);
+        assert_eq!(bps.compute(u64::MAX), 46_116_860_184_273_879);
+        assert_eq!(bps.compute(0), 0);
+
+        // The fee never exceeds the USDC amount.
+        assert_eq!(
+            IntegratorFeeAmount::Bps { bps: u16::MAX }.compute(1_000),
+            1_000
+        );
+
+        let absolute = IntegratorFeeAmount::Absolute {
+            amount: Uint48::try_from(1_000_000u32).unwrap(),
+        };
+        assert_eq!(absolute.compute(5_000_000), 1_000_000);
+        assert_eq!(absolute.compute(500_000), 500_000);
+    }
+
+    #[test]
+    fn test_integrator_fee_validate_for_target() {
+        let fee = IntegratorFee {
+            recipient: [1; 32],
+            amount: IntegratorFeeAmount::Bps {
+                bps: IntegratorFeeAmount::MAX_BPS,
+            },
+        };
+        assert_eq!(fee.validate_for_target(1), Ok(()));
+        assert_eq!(
+            fee.validate_for_target(2),
+            Err(SwapMessageError::InvalidEvmAddress {
+                field: "integrator_fee.recipient"
+            })
+        );
+
+        let fee = IntegratorFee {
+            amount: IntegratorFeeAmount::Bps { bps: 10_001 },
+            ..fee
+        };
+        assert_eq!(
+            fee.validate_for_target(1),
+            Err(SwapMessageError::ValueOutOfRange {
+                field: "integrator_fee.bps",
+                value: 10_001
+            })
+        );
+    }
+}
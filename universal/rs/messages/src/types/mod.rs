@@ -1,9 +1,21 @@
+mod address;
+pub use address::*;
+
+mod chain;
+pub use chain::*;
+
+mod integrator_fee;
+pub use integrator_fee::*;
+
 mod output_token;
 pub use output_token::*;
 
 mod redeem_mode;
 pub use redeem_mode::*;
 
+mod split_output;
+pub use split_output::*;
+
 mod swap_type;
 pub use swap_type::*;
 
@@ -1,14 +1,26 @@
-use std::io;
+use crate::{
+    error::SwapMessageError,
+    io,
+    wormhole_io::{Readable, Writeable},
+};
 
-use crate::wormhole_io::{Readable, Writeable};
+use super::{is_evm_address, SwapType, SOLANA_CHAIN};
 
-use super::SwapType;
+#[cfg(feature = "anchor")]
+use anchor_lang::prelude::{borsh, AnchorDeserialize, AnchorSerialize};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
 pub enum OutputToken {
     Usdc,
     Gas(OutputSwap),
-    Other { address: [u8; 32], swap: OutputSwap },
+    Other {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_array"))]
+        address: [u8; 32],
+        swap: OutputSwap,
+    },
 }
 
 impl OutputToken {
@@ -26,31 +38,87 @@ impl OutputToken {
             ),
         }
     }
+
+    /// Size of the output token when encoded in a
+    /// [SwapMessageV2](crate::messages::SwapMessageV2), where the swap is followed by its mode.
+    pub fn written_size_v2(&self) -> usize {
+        match self.swap() {
+            Some(_) => self.written_size().saturating_add(1), // mode
+            None => self.written_size(),
+        }
+    }
+
+    pub fn swap(&self) -> Option<&OutputSwap> {
+        match self {
+            Self::Usdc => None,
+            Self::Gas(swap) | Self::Other { address: _, swap } => Some(swap),
+        }
+    }
+
+    /// Whether the output token can be encoded in a
+    /// [SwapMessageV1](crate::messages::SwapMessageV1). Exact out swaps were added by the second
+    /// version of the swap message.
+    pub fn is_supported_in_v1(&self) -> bool {
+        !self.swap().is_some_and(OutputSwap::is_exact_out)
+    }
+
+    /// Check that the output token can be delivered on the target chain (Wormhole chain ID). Any
+    /// token other than gas must have an EVM address when sent to an EVM network.
+    pub fn validate_for_target(&self, chain: u16) -> Result<(), SwapMessageError> {
+        match self {
+            Self::Usdc => Ok(()),
+            Self::Gas(swap) => swap.validate_for_target(chain),
+            Self::Other { address, swap } => {
+                if chain != SOLANA_CHAIN && !is_evm_address(address) {
+                    return Err(SwapMessageError::InvalidEvmAddress {
+                        field: "output_token.address",
+                    });
+                }
+
+                swap.validate_for_target(chain)
+            }
+        }
+    }
 }
 
-impl Readable for OutputToken {
-    fn read<R>(reader: &mut R) -> io::Result<Self>
+impl OutputToken {
+    /// Read an output token encoded in a [SwapMessageV2](crate::messages::SwapMessageV2).
+    pub fn read_v2<R>(reader: &mut R) -> io::Result<Self>
+    where
+        R: io::Read,
+    {
+        Self::read_versioned(reader, true)
+    }
+
+    /// Write the output token as it is encoded in a
+    /// [SwapMessageV2](crate::messages::SwapMessageV2).
+    pub fn write_v2<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.write_versioned(writer, true)
+    }
+
+    fn read_versioned<R>(reader: &mut R, with_mode: bool) -> io::Result<Self>
     where
-        Self: Sized,
         R: io::Read,
     {
         match u8::read(reader)? {
             Self::USDC => Ok(Self::Usdc),
-            Self::GAS => Ok(Self::Gas(Readable::read(reader)?)),
+            Self::GAS => Ok(Self::Gas(OutputSwap::read_versioned(reader, with_mode)?)),
             Self::OTHER => Ok(Self::Other {
                 address: Readable::read(reader)?,
-                swap: Readable::read(reader)?,
+                swap: OutputSwap::read_versioned(reader, with_mode)?,
             }),
-            _ => Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Invalid OutputToken",
-            )),
+            value => Err(SwapMessageError::UnknownDiscriminant {
+                field: "output_token",
+                value,
+            }
+            .into()),
         }
     }
-}
 
-impl Writeable for OutputToken {
-    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    fn write_versioned<W>(&self, writer: &mut W, with_mode: bool) -> io::Result<()>
     where
         W: io::Write,
     {
@@ -58,45 +126,175 @@ impl Writeable for OutputToken {
             Self::Usdc => Self::USDC.write(writer),
             Self::Gas(swap) => {
                 Self::GAS.write(writer)?;
-                swap.write(writer)
+                swap.write_versioned(writer, with_mode)
             }
             Self::Other { address, swap } => {
                 Self::OTHER.write(writer)?;
                 address.write(writer)?;
-                swap.write(writer)
+                swap.write_versioned(writer, with_mode)
             }
         }
     }
 }
 
+impl Readable for OutputToken {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        Self::read_versioned(reader, false)
+    }
+}
+
+impl Writeable for OutputToken {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.write_versioned(writer, false)
+    }
+}
+
+/// Whether the limit amount of an [OutputSwap] is the minimum amount out of the swap (exact in)
+/// or the exact amount of the output token to deliver (exact out). An exact out swap leaves any
+/// USDC it does not use with the recipient.
+///
+/// Only [SwapMessageV2](crate::messages::SwapMessageV2) encodes the mode. Swaps in a
+/// [SwapMessageV1](crate::messages::SwapMessageV1) are always exact in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SwapMode {
+    #[default]
+    ExactIn,
+    ExactOut,
+}
+
+impl SwapMode {
+    const EXACT_IN: u8 = 0;
+    const EXACT_OUT: u8 = 1;
+}
+
+impl Readable for SwapMode {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        match u8::read(reader)? {
+            Self::EXACT_IN => Ok(Self::ExactIn),
+            Self::EXACT_OUT => Ok(Self::ExactOut),
+            value => Err(SwapMessageError::UnknownDiscriminant {
+                field: "swap_mode",
+                value,
+            }
+            .into()),
+        }
+    }
+}
+
+impl Writeable for SwapMode {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        match self {
+            Self::ExactIn => Self::EXACT_IN.write(writer),
+            Self::ExactOut => Self::EXACT_OUT.write(writer),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct OutputSwap {
     pub deadline: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::u128_string"))]
     pub limit_amount: u128,
     pub swap_type: SwapType,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mode: SwapMode,
 }
 
 impl OutputSwap {
+    /// Whether the limit amount is the exact amount of the output token to deliver. Otherwise the
+    /// limit amount is the minimum amount out.
+    pub fn is_exact_out(&self) -> bool {
+        self.mode == SwapMode::ExactOut
+    }
+
     pub fn written_size(&self) -> usize {
         const FIXED: usize = 4 // deadline
         + 16 // limit_amount
         ;
         self.swap_type.written_size().saturating_add(FIXED)
     }
-}
 
-impl Readable for OutputSwap {
-    fn read<R>(reader: &mut R) -> io::Result<Self>
+    /// Check that the swap can be executed on the target chain (Wormhole chain ID). Token amounts
+    /// on Solana are u64, so the limit amount cannot exceed it. Only Jupiter V6 swaps can be exact
+    /// out.
+    pub fn validate_for_target(&self, chain: u16) -> Result<(), SwapMessageError> {
+        if chain == SOLANA_CHAIN && u64::try_from(self.limit_amount).is_err() {
+            return Err(SwapMessageError::LimitAmountTooLarge {
+                chain,
+                limit_amount: self.limit_amount,
+            });
+        }
+
+        if self.is_exact_out() && !matches!(self.swap_type, SwapType::JupiterV6(_)) {
+            return Err(SwapMessageError::SwapTypeNotSupported { chain });
+        }
+
+        self.swap_type.validate_for_target(chain)
+    }
+
+    fn read_versioned<R>(reader: &mut R, with_mode: bool) -> io::Result<Self>
     where
-        Self: Sized,
         R: io::Read,
     {
         Ok(Self {
             deadline: Readable::read(reader)?,
             limit_amount: Readable::read(reader)?,
             swap_type: Readable::read(reader)?,
+            mode: match with_mode {
+                true => Readable::read(reader)?,
+                false => SwapMode::ExactIn,
+            },
         })
     }
+
+    fn write_versioned<W>(&self, writer: &mut W, with_mode: bool) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        if !with_mode && self.is_exact_out() {
+            return Err(SwapMessageError::SwapModeNotSupported {
+                version: crate::messages::SwapMessage::VERSION_1,
+            }
+            .into());
+        }
+
+        self.deadline.write(writer)?;
+        self.limit_amount.write(writer)?;
+        self.swap_type.write(writer)?;
+        if with_mode {
+            self.mode.write(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl Readable for OutputSwap {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        Self::read_versioned(reader, false)
+    }
 }
 
 impl Writeable for OutputSwap {
@@ -104,8 +302,6 @@ impl Writeable for OutputSwap {
     where
         W: io::Write,
     {
-        self.deadline.write(writer)?;
-        self.limit_amount.write(writer)?;
-        self.swap_type.write(writer)
+        self.write_versioned(writer, false)
     }
 }
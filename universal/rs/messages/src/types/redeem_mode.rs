@@ -1,28 +1,47 @@
-use std::io;
-
 use crate::{
+    error::SwapMessageError,
+    io,
     types::Uint48,
     wormhole_io::{Readable, Writeable, WriteableBytes},
 };
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "mode"))]
 pub enum RedeemMode {
     #[default]
     Direct,
     Payload {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_array"))]
         sender: [u8; 32],
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_payload"))]
         buf: WriteableBytes<u16>,
     },
+    #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
     Relay {
         gas_dropoff: u32,
         relaying_fee: crate::types::Uint48,
     },
+    /// Redeem via relayer, which also delivers the payload to the recipient (a contract or
+    /// program). The relayer is paid from the USDC like [Relay](Self::Relay).
+    ///
+    /// Only [SwapMessageV2](crate::messages::SwapMessageV2) supports this redeem mode.
+    #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+    RelayWithPayload {
+        gas_dropoff: u32,
+        relaying_fee: crate::types::Uint48,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_array"))]
+        sender: [u8; 32],
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_payload"))]
+        buf: WriteableBytes<u16>,
+    },
 }
 
 impl RedeemMode {
     const DIRECT: u8 = 0;
     const PAYLOAD: u8 = 1;
     const RELAY: u8 = 2;
+    pub(crate) const RELAY_WITH_PAYLOAD: u8 = 3;
 
     pub fn written_size(&self) -> usize {
         match self {
@@ -30,7 +49,7 @@ impl RedeemMode {
             Self::Payload { sender: _, buf } => buf.len().saturating_add(
                 1 // discriminant
                 + 32 // sender
-                + 4, // payload len
+                + 2, // payload len
             ),
             Self::Relay { .. } => {
                 const FIXED: usize = 1 // discriminant
@@ -39,8 +58,22 @@ impl RedeemMode {
 
                 FIXED
             }
+            Self::RelayWithPayload { buf, .. } => buf.len().saturating_add(
+                1 // discriminant
+                + 4 // gas_dropoff
+                + Uint48::BYTES // relaying_fee
+                + 32 // sender
+                + 2, // payload len
+            ),
         }
     }
+
+    /// Whether the redeem mode can be encoded in a
+    /// [SwapMessageV1](crate::messages::SwapMessageV1). Relaying with a payload was added by the
+    /// second version of the swap message.
+    pub fn is_supported_in_v1(&self) -> bool {
+        !matches!(self, Self::RelayWithPayload { .. })
+    }
 }
 
 impl Readable for RedeemMode {
@@ -59,10 +92,17 @@ impl Readable for RedeemMode {
                 gas_dropoff: Readable::read(reader)?,
                 relaying_fee: Readable::read(reader)?,
             }),
-            _ => Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Invalid RedeemMode",
-            )),
+            Self::RELAY_WITH_PAYLOAD => Ok(Self::RelayWithPayload {
+                gas_dropoff: Readable::read(reader)?,
+                relaying_fee: Readable::read(reader)?,
+                sender: Readable::read(reader)?,
+                buf: Readable::read(reader)?,
+            }),
+            value => Err(SwapMessageError::UnknownDiscriminant {
+                field: "redeem_mode",
+                value,
+            }
+            .into()),
         }
     }
 }
@@ -87,6 +127,18 @@ impl Writeable for RedeemMode {
                 gas_dropoff.write(writer)?;
                 relaying_fee.write(writer)
             }
+            Self::RelayWithPayload {
+                gas_dropoff,
+                relaying_fee,
+                sender,
+                buf,
+            } => {
+                Self::RELAY_WITH_PAYLOAD.write(writer)?;
+                gas_dropoff.write(writer)?;
+                relaying_fee.write(writer)?;
+                sender.write(writer)?;
+                buf.write(writer)
+            }
         }
     }
 }
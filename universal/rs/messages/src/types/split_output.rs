@@ -0,0 +1,247 @@
+use alloc::vec::Vec;
+
+use crate::{
+    error::SwapMessageError,
+    io,
+    types::{apply_bps, validate_recipient_for_target, IntegratorFeeAmount, OutputToken},
+    wormhole_io::{Readable, Writeable},
+};
+
+#[cfg(feature = "anchor")]
+use anchor_lang::prelude::{borsh, AnchorDeserialize, AnchorSerialize};
+
+/// Share of the USDC delivered to another recipient, who may receive a different output token
+/// than the message recipient. Split outputs only exist in
+/// [SwapMessageV2](crate::messages::SwapMessageV2), so the output token is encoded with its swap
+/// mode. This lets one fill pay several recipients (e.g. payroll) instead of
+/// sending one order per recipient.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SplitOutput {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_array"))]
+    pub recipient: [u8; 32],
+    /// Share of the USDC in basis points, rounded down. The message recipient receives whatever
+    /// is left after every split output is paid.
+    pub bps: u16,
+    pub output_token: OutputToken,
+}
+
+impl SplitOutput {
+    /// Most split outputs one message can have. Each split output is completed by its own
+    /// instruction on Solana, so this bounds the work needed to complete one fill.
+    pub const MAX_OUTPUTS: usize = 8;
+
+    pub fn written_size(&self) -> usize {
+        self.output_token.written_size_v2().saturating_add(
+            32 // recipient
+            + 2, // bps
+        )
+    }
+
+    /// Amount of USDC this split output receives out of the amount being split.
+    pub fn amount(&self, usdc_amount: u64) -> u64 {
+        apply_bps(usdc_amount, self.bps)
+    }
+
+    /// Check that the split output can be delivered on the target chain (Wormhole chain ID).
+    pub fn validate_for_target(&self, chain: u16) -> Result<(), SwapMessageError> {
+        validate_recipient_for_target(&self.recipient, chain).map_err(|_| {
+            SwapMessageError::InvalidEvmAddress {
+                field: "split_outputs.recipient",
+            }
+        })?;
+
+        if self.bps == 0 {
+            return Err(SwapMessageError::ValueOutOfRange {
+                field: "split_outputs.bps",
+                value: 0,
+            });
+        }
+
+        self.output_token.validate_for_target(chain)
+    }
+}
+
+/// Number of split outputs, which must not exceed [SplitOutput::MAX_OUTPUTS].
+pub fn encoded_split_outputs_len(split_outputs: &[SplitOutput]) -> Result<u8, SwapMessageError> {
+    let len = split_outputs.len();
+    match u8::try_from(len) {
+        Ok(encoded) if len <= SplitOutput::MAX_OUTPUTS => Ok(encoded),
+        _ => Err(SwapMessageError::TooManySplitOutputs { len }),
+    }
+}
+
+/// Check split outputs for the target chain (Wormhole chain ID). The split outputs must leave
+/// some USDC for the message recipient.
+pub fn validate_split_outputs_for_target(
+    split_outputs: &[SplitOutput],
+    chain: u16,
+) -> Result<(), SwapMessageError> {
+    encoded_split_outputs_len(split_outputs)?;
+
+    let mut total_bps = 0u64;
+    for split_output in split_outputs {
+        split_output.validate_for_target(chain)?;
+        total_bps = total_bps.saturating_add(split_output.bps.into());
+    }
+
+    if total_bps >= u64::from(IntegratorFeeAmount::MAX_BPS) {
+        return Err(SwapMessageError::ValueOutOfRange {
+            field: "split_outputs.total_bps",
+            value: total_bps,
+        });
+    }
+
+    Ok(())
+}
+
+/// Split the USDC amount between the message recipient and the split outputs. Returns the amount
+/// left for the message recipient followed by the amount for each split output.
+pub fn split_amounts(split_outputs: &[SplitOutput], usdc_amount: u64) -> (u64, Vec<u64>) {
+    let amounts = split_outputs
+        .iter()
+        .map(|split_output| split_output.amount(usdc_amount))
+        .collect::<Vec<_>>();
+    let remaining = amounts.iter().fold(usdc_amount, |remaining, amount| {
+        remaining.saturating_sub(*amount)
+    });
+
+    (remaining, amounts)
+}
+
+impl Readable for SplitOutput {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        Ok(Self {
+            recipient: Readable::read(reader)?,
+            bps: Readable::read(reader)?,
+            output_token: OutputToken::read_v2(reader)?,
+        })
+    }
+}
+
+impl Writeable for SplitOutput {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.recipient.write(writer)?;
+        self.bps.write(writer)?;
+        self.output_token.write_v2(writer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use crate::types::{OutputSwap, SwapMode, SwapType, Uint24, UniswapSwapParameters};
+
+    use super::*;
+
+    fn usdc_split(bps: u16) -> SplitOutput {
+        SplitOutput {
+            recipient: hex!("00000000000000000000000000000000000000000000000000000000000ba5ed"),
+            bps,
+            output_token: OutputToken::Usdc,
+        }
+    }
+
+    #[test]
+    fn test_split_output() {
+        let split_output = usdc_split(2500);
+
+        let encoded = hex!(
+            "00000000000000000000000000000000000000000000000000000000000ba5ed" // recipient
+            "09c4" // 25%
+            "00" // usdc
+        );
+
+        let mut written = Vec::with_capacity(split_output.written_size());
+        split_output.write(&mut written).unwrap();
+        assert_eq!(written, encoded);
+        assert_eq!(split_output.written_size(), encoded.len());
+        assert_eq!(SplitOutput::read(&mut &encoded[..]).unwrap(), split_output);
+    }
+
+    #[test]
+    fn test_split_amounts() {
+        let split_outputs = [usdc_split(2500), usdc_split(3333)];
+
+        // Rounding dust goes to the message recipient.
+        assert_eq!(
+            split_amounts(&split_outputs, 1_000_001),
+            (416_701, vec![250_000, 333_300])
+        );
+        assert_eq!(split_amounts(&[], 1_000_000), (1_000_000, vec![]));
+    }
+
+    #[test]
+    fn test_validate_split_outputs_for_target() {
+        assert_eq!(
+            validate_split_outputs_for_target(&[usdc_split(5000), usdc_split(4999)], 2),
+            Ok(())
+        );
+
+        // The message recipient must receive some USDC.
+        assert_eq!(
+            validate_split_outputs_for_target(&[usdc_split(5000), usdc_split(5000)], 2),
+            Err(SwapMessageError::ValueOutOfRange {
+                field: "split_outputs.total_bps",
+                value: 10_000
+            })
+        );
+
+        assert_eq!(
+            validate_split_outputs_for_target(&[usdc_split(0)], 2),
+            Err(SwapMessageError::ValueOutOfRange {
+                field: "split_outputs.bps",
+                value: 0
+            })
+        );
+
+        let split_outputs = vec![usdc_split(1); SplitOutput::MAX_OUTPUTS.saturating_add(1)];
+        assert_eq!(
+            validate_split_outputs_for_target(&split_outputs, 2),
+            Err(SwapMessageError::TooManySplitOutputs { len: 9 })
+        );
+
+        // Each split output is validated like the message recipient and output token.
+        let split_output = SplitOutput {
+            recipient: [1; 32],
+            ..usdc_split(1)
+        };
+        assert_eq!(
+            validate_split_outputs_for_target(core::slice::from_ref(&split_output), 1),
+            Ok(())
+        );
+        assert_eq!(
+            validate_split_outputs_for_target(&[split_output], 2),
+            Err(SwapMessageError::InvalidEvmAddress {
+                field: "split_outputs.recipient"
+            })
+        );
+
+        let split_output = SplitOutput {
+            output_token: OutputToken::Gas(OutputSwap {
+                deadline: 0,
+                limit_amount: 0,
+                swap_type: SwapType::UniswapV3(UniswapSwapParameters {
+                    first_leg_fee: Uint24::from(500),
+                    path: vec![],
+                }),
+                mode: SwapMode::ExactIn,
+            }),
+            ..usdc_split(1)
+        };
+        assert_eq!(
+            validate_split_outputs_for_target(&[split_output], 1),
+            Err(SwapMessageError::SwapTypeNotSupported { chain: 1 })
+        );
+    }
+}
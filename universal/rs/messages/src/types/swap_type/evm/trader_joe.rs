@@ -1,8 +1,18 @@
-use std::io::{self, ErrorKind};
+use core::iter;
 
+use alloc::vec::Vec;
+
+use crate::error::SwapMessageError;
+use crate::io;
 use crate::wormhole_io::{Readable, Writeable};
 
+#[cfg(feature = "anchor")]
+use anchor_lang::prelude::{borsh, AnchorDeserialize, AnchorSerialize};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct TraderJoeSwapParameters {
     pub first_pool_id: TraderJoePoolId,
     pub path: Vec<TraderJoeSwapPath>,
@@ -18,6 +28,29 @@ impl TraderJoeSwapParameters {
             .saturating_mul(TraderJoeSwapPath::ENCODED_SIZE)
             .saturating_add(FIXED)
     }
+
+    /// Number of hops in the path, which must fit in the one byte used to encode it.
+    pub fn encoded_path_len(&self) -> Result<u8, SwapMessageError> {
+        u8::try_from(self.path.len()).map_err(|_| SwapMessageError::PathTooLong {
+            field: "trader_joe.path",
+            len: self.path.len(),
+        })
+    }
+
+    /// LBRouter path from the input token to the output token, which is how the EVM Swap Layer
+    /// executes the swap. Exact input and exact output swaps use the same path.
+    pub fn to_router_path(&self, input: [u8; 20], output: [u8; 20]) -> TraderJoeRouterPath {
+        let pools = iter::once(&self.first_pool_id).chain(self.path.iter().map(|hop| &hop.pool_id));
+        let tokens = iter::once(input)
+            .chain(self.path.iter().map(|hop| hop.evm_address))
+            .chain(iter::once(output));
+
+        TraderJoeRouterPath {
+            pair_bin_steps: pools.clone().map(|pool_id| pool_id.bin_size).collect(),
+            versions: pools.map(|pool_id| pool_id.version).collect(),
+            token_path: tokens.collect(),
+        }
+    }
 }
 
 impl Readable for TraderJoeSwapParameters {
@@ -47,8 +80,7 @@ impl Writeable for TraderJoeSwapParameters {
     {
         self.first_pool_id.write(writer)?;
 
-        let path_len = u8::try_from(self.path.len()).map_err(|_| ErrorKind::InvalidInput)?;
-        path_len.write(writer)?;
+        self.encoded_path_len()?.write(writer)?;
         for path in &self.path {
             path.write(writer)?;
         }
@@ -57,13 +89,17 @@ impl Writeable for TraderJoeSwapParameters {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct TraderJoeSwapPath {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_array"))]
     pub evm_address: [u8; 20],
     pub pool_id: TraderJoePoolId,
 }
 
 impl TraderJoeSwapPath {
-    const ENCODED_SIZE: usize = 20 + TraderJoePoolId::ENCODED_SIZE;
+    pub(crate) const ENCODED_SIZE: usize = 20 + TraderJoePoolId::ENCODED_SIZE;
 }
 
 impl Readable for TraderJoeSwapPath {
@@ -88,14 +124,18 @@ impl Writeable for TraderJoeSwapPath {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct TraderJoePoolId {
-    pub version: u8,
+    pub version: TraderJoeVersion,
+    /// Bin step of the pair, in basis points.
     pub bin_size: u16,
 }
 
 impl TraderJoePoolId {
-    const ENCODED_SIZE: usize = 3;
+    pub(crate) const ENCODED_SIZE: usize = 3;
 }
 
 impl Readable for TraderJoePoolId {
@@ -105,7 +145,7 @@ impl Readable for TraderJoePoolId {
         R: io::Read,
     {
         Ok(Self {
-            version: u8::read(reader)?,
+            version: Readable::read(reader)?,
             bin_size: u16::read(reader)?,
         })
     }
@@ -120,3 +160,216 @@ impl Writeable for TraderJoePoolId {
         self.bin_size.write(writer)
     }
 }
+
+/// Version of a Liquidity Book pair, encoded like the LBRouter's `Version` enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "u8", try_from = "u8"))]
+pub enum TraderJoeVersion {
+    V1,
+    V2,
+    V2_1,
+    V2_2,
+}
+
+impl TraderJoeVersion {
+    const V1_VALUE: u8 = 0;
+    const V2_VALUE: u8 = 1;
+    const V2_1_VALUE: u8 = 2;
+    const V2_2_VALUE: u8 = 3;
+}
+
+impl From<TraderJoeVersion> for u8 {
+    fn from(version: TraderJoeVersion) -> Self {
+        match version {
+            TraderJoeVersion::V1 => TraderJoeVersion::V1_VALUE,
+            TraderJoeVersion::V2 => TraderJoeVersion::V2_VALUE,
+            TraderJoeVersion::V2_1 => TraderJoeVersion::V2_1_VALUE,
+            TraderJoeVersion::V2_2 => TraderJoeVersion::V2_2_VALUE,
+        }
+    }
+}
+
+impl TryFrom<u8> for TraderJoeVersion {
+    type Error = SwapMessageError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            Self::V1_VALUE => Ok(Self::V1),
+            Self::V2_VALUE => Ok(Self::V2),
+            Self::V2_1_VALUE => Ok(Self::V2_1),
+            Self::V2_2_VALUE => Ok(Self::V2_2),
+            value => Err(SwapMessageError::UnknownDiscriminant {
+                field: "trader_joe.version",
+                value,
+            }),
+        }
+    }
+}
+
+impl Readable for TraderJoeVersion {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        Ok(Self::try_from(u8::read(reader)?)?)
+    }
+}
+
+impl Writeable for TraderJoeVersion {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        u8::from(*self).write(writer)
+    }
+}
+
+/// Borsh encodes this type as a [u8] so accounts staged before it was introduced (and the IDL)
+/// are unchanged.
+#[cfg(feature = "anchor")]
+impl AnchorSerialize for TraderJoeVersion {
+    fn serialize<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        AnchorSerialize::serialize(&u8::from(*self), writer)
+    }
+}
+
+#[cfg(feature = "anchor")]
+impl AnchorDeserialize for TraderJoeVersion {
+    fn deserialize_reader<R>(reader: &mut R) -> io::Result<Self>
+    where
+        R: io::Read,
+    {
+        let value = <u8 as AnchorDeserialize>::deserialize_reader(reader)?;
+        Ok(Self::try_from(value)?)
+    }
+}
+
+#[cfg(feature = "idl-build")]
+impl anchor_lang::IdlBuild for TraderJoeVersion {
+    fn create_type() -> Option<anchor_lang::idl::types::IdlTypeDef> {
+        Some(anchor_lang::idl::types::IdlTypeDef {
+            name: Self::get_full_path(),
+            docs: Default::default(),
+            serialization: Default::default(),
+            repr: None,
+            generics: Default::default(),
+            ty: anchor_lang::idl::types::IdlTypeDefTy::Type {
+                alias: anchor_lang::idl::types::IdlType::U8,
+            },
+        })
+    }
+}
+
+/// Swap path in the form taken by the Liquidity Book router (LBRouter) `Path` struct. Pair `i`
+/// swaps `token_path[i]` for `token_path[i + 1]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraderJoeRouterPath {
+    pub pair_bin_steps: Vec<u16>,
+    pub versions: Vec<TraderJoeVersion>,
+    pub token_path: Vec<[u8; 20]>,
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use super::*;
+
+    const USDC: [u8; 20] = hex!("b97ef9ef8734c71904d8002f8b6bc66dd9c48a6e");
+    const WAVAX: [u8; 20] = hex!("b31f66aa3c1e785363f0875a1b74e27b85fd66c7");
+    const BTC_B: [u8; 20] = hex!("152b9d0fdc40c096757f570a51e494bd4b943e50");
+
+    #[test]
+    fn test_trader_joe_version() {
+        for (version, value) in [
+            (TraderJoeVersion::V1, 0),
+            (TraderJoeVersion::V2, 1),
+            (TraderJoeVersion::V2_1, 2),
+            (TraderJoeVersion::V2_2, 3),
+        ] {
+            assert_eq!(u8::from(version), value);
+            assert_eq!(TraderJoeVersion::try_from(value), Ok(version));
+        }
+        assert!(TraderJoeVersion::try_from(4).is_err());
+
+        let pool_id = TraderJoePoolId {
+            version: TraderJoeVersion::V2_2,
+            bin_size: 25,
+        };
+        let mut encoded = Vec::new();
+        pool_id.write(&mut encoded).unwrap();
+        assert_eq!(encoded, hex!("030019"));
+        assert_eq!(
+            TraderJoePoolId::read(&mut encoded.as_slice()).unwrap(),
+            pool_id
+        );
+
+        let err = TraderJoePoolId::read(&mut &hex!("040019")[..]).unwrap_err();
+        assert_eq!(
+            SwapMessageError::from(err),
+            SwapMessageError::UnknownDiscriminant {
+                field: "trader_joe.version",
+                value: 4
+            }
+        );
+    }
+
+    #[test]
+    fn test_trader_joe_router_path() {
+        let parameters = TraderJoeSwapParameters {
+            first_pool_id: TraderJoePoolId {
+                version: TraderJoeVersion::V2_1,
+                bin_size: 20,
+            },
+            path: vec![TraderJoeSwapPath {
+                evm_address: WAVAX,
+                pool_id: TraderJoePoolId {
+                    version: TraderJoeVersion::V1,
+                    bin_size: 0,
+                },
+            }],
+        };
+
+        assert_eq!(
+            parameters.to_router_path(USDC, BTC_B),
+            TraderJoeRouterPath {
+                pair_bin_steps: vec![20, 0],
+                versions: vec![TraderJoeVersion::V2_1, TraderJoeVersion::V1],
+                token_path: vec![USDC, WAVAX, BTC_B],
+            }
+        );
+
+        // A single pair.
+        let parameters = TraderJoeSwapParameters {
+            path: vec![],
+            ..parameters
+        };
+        assert_eq!(
+            parameters.to_router_path(USDC, WAVAX),
+            TraderJoeRouterPath {
+                pair_bin_steps: vec![20],
+                versions: vec![TraderJoeVersion::V2_1],
+                token_path: vec![USDC, WAVAX],
+            }
+        );
+    }
+
+    #[cfg(feature = "anchor")]
+    #[test]
+    fn test_trader_joe_version_borsh() {
+        use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+
+        let encoded = TraderJoeVersion::V2_1.try_to_vec().unwrap();
+        assert_eq!(encoded, [2]);
+        assert_eq!(
+            TraderJoeVersion::try_from_slice(&encoded).unwrap(),
+            TraderJoeVersion::V2_1
+        );
+        assert!(TraderJoeVersion::try_from_slice(&[4]).is_err());
+    }
+}
@@ -1,9 +1,19 @@
-use std::io::{self, ErrorKind};
+use core::{array::TryFromSliceError, iter};
 
+use alloc::vec::Vec;
+
+use crate::error::SwapMessageError;
+use crate::io;
 use crate::types::Uint24;
 use crate::wormhole_io::{Readable, Writeable};
 
+#[cfg(feature = "anchor")]
+use anchor_lang::prelude::{borsh, AnchorDeserialize, AnchorSerialize};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct UniswapSwapParameters {
     pub first_leg_fee: Uint24,
     pub path: Vec<UniswapSwapPath>,
@@ -19,6 +29,139 @@ impl UniswapSwapParameters {
             .saturating_mul(UniswapSwapPath::ENCODED_SIZE)
             .saturating_add(FIXED)
     }
+
+    /// Number of hops in the path, which must fit in the one byte used to encode it.
+    pub fn encoded_path_len(&self) -> Result<u8, SwapMessageError> {
+        u8::try_from(self.path.len()).map_err(|_| SwapMessageError::PathTooLong {
+            field: "uniswap_v3.path",
+            len: self.path.len(),
+        })
+    }
+
+    /// Packed path for the Uniswap V3 router's exact input swaps, i.e. `input|fee|token|...|output`
+    /// with 20-byte token addresses and 3-byte fees. This is the path the EVM Swap Layer composes
+    /// from the message and the input and output tokens.
+    pub fn to_uniswap_v3_path(&self, input: [u8; 20], output: [u8; 20]) -> Vec<u8> {
+        pack_path((input, self.legs(output)))
+    }
+
+    /// Packed path for exact output swaps, which Uniswap expects in reverse order, i.e.
+    /// `output|fee|token|...|input`.
+    pub fn to_uniswap_v3_exact_output_path(&self, input: [u8; 20], output: [u8; 20]) -> Vec<u8> {
+        pack_path(reverse_path((input, self.legs(output))))
+    }
+
+    /// Inverse of [to_uniswap_v3_path](Self::to_uniswap_v3_path). Returns the parameters with the
+    /// input and output tokens.
+    pub fn from_uniswap_v3_path(
+        path: &[u8],
+    ) -> Result<(Self, [u8; 20], [u8; 20]), SwapMessageError> {
+        Self::from_legs(unpack_path(path)?)
+    }
+
+    /// Inverse of [to_uniswap_v3_exact_output_path](Self::to_uniswap_v3_exact_output_path).
+    pub fn from_uniswap_v3_exact_output_path(
+        path: &[u8],
+    ) -> Result<(Self, [u8; 20], [u8; 20]), SwapMessageError> {
+        Self::from_legs(reverse_path(unpack_path(path)?))
+    }
+
+    /// Each swap of the route as the fee of its pool and the token swapped into.
+    fn legs(&self, output: [u8; 20]) -> Vec<(Uint24, [u8; 20])> {
+        let fees = iter::once(self.first_leg_fee).chain(self.path.iter().map(|hop| hop.fee));
+        let tokens = self
+            .path
+            .iter()
+            .map(|hop| hop.evm_address)
+            .chain(iter::once(output));
+        fees.zip(tokens).collect()
+    }
+
+    fn from_legs(
+        (input, legs): PackedPath,
+    ) -> Result<(Self, [u8; 20], [u8; 20]), SwapMessageError> {
+        let mut fees = legs.iter().map(|(fee, _)| *fee);
+        let (Some(first_leg_fee), Some((_, output))) = (fees.next(), legs.last()) else {
+            return Err(SwapMessageError::InvalidPackedPath {
+                field: "uniswap_v3.path",
+                len: 20,
+            });
+        };
+
+        // The last token is the output, so it is not part of the encoded path.
+        let parameters = Self {
+            first_leg_fee,
+            path: legs
+                .iter()
+                .zip(fees)
+                .map(|((_, evm_address), fee)| UniswapSwapPath {
+                    evm_address: *evm_address,
+                    fee,
+                })
+                .collect(),
+        };
+        parameters.encoded_path_len()?;
+
+        Ok((parameters, input, *output))
+    }
+}
+
+/// First token of a packed path, followed by each swap as the fee of its pool and the token
+/// swapped into.
+type PackedPath = ([u8; 20], Vec<(Uint24, [u8; 20])>);
+
+fn pack_path((first, legs): PackedPath) -> Vec<u8> {
+    let mut path = Vec::with_capacity(
+        legs.len()
+            .saturating_mul(UniswapSwapPath::ENCODED_SIZE)
+            .saturating_add(20),
+    );
+    path.extend_from_slice(&first);
+    for (fee, token) in legs {
+        path.extend_from_slice(&fee.to_be_bytes());
+        path.extend_from_slice(&token);
+    }
+    path
+}
+
+/// Unpack a path with at least one pool.
+fn unpack_path(path: &[u8]) -> Result<PackedPath, SwapMessageError> {
+    let err = || SwapMessageError::InvalidPackedPath {
+        field: "uniswap_v3.path",
+        len: path.len(),
+    };
+
+    let (first, legs) = path.split_at(path.len().min(20));
+    let first = <[u8; 20]>::try_from(first).map_err(|_| err())?;
+    if legs.is_empty() {
+        return Err(err());
+    }
+
+    let legs = legs
+        .chunks(UniswapSwapPath::ENCODED_SIZE)
+        .map(|leg| {
+            let (fee, token) = leg.split_at(leg.len().min(Uint24::BYTES));
+            Ok((
+                Uint24::from_be_bytes(fee.try_into()?),
+                <[u8; 20]>::try_from(token)?,
+            ))
+        })
+        .collect::<Result<_, TryFromSliceError>>()
+        .map_err(|_| err())?;
+
+    Ok((first, legs))
+}
+
+/// Reverse the direction of a path, which is how Uniswap expects exact output paths.
+fn reverse_path((first, legs): PackedPath) -> PackedPath {
+    let mut tokens = iter::once(first)
+        .chain(legs.iter().map(|(_, token)| *token))
+        .rev();
+    let last = tokens.next().unwrap_or(first);
+
+    // The pool between two tokens keeps its fee.
+    let legs = legs.iter().rev().map(|(fee, _)| *fee).zip(tokens).collect();
+    (last, legs)
 }
 
 impl Readable for UniswapSwapParameters {
@@ -48,8 +191,7 @@ impl Writeable for UniswapSwapParameters {
     {
         self.first_leg_fee.write(writer)?;
 
-        let path_len = u8::try_from(self.path.len()).map_err(|_| ErrorKind::InvalidInput)?;
-        path_len.write(writer)?;
+        self.encoded_path_len()?.write(writer)?;
         for path in &self.path {
             path.write(writer)?;
         }
@@ -58,13 +200,17 @@ impl Writeable for UniswapSwapParameters {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct UniswapSwapPath {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_array"))]
     pub evm_address: [u8; 20],
     pub fee: Uint24,
 }
 
 impl UniswapSwapPath {
-    const ENCODED_SIZE: usize = 20 + Uint24::BYTES;
+    pub(crate) const ENCODED_SIZE: usize = 20 + Uint24::BYTES;
 }
 
 impl Readable for UniswapSwapPath {
@@ -88,3 +234,103 @@ impl Writeable for UniswapSwapPath {
         self.fee.write(writer)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use super::*;
+
+    const USDC: [u8; 20] = hex!("af88d065e77c8cc2239327c5edb3a432268e5831");
+    const WETH: [u8; 20] = hex!("82af49447d8a07e3bd95bd0d56f35241523fbab1");
+    const WBTC: [u8; 20] = hex!("2f2a2543b76a4166549f7aab2e75bef0aefc5b0f");
+
+    fn weth_to_wbtc() -> UniswapSwapParameters {
+        UniswapSwapParameters {
+            first_leg_fee: Uint24::from(500),
+            path: vec![UniswapSwapPath {
+                evm_address: WETH,
+                fee: Uint24::from(3000),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_uniswap_v3_path() {
+        let parameters = weth_to_wbtc();
+
+        let path = parameters.to_uniswap_v3_path(USDC, WBTC);
+        assert_eq!(
+            path,
+            [&USDC[..], &hex!("0001f4"), &WETH, &hex!("000bb8"), &WBTC,].concat()
+        );
+        assert_eq!(
+            UniswapSwapParameters::from_uniswap_v3_path(&path),
+            Ok((parameters.clone(), USDC, WBTC))
+        );
+
+        let path = parameters.to_uniswap_v3_exact_output_path(USDC, WBTC);
+        assert_eq!(
+            path,
+            [&WBTC[..], &hex!("000bb8"), &WETH, &hex!("0001f4"), &USDC,].concat()
+        );
+        assert_eq!(
+            UniswapSwapParameters::from_uniswap_v3_exact_output_path(&path),
+            Ok((parameters, USDC, WBTC))
+        );
+    }
+
+    #[test]
+    fn test_uniswap_v3_path_single_pool() {
+        let parameters = UniswapSwapParameters {
+            first_leg_fee: Uint24::from(100),
+            path: vec![],
+        };
+
+        let path = parameters.to_uniswap_v3_path(USDC, WETH);
+        assert_eq!(path, [&USDC[..], &hex!("000064"), &WETH].concat());
+        assert_eq!(
+            parameters.to_uniswap_v3_exact_output_path(USDC, WETH),
+            [&WETH[..], &hex!("000064"), &USDC].concat()
+        );
+        assert_eq!(
+            UniswapSwapParameters::from_uniswap_v3_path(&path),
+            Ok((parameters, USDC, WETH))
+        );
+    }
+
+    #[test]
+    fn test_uniswap_v3_path_errors() {
+        let path = weth_to_wbtc().to_uniswap_v3_path(USDC, WBTC);
+
+        // Without any pool, or with a truncated one.
+        for len in [0, 19, 20, 42, 44, path.len().saturating_sub(1)] {
+            assert_eq!(
+                UniswapSwapParameters::from_uniswap_v3_path(path.get(..len).unwrap()),
+                Err(SwapMessageError::InvalidPackedPath {
+                    field: "uniswap_v3.path",
+                    len
+                })
+            );
+        }
+
+        // The encoded path length must fit in one byte.
+        let hops = UniswapSwapParameters {
+            first_leg_fee: Uint24::from(500),
+            path: vec![
+                UniswapSwapPath {
+                    evm_address: WETH,
+                    fee: Uint24::from(500),
+                };
+                256
+            ],
+        };
+        assert_eq!(
+            UniswapSwapParameters::from_uniswap_v3_path(&hops.to_uniswap_v3_path(USDC, WBTC)),
+            Err(SwapMessageError::PathTooLong {
+                field: "uniswap_v3.path",
+                len: 256
+            })
+        );
+    }
+}
@@ -4,31 +4,61 @@ pub use evm::*;
 mod solana;
 pub use solana::*;
 
-use std::io;
+use crate::{
+    error::SwapMessageError,
+    io,
+    types::SOLANA_CHAIN,
+    wormhole_io::{Readable, Writeable},
+};
 
-use crate::wormhole_io::{Readable, Writeable};
+#[cfg(feature = "anchor")]
+use anchor_lang::prelude::{borsh, AnchorDeserialize, AnchorSerialize};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
 pub enum SwapType {
     Invalid,
     UniswapV3(UniswapSwapParameters),
     TraderJoe(TraderJoeSwapParameters),
     JupiterV6(JupiterV6SwapParameters),
+    // Variants are appended so the Borsh encoding of staged swaps does not change.
+    OrcaWhirlpool(OrcaWhirlpoolSwapParameters),
 }
 
 impl SwapType {
-    const UNISWAP_V3: u8 = 1;
-    const TRADER_JOE: u8 = 2;
-    const JUPITER_V6: u8 = 16;
+    pub(crate) const UNISWAP_V3: u8 = 1;
+    pub(crate) const TRADER_JOE: u8 = 2;
+    pub(crate) const JUPITER_V6: u8 = 16;
+    pub(crate) const ORCA_WHIRLPOOL: u8 = 18;
 
     pub fn written_size(&self) -> usize {
         match self {
             Self::Invalid => 0,
             Self::UniswapV3(parameters) => parameters.written_size().saturating_add(1),
             Self::TraderJoe(parameters) => parameters.written_size().saturating_add(1),
+            Self::OrcaWhirlpool(parameters) => parameters.written_size().saturating_add(1),
             Self::JupiterV6(parameters) => parameters.written_size().saturating_add(1),
         }
     }
+
+    /// Check that the swap can be executed on the target chain (Wormhole chain ID). EVM swap
+    /// types can only be executed on EVM networks and Jupiter V6 and Orca Whirlpool only on Solana.
+    pub fn validate_for_target(&self, chain: u16) -> Result<(), SwapMessageError> {
+        let is_solana = chain == SOLANA_CHAIN;
+
+        match self {
+            Self::Invalid => Err(SwapMessageError::InvalidSwapType),
+            Self::UniswapV3(parameters) if !is_solana => parameters.encoded_path_len().map(|_| ()),
+            Self::TraderJoe(parameters) if !is_solana => parameters.encoded_path_len().map(|_| ()),
+            Self::JupiterV6(_) if is_solana => Ok(()),
+            Self::OrcaWhirlpool(parameters) if is_solana => {
+                parameters.encoded_pools_len().map(|_| ())
+            }
+            _ => Err(SwapMessageError::SwapTypeNotSupported { chain }),
+        }
+    }
 }
 
 impl Readable for SwapType {
@@ -41,10 +71,12 @@ impl Readable for SwapType {
             Self::UNISWAP_V3 => Ok(Self::UniswapV3(Readable::read(reader)?)),
             Self::TRADER_JOE => Ok(Self::TraderJoe(Readable::read(reader)?)),
             Self::JUPITER_V6 => Ok(Self::JupiterV6(Readable::read(reader)?)),
-            _ => Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Invalid SwapType",
-            )),
+            Self::ORCA_WHIRLPOOL => Ok(Self::OrcaWhirlpool(Readable::read(reader)?)),
+            value => Err(SwapMessageError::UnknownDiscriminant {
+                field: "swap_type",
+                value,
+            }
+            .into()),
         }
     }
 }
@@ -67,10 +99,11 @@ impl Writeable for SwapType {
                 Self::JUPITER_V6.write(writer)?;
                 parameters.write(writer)
             }
-            _ => Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Invalid SwapType",
-            )),
+            Self::OrcaWhirlpool(parameters) => {
+                Self::ORCA_WHIRLPOOL.write(writer)?;
+                parameters.write(writer)
+            }
+            Self::Invalid => Err(SwapMessageError::InvalidSwapType.into()),
         }
     }
 }
@@ -1,9 +1,21 @@
-use std::io;
+use crate::{
+    error::SwapMessageError,
+    io,
+    wormhole_io::{Readable, Writeable},
+};
 
-use crate::wormhole_io::{Readable, Writeable};
+#[cfg(feature = "anchor")]
+use anchor_lang::prelude::{borsh, AnchorDeserialize, AnchorSerialize};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct JupiterV6SwapParameters {
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::hex_array_option", default)
+    )]
     pub dex_program_id: Option<[u8; 32]>,
 }
 
@@ -22,9 +34,19 @@ impl Readable for JupiterV6SwapParameters {
         Self: Sized,
         R: io::Read,
     {
-        Ok(Self {
-            dex_program_id: Readable::read(reader)?,
-        })
+        let dex_program_id = match u8::read(reader)? {
+            0 => None,
+            1 => Some(Readable::read(reader)?),
+            value => {
+                return Err(SwapMessageError::UnknownDiscriminant {
+                    field: "dex_program_id",
+                    value,
+                }
+                .into())
+            }
+        };
+
+        Ok(Self { dex_program_id })
     }
 }
 
@@ -41,7 +63,8 @@ impl Writeable for JupiterV6SwapParameters {
 mod test {
     use hex_literal::hex;
     use solana_program::pubkey::Pubkey;
-    use wormhole_io::{Readable, Writeable};
+
+    use crate::wormhole_io::{Readable, Writeable};
 
     use super::JupiterV6SwapParameters;
 
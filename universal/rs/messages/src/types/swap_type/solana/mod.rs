@@ -1,2 +1,5 @@
 mod jupiter_v6;
 pub use jupiter_v6::*;
+
+mod orca_whirlpool;
+pub use orca_whirlpool::*;
@@ -0,0 +1,192 @@
+use alloc::vec::Vec;
+
+use crate::{
+    error::SwapMessageError,
+    io,
+    wormhole_io::{Readable, Writeable},
+};
+
+#[cfg(feature = "anchor")]
+use anchor_lang::prelude::{borsh, AnchorDeserialize, AnchorSerialize};
+
+/// Swap through Orca Whirlpools directly (without Jupiter V6). The Whirlpool program can swap
+/// through at most two pools in one instruction, so the path must have one or two pools.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct OrcaWhirlpoolSwapParameters {
+    pub pools: Vec<OrcaWhirlpoolPool>,
+}
+
+impl OrcaWhirlpoolSwapParameters {
+    pub fn written_size(&self) -> usize {
+        self.pools
+            .len()
+            .saturating_mul(OrcaWhirlpoolPool::ENCODED_SIZE)
+            .saturating_add(1) // pools_len
+    }
+
+    /// Number of pools, which must be one or two.
+    pub fn encoded_pools_len(&self) -> Result<u8, SwapMessageError> {
+        match self.pools.len() {
+            1 => Ok(1),
+            2 => Ok(2),
+            len => Err(SwapMessageError::UnsupportedPathLength {
+                field: "orca_whirlpool.pools",
+                len,
+            }),
+        }
+    }
+}
+
+impl Readable for OrcaWhirlpoolSwapParameters {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        let pools_len = u8::read(reader)?;
+        let mut pools = Vec::with_capacity(pools_len.into());
+        for _ in 0..pools_len {
+            pools.push(Readable::read(reader)?);
+        }
+        Ok(Self { pools })
+    }
+}
+
+impl Writeable for OrcaWhirlpoolSwapParameters {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.encoded_pools_len()?.write(writer)?;
+        for pool in &self.pools {
+            pool.write(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whirlpool account and the direction of the swap through it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "anchor", derive(AnchorSerialize, AnchorDeserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct OrcaWhirlpoolPool {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_array"))]
+    pub whirlpool: [u8; 32],
+    /// Whether token A of the whirlpool is swapped for token B.
+    pub a_to_b: bool,
+}
+
+impl OrcaWhirlpoolPool {
+    pub(crate) const ENCODED_SIZE: usize = 33;
+}
+
+impl Readable for OrcaWhirlpoolPool {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read,
+    {
+        let whirlpool = Readable::read(reader)?;
+        let a_to_b = match u8::read(reader)? {
+            0 => false,
+            1 => true,
+            value => {
+                return Err(SwapMessageError::UnknownDiscriminant {
+                    field: "orca_whirlpool.a_to_b",
+                    value,
+                }
+                .into())
+            }
+        };
+
+        Ok(Self { whirlpool, a_to_b })
+    }
+}
+
+impl Writeable for OrcaWhirlpoolPool {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.whirlpool.write(writer)?;
+        u8::from(self.a_to_b).write(writer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex_literal::hex;
+
+    use super::*;
+
+    #[test]
+    fn test_orca_whirlpool_swap_parameters() {
+        let params = OrcaWhirlpoolSwapParameters {
+            pools: vec![
+                OrcaWhirlpoolPool {
+                    whirlpool: [1; 32],
+                    a_to_b: true,
+                },
+                OrcaWhirlpoolPool {
+                    whirlpool: [2; 32],
+                    a_to_b: false,
+                },
+            ],
+        };
+
+        let encoded = hex!(
+            "02"
+            "0101010101010101010101010101010101010101010101010101010101010101" "01"
+            "0202020202020202020202020202020202020202020202020202020202020202" "00"
+        );
+
+        let mut written = Vec::with_capacity(params.written_size());
+        params.write(&mut written).unwrap();
+        assert_eq!(written, encoded);
+        assert_eq!(params.written_size(), encoded.len());
+        assert_eq!(
+            OrcaWhirlpoolSwapParameters::read(&mut &encoded[..]).unwrap(),
+            params
+        );
+
+        // The direction must be encoded as a boolean.
+        let mut invalid = encoded;
+        invalid[33] = 2;
+        assert_eq!(
+            SwapMessageError::from(
+                OrcaWhirlpoolSwapParameters::read(&mut &invalid[..]).unwrap_err()
+            ),
+            SwapMessageError::UnknownDiscriminant {
+                field: "orca_whirlpool.a_to_b",
+                value: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_orca_whirlpool_pools_len() {
+        let pool = OrcaWhirlpoolPool {
+            whirlpool: [1; 32],
+            a_to_b: true,
+        };
+
+        for len in [0, 3] {
+            let params = OrcaWhirlpoolSwapParameters {
+                pools: vec![pool; len],
+            };
+            let err = SwapMessageError::UnsupportedPathLength {
+                field: "orca_whirlpool.pools",
+                len,
+            };
+            assert_eq!(params.encoded_pools_len(), Err(err.clone()));
+            assert_eq!(
+                SwapMessageError::from(params.write(&mut Vec::new()).unwrap_err()),
+                err
+            );
+        }
+    }
+}
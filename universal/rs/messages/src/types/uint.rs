@@ -1,200 +1,213 @@
-use std::{io, ops::Deref};
-
-use crate::wormhole_io::{Readable, Writeable};
-use ruint::{ToUintError, Uint};
-
-/// New type for a 3-byte unsigned integer.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub struct Uint24(Uint<24, 1>);
-
-impl Uint24 {
-    pub const ZERO: Uint24 = Self(Uint::ZERO);
-    pub const BYTES: usize = Uint::<24, 1>::BYTES;
-    pub const LAST_INDEX: usize = Self::BYTES - 1;
-
-    pub fn from_be_bytes(bytes: [u8; Self::BYTES]) -> Self {
-        let mut out = u64::default();
-        for (i, byte) in bytes.into_iter().enumerate() {
-            let value = u64::from(byte)
-                << usize::saturating_mul(8, usize::saturating_sub(Self::LAST_INDEX, i));
-            out = out.saturating_add(value);
-        }
+use core::{fmt, num::ParseIntError, str::FromStr};
+
+use crate::{
+    io,
+    wormhole_io::{Readable, Writeable},
+};
+
+/// Unsigned integer encoded as `BYTES` big-endian bytes (e.g. Solidity's uint24 and uint48).
+/// Widths from 2 to 8 bytes are supported. Using any other width fails to compile.
+///
+/// Arithmetic is either checked or saturating at [MAX](Self::MAX), so amounts can stay in the
+/// wire type without converting to a primitive and back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct WireUint<const BYTES: usize>(u64);
+
+/// 3-byte unsigned integer.
+pub type Uint24 = WireUint<3>;
+
+/// 6-byte unsigned integer.
+pub type Uint48 = WireUint<6>;
+
+/// Failed conversion to a [WireUint].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WireUintError {
+    /// The value is negative or does not fit in this many bytes.
+    OutOfRange { bytes: usize },
+
+    /// The string is not a decimal integer.
+    Parse(ParseIntError),
+}
 
-        Self(Uint::from(out))
+impl fmt::Display for WireUintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfRange { bytes } => write!(f, "value does not fit in {bytes} bytes"),
+            Self::Parse(err) => write!(f, "invalid integer: {err}"),
+        }
     }
+}
 
-    pub fn from_le_bytes(bytes: [u8; Self::BYTES]) -> Self {
-        let mut out = u64::default();
-        for (i, byte) in bytes.into_iter().enumerate() {
-            let value = u64::from(byte) << usize::saturating_mul(8, i);
-            out = out.saturating_add(value);
-        }
+#[cfg(feature = "std")]
+impl std::error::Error for WireUintError {}
+
+impl<const BYTES: usize> WireUint<BYTES> {
+    pub const BYTES: usize = BYTES;
+    pub const ZERO: Self = Self::new_unchecked(0);
+    pub const MAX: Self = Self::new_unchecked(u64::MAX >> (64 - 8 * BYTES));
+
+    const SUPPORTED_WIDTH: () = assert!(BYTES >= 2 && BYTES <= 8, "unsupported wire width");
 
-        Self(Uint::from(out))
+    /// Every constructor goes through here so that unsupported widths are rejected.
+    const fn new_unchecked(value: u64) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::SUPPORTED_WIDTH;
+        Self(value)
     }
 
-    pub fn to_be_bytes(&self) -> [u8; Self::BYTES] {
-        self.0.to_be_bytes::<{ Self::BYTES }>()
+    fn checked_new(value: u64) -> Option<Self> {
+        (value <= Self::MAX.0).then(|| Self::new_unchecked(value))
     }
 
-    pub fn to_le_bytes(&self) -> [u8; Self::BYTES] {
-        self.0.to_le_bytes::<{ Self::BYTES }>()
+    pub fn from_be_bytes(bytes: [u8; BYTES]) -> Self {
+        let mut buf = [0; 8];
+        buf.get_mut(8usize.saturating_sub(BYTES)..)
+            .unwrap_or_default()
+            .copy_from_slice(&bytes);
+
+        Self::new_unchecked(u64::from_be_bytes(buf))
     }
-}
 
-impl Deref for Uint24 {
-    type Target = Uint<24, 1>;
+    pub fn from_le_bytes(bytes: [u8; BYTES]) -> Self {
+        let mut buf = [0; 8];
+        buf.get_mut(..BYTES)
+            .unwrap_or_default()
+            .copy_from_slice(&bytes);
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+        Self::new_unchecked(u64::from_le_bytes(buf))
     }
-}
 
-impl From<Uint24> for Uint<24, 1> {
-    fn from(value: Uint24) -> Self {
-        value.0
+    pub fn to_be_bytes(&self) -> [u8; BYTES] {
+        let mut out = [0; BYTES];
+        out.copy_from_slice(
+            self.0
+                .to_be_bytes()
+                .get(8usize.saturating_sub(BYTES)..)
+                .unwrap_or_default(),
+        );
+        out
     }
-}
 
-impl From<Uint24> for u64 {
-    fn from(value: Uint24) -> Self {
-        value.0.into_limbs()[0]
+    pub fn to_le_bytes(&self) -> [u8; BYTES] {
+        let mut out = [0; BYTES];
+        out.copy_from_slice(self.0.to_le_bytes().get(..BYTES).unwrap_or_default());
+        out
     }
-}
 
-impl From<u16> for Uint24 {
-    fn from(value: u16) -> Self {
-        Self(Uint::from(value))
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).and_then(Self::checked_new)
     }
-}
 
-impl TryFrom<u32> for Uint24 {
-    type Error = ToUintError<<Self as Deref>::Target>;
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self::new_unchecked)
+    }
 
-    fn try_from(value: u32) -> Result<Self, Self::Error> {
-        Ok(Self(Uint::try_from(value)?))
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        self.0.checked_mul(rhs.0).and_then(Self::checked_new)
     }
-}
 
-impl TryFrom<u64> for Uint24 {
-    type Error = ToUintError<<Self as Deref>::Target>;
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.0.checked_div(rhs.0).map(Self::new_unchecked)
+    }
 
-    fn try_from(value: u64) -> Result<Self, Self::Error> {
-        Ok(Self(Uint::try_from(value)?))
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).unwrap_or(Self::MAX)
     }
-}
 
-impl Readable for Uint24 {
-    fn read<R>(reader: &mut R) -> io::Result<Self>
-    where
-        R: io::Read,
-    {
-        let mut bytes = <[u8; Self::BYTES]>::default();
-        reader.read_exact(&mut bytes)?;
-        Ok(Self::from_be_bytes(bytes))
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self::new_unchecked(self.0.saturating_sub(rhs.0))
     }
-}
 
-impl Writeable for Uint24 {
-    fn write<W>(&self, writer: &mut W) -> io::Result<()>
-    where
-        W: io::Write,
-    {
-        writer.write_all(&self.to_be_bytes())
+    pub fn saturating_mul(self, rhs: Self) -> Self {
+        self.checked_mul(rhs).unwrap_or(Self::MAX)
     }
 }
 
-/// New type for a 6-byte unsigned integer.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Uint48(Uint<48, 1>);
-
-impl Uint48 {
-    pub const ZERO: Uint48 = Self(Uint::ZERO);
-    pub const BYTES: usize = Uint::<48, 1>::BYTES;
-    pub const LAST_INDEX: usize = Self::BYTES - 1;
-
-    pub fn from_be_bytes(bytes: [u8; Self::BYTES]) -> Self {
-        let mut out = u64::default();
-        for (i, byte) in bytes.into_iter().enumerate() {
-            let value = u64::from(byte)
-                << usize::saturating_mul(8, usize::saturating_sub(Self::LAST_INDEX, i));
-            out = out.saturating_add(value);
-        }
-
-        Self(Uint::from(out))
+/// Only [u16] converts infallibly, which keeps integer literals unambiguous (e.g.
+/// `Uint24::from(500)`). Every other primitive goes through [TryFrom].
+impl<const BYTES: usize> From<u16> for WireUint<BYTES> {
+    fn from(value: u16) -> Self {
+        Self::new_unchecked(value.into())
     }
+}
 
-    pub fn from_le_bytes(bytes: [u8; Self::BYTES]) -> Self {
-        let mut out = u64::default();
-        for (i, byte) in bytes.into_iter().enumerate() {
-            let value = u64::from(byte) << usize::saturating_mul(8, i);
-            out = out.saturating_add(value);
+macro_rules! impl_try_from_int {
+    ($($int:ty),*) => {$(
+        impl<const BYTES: usize> TryFrom<$int> for WireUint<BYTES> {
+            type Error = WireUintError;
+
+            fn try_from(value: $int) -> Result<Self, Self::Error> {
+                u64::try_from(value)
+                    .ok()
+                    .and_then(Self::checked_new)
+                    .ok_or(WireUintError::OutOfRange { bytes: BYTES })
+            }
         }
+    )*};
+}
 
-        Self(Uint::from(out))
-    }
+impl_try_from_int!(u8, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
 
-    pub fn to_be_bytes(&self) -> [u8; Self::BYTES] {
-        self.0.to_be_bytes::<{ Self::BYTES }>()
-    }
+macro_rules! impl_try_into_int {
+    ($($int:ty),*) => {$(
+        impl<const BYTES: usize> TryFrom<WireUint<BYTES>> for $int {
+            type Error = WireUintError;
 
-    pub fn to_le_bytes(&self) -> [u8; Self::BYTES] {
-        self.0.to_le_bytes::<{ Self::BYTES }>()
-    }
+            fn try_from(value: WireUint<BYTES>) -> Result<Self, Self::Error> {
+                <$int>::try_from(value.0).map_err(|_| WireUintError::OutOfRange {
+                    bytes: core::mem::size_of::<$int>(),
+                })
+            }
+        }
+    )*};
 }
 
-impl Deref for Uint48 {
-    type Target = Uint<48, 1>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
+impl_try_into_int!(u8, u16, u32, usize, i8, i16, i32, i64, isize);
 
-impl From<Uint48> for Uint<48, 1> {
-    fn from(value: Uint48) -> Self {
+impl<const BYTES: usize> From<WireUint<BYTES>> for u64 {
+    fn from(value: WireUint<BYTES>) -> Self {
         value.0
     }
 }
 
-impl From<Uint48> for u64 {
-    fn from(value: Uint48) -> Self {
-        value.0.into_limbs()[0]
+impl<const BYTES: usize> From<WireUint<BYTES>> for u128 {
+    fn from(value: WireUint<BYTES>) -> Self {
+        value.0.into()
     }
 }
 
-impl From<u16> for Uint48 {
-    fn from(value: u16) -> Self {
-        Self(Uint::from(value))
+impl<const BYTES: usize> From<WireUint<BYTES>> for i128 {
+    fn from(value: WireUint<BYTES>) -> Self {
+        value.0.into()
     }
 }
 
-impl From<u32> for Uint48 {
-    fn from(value: u32) -> Self {
-        Self(Uint::from(value))
+impl<const BYTES: usize> fmt::Display for WireUint<BYTES> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
     }
 }
 
-impl TryFrom<u64> for Uint48 {
-    type Error = ToUintError<<Self as Deref>::Target>;
+impl<const BYTES: usize> FromStr for WireUint<BYTES> {
+    type Err = WireUintError;
 
-    fn try_from(value: u64) -> Result<Self, Self::Error> {
-        Ok(Self(Uint::try_from(value)?))
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<u64>().map_err(WireUintError::Parse)?.try_into()
     }
 }
 
-impl Readable for Uint48 {
+impl<const BYTES: usize> Readable for WireUint<BYTES> {
     fn read<R>(reader: &mut R) -> io::Result<Self>
     where
         R: io::Read,
     {
-        let mut bytes = <[u8; Self::BYTES]>::default();
+        let mut bytes = [0; BYTES];
         reader.read_exact(&mut bytes)?;
         Ok(Self::from_be_bytes(bytes))
     }
 }
 
-impl Writeable for Uint48 {
+impl<const BYTES: usize> Writeable for WireUint<BYTES> {
     fn write<W>(&self, writer: &mut W) -> io::Result<()>
     where
         W: io::Write,
@@ -203,6 +216,84 @@ impl Writeable for Uint48 {
     }
 }
 
+/// Borsh encodes this type as a [u32] (up to 4 bytes) or a [u64] so it can be described by an
+/// Anchor IDL.
+#[cfg(feature = "anchor")]
+impl<const BYTES: usize> anchor_lang::AnchorSerialize for WireUint<BYTES> {
+    fn serialize<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        if BYTES <= 4 {
+            let value = u32::try_from(self.0).map_err(|_| io::ErrorKind::InvalidData)?;
+            anchor_lang::AnchorSerialize::serialize(&value, writer)
+        } else {
+            anchor_lang::AnchorSerialize::serialize(&self.0, writer)
+        }
+    }
+}
+
+#[cfg(feature = "anchor")]
+impl<const BYTES: usize> anchor_lang::AnchorDeserialize for WireUint<BYTES> {
+    fn deserialize_reader<R>(reader: &mut R) -> io::Result<Self>
+    where
+        R: io::Read,
+    {
+        let value = if BYTES <= 4 {
+            <u32 as anchor_lang::AnchorDeserialize>::deserialize_reader(reader)?.into()
+        } else {
+            <u64 as anchor_lang::AnchorDeserialize>::deserialize_reader(reader)?
+        };
+        Self::try_from(value).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+/// Named after the Solidity type (e.g. `Uint24`) rather than the generic type.
+#[cfg(feature = "idl-build")]
+impl<const BYTES: usize> anchor_lang::IdlBuild for WireUint<BYTES> {
+    fn get_full_path() -> String {
+        format!("{}::Uint{}", module_path!(), BYTES.saturating_mul(8))
+    }
+
+    fn create_type() -> Option<anchor_lang::idl::types::IdlTypeDef> {
+        Some(anchor_lang::idl::types::IdlTypeDef {
+            name: Self::get_full_path(),
+            docs: Default::default(),
+            serialization: Default::default(),
+            repr: None,
+            generics: Default::default(),
+            ty: anchor_lang::idl::types::IdlTypeDefTy::Type {
+                alias: if BYTES <= 4 {
+                    anchor_lang::idl::types::IdlType::U32
+                } else {
+                    anchor_lang::idl::types::IdlType::U64
+                },
+            },
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const BYTES: usize> serde::Serialize for WireUint<BYTES> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const BYTES: usize> serde::Deserialize<'de> for WireUint<BYTES> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = <u64 as serde::Deserialize>::deserialize(deserializer)?;
+        Self::try_from(value).map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use hex_literal::hex;
@@ -215,11 +306,11 @@ mod test {
 
         let encoded = hex!("000045");
         let value = Uint24::from_be_bytes(encoded);
-        assert_eq!(value.0, Uint::from(EXPECTED));
+        assert_eq!(u64::from(value), EXPECTED);
         assert_eq!(value.to_be_bytes(), encoded);
 
         let value = <Uint24 as Readable>::read(&mut &encoded[..]).unwrap();
-        assert_eq!(value.0, Uint::from(EXPECTED));
+        assert_eq!(u64::from(value), EXPECTED);
 
         let mut written = [0u8; 3];
         value.write(&mut written.as_mut_slice()).unwrap();
@@ -232,11 +323,11 @@ mod test {
 
         let encoded = hex!("434445");
         let value = Uint24::from_be_bytes(encoded);
-        assert_eq!(value.0, Uint::from(EXPECTED));
+        assert_eq!(u64::from(value), EXPECTED);
         assert_eq!(value.to_be_bytes(), encoded);
 
         let value = <Uint24 as Readable>::read(&mut &encoded[..]).unwrap();
-        assert_eq!(value.0, Uint::from(EXPECTED));
+        assert_eq!(u64::from(value), EXPECTED);
 
         let mut written = [0u8; 3];
         value.write(&mut written.as_mut_slice()).unwrap();
@@ -249,11 +340,11 @@ mod test {
 
         let encoded = hex!("000000000045");
         let value = Uint48::from_be_bytes(encoded);
-        assert_eq!(value.0, Uint::from(EXPECTED));
+        assert_eq!(u64::from(value), EXPECTED);
         assert_eq!(value.to_be_bytes(), encoded);
 
         let value = <Uint48 as Readable>::read(&mut &encoded[..]).unwrap();
-        assert_eq!(value.0, Uint::from(EXPECTED));
+        assert_eq!(u64::from(value), EXPECTED);
 
         let mut written = [0u8; 6];
         value.write(&mut written.as_mut_slice()).unwrap();
@@ -266,14 +357,122 @@ mod test {
 
         let encoded = hex!("404142434445");
         let value = Uint48::from_be_bytes(encoded);
-        assert_eq!(value.0, Uint::from(EXPECTED));
+        assert_eq!(u64::from(value), EXPECTED);
         assert_eq!(value.to_be_bytes(), encoded);
 
         let value = <Uint48 as Readable>::read(&mut &encoded[..]).unwrap();
-        assert_eq!(value.0, Uint::from(EXPECTED));
+        assert_eq!(u64::from(value), EXPECTED);
 
         let mut written = [0u8; 6];
         value.write(&mut written.as_mut_slice()).unwrap();
         assert_eq!(written, encoded);
     }
+
+    #[test]
+    fn test_uint_max() {
+        assert_eq!(Uint24::MAX.to_be_bytes(), hex!("ffffff"));
+        assert_eq!(u64::from(Uint48::MAX), 0xffff_ffff_ffff);
+        assert_eq!(u64::from(WireUint::<8>::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_uint_le() {
+        let value = Uint48::from_le_bytes(hex!("454443424140"));
+        assert_eq!(value, Uint48::from_be_bytes(hex!("404142434445")));
+        assert_eq!(value.to_le_bytes(), hex!("454443424140"));
+    }
+
+    #[test]
+    fn test_uint_try_from() {
+        assert_eq!(Uint24::try_from(0xffffffu32), Ok(Uint24::MAX));
+        assert_eq!(
+            Uint24::try_from(0x1000000u32),
+            Err(WireUintError::OutOfRange { bytes: 3 })
+        );
+        assert_eq!(
+            Uint48::try_from(-1i64),
+            Err(WireUintError::OutOfRange { bytes: 6 })
+        );
+        assert_eq!(
+            Uint48::try_from(u128::MAX),
+            Err(WireUintError::OutOfRange { bytes: 6 })
+        );
+        assert_eq!(Uint48::try_from(69u8), Ok(Uint48::from(69)));
+        assert_eq!(Uint48::try_from(69usize), Ok(Uint48::from(69)));
+
+        assert_eq!(u32::try_from(Uint24::MAX), Ok(0xffffff));
+        assert_eq!(
+            u16::try_from(Uint24::MAX),
+            Err(WireUintError::OutOfRange { bytes: 2 })
+        );
+        assert_eq!(u128::from(Uint48::MAX), 0xffff_ffff_ffff);
+    }
+
+    #[test]
+    fn test_uint_checked_arithmetic() {
+        let one = Uint24::from(1);
+        let two = Uint24::from(2);
+
+        assert_eq!(one.checked_add(two), Some(Uint24::from(3)));
+        assert_eq!(Uint24::MAX.checked_add(one), None);
+        assert_eq!(two.checked_sub(one), Some(one));
+        assert_eq!(one.checked_sub(two), None);
+        assert_eq!(
+            Uint24::from(3000).checked_mul(two),
+            Some(Uint24::from(6000))
+        );
+        assert_eq!(Uint24::MAX.checked_mul(two), None);
+        assert_eq!(
+            Uint24::from(6000).checked_div(two),
+            Some(Uint24::from(3000))
+        );
+        assert_eq!(one.checked_div(Uint24::ZERO), None);
+    }
+
+    #[test]
+    fn test_uint_saturating_arithmetic() {
+        let one = Uint48::from(1);
+
+        assert_eq!(Uint48::MAX.saturating_add(one), Uint48::MAX);
+        assert_eq!(Uint48::ZERO.saturating_sub(one), Uint48::ZERO);
+        assert_eq!(Uint48::MAX.saturating_mul(Uint48::from(2)), Uint48::MAX);
+        assert_eq!(one.saturating_add(one), Uint48::from(2));
+    }
+
+    #[test]
+    fn test_uint_display_from_str() {
+        let value = "4408389".parse::<Uint24>().unwrap();
+        assert_eq!(value, Uint24::from_be_bytes(hex!("434445")));
+        assert_eq!(value.to_string(), "4408389");
+
+        assert_eq!(
+            "16777216".parse::<Uint24>(),
+            Err(WireUintError::OutOfRange { bytes: 3 })
+        );
+        assert!(matches!(
+            "0x45".parse::<Uint24>(),
+            Err(WireUintError::Parse(_))
+        ));
+    }
+
+    #[cfg(feature = "anchor")]
+    #[test]
+    fn test_uint_borsh() {
+        use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+
+        let value = Uint24::from_be_bytes(hex!("434445"));
+        let encoded = value.try_to_vec().unwrap();
+        assert_eq!(encoded, hex!("45444300"));
+        assert_eq!(Uint24::try_from_slice(&encoded).unwrap(), value);
+
+        // Values that do not fit in 3 bytes are rejected.
+        assert!(Uint24::try_from_slice(&hex!("00000001")).is_err());
+
+        let value = Uint48::from_be_bytes(hex!("404142434445"));
+        let encoded = value.try_to_vec().unwrap();
+        assert_eq!(encoded, hex!("4544434241400000"));
+        assert_eq!(Uint48::try_from_slice(&encoded).unwrap(), value);
+
+        assert!(Uint48::try_from_slice(&hex!("0000000000000100")).is_err());
+    }
 }
@@ -0,0 +1,228 @@
+//! Stand-in for the `wormhole-io` crate, which requires `std`. Without the `std` feature, this
+//! module provides the same traits (over [crate::io]) so the swap message types keep a single
+//! implementation of their encoding.
+
+use core::{fmt, marker::PhantomData, ops::Deref};
+
+use alloc::vec::Vec;
+
+use crate::io;
+
+pub trait Readable {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        Self: Sized,
+        R: io::Read;
+}
+
+pub trait Writeable {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write;
+}
+
+macro_rules! impl_for_int {
+    ($($int:ty),*) => {$(
+        impl Readable for $int {
+            fn read<R>(reader: &mut R) -> io::Result<Self>
+            where
+                R: io::Read,
+            {
+                let mut bytes = [0; core::mem::size_of::<$int>()];
+                reader.read_exact(&mut bytes)?;
+                Ok(<$int>::from_be_bytes(bytes))
+            }
+        }
+
+        impl Writeable for $int {
+            fn write<W>(&self, writer: &mut W) -> io::Result<()>
+            where
+                W: io::Write,
+            {
+                writer.write_all(&self.to_be_bytes())
+            }
+        }
+    )*};
+}
+
+impl_for_int!(u8, u16, u32, u64, u128);
+
+impl Readable for bool {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        R: io::Read,
+    {
+        match u8::read(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(io::ErrorKind::InvalidData.into()),
+        }
+    }
+}
+
+impl Writeable for bool {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        u8::from(*self).write(writer)
+    }
+}
+
+impl<const N: usize> Readable for [u8; N] {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        R: io::Read,
+    {
+        let mut bytes = [0; N];
+        reader.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+impl<const N: usize> Writeable for [u8; N] {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        writer.write_all(self)
+    }
+}
+
+impl<T: Readable> Readable for Option<T> {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        R: io::Read,
+    {
+        match bool::read(reader)? {
+            true => T::read(reader).map(Some),
+            false => Ok(None),
+        }
+    }
+}
+
+impl<T: Writeable> Writeable for Option<T> {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        match self {
+            Some(value) => {
+                true.write(writer)?;
+                value.write(writer)
+            }
+            None => false.write(writer),
+        }
+    }
+}
+
+/// Bytes prefixed with their length, which is encoded as `L`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Hash)]
+pub struct WriteableBytes<L> {
+    inner: Vec<u8>,
+    phantom: PhantomData<L>,
+}
+
+impl<L> WriteableBytes<L> {
+    pub fn new(inner: Vec<u8>) -> Self {
+        Self {
+            inner,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<L> Deref for WriteableBytes<L> {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<L> From<WriteableBytes<L>> for Vec<u8> {
+    fn from(bytes: WriteableBytes<L>) -> Self {
+        bytes.inner
+    }
+}
+
+impl TryFrom<Vec<u8>> for WriteableBytes<u16> {
+    type Error = <u16 as TryFrom<usize>>::Error;
+
+    fn try_from(inner: Vec<u8>) -> Result<Self, Self::Error> {
+        u16::try_from(inner.len())?;
+        Ok(Self::new(inner))
+    }
+}
+
+impl Readable for WriteableBytes<u16> {
+    fn read<R>(reader: &mut R) -> io::Result<Self>
+    where
+        R: io::Read,
+    {
+        let mut inner = alloc::vec![0; usize::from(u16::read(reader)?)];
+        reader.read_exact(&mut inner)?;
+        Ok(Self::new(inner))
+    }
+}
+
+impl Writeable for WriteableBytes<u16> {
+    fn write<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        u16::try_from(self.inner.len())
+            .map_err(|_| io::ErrorKind::InvalidInput)?
+            .write(writer)?;
+        writer.write_all(&self.inner)
+    }
+}
+
+/// Payload prefixed with its `N`-byte type, if it has one.
+pub trait TypePrefixedPayload<const N: usize>: Readable + Writeable + Clone + fmt::Debug {
+    const TYPE: Option<[u8; N]>;
+
+    /// Encoded size, excluding the type prefix.
+    fn written_size(&self) -> usize;
+
+    fn read_payload<R>(reader: &mut R) -> io::Result<Self>
+    where
+        R: io::Read,
+    {
+        if let Some(expected) = Self::TYPE {
+            if <[u8; N]>::read(reader)? != expected {
+                return Err(io::ErrorKind::InvalidData.into());
+            }
+        }
+
+        Self::read(reader)
+    }
+
+    /// Read a payload, which must span the entire slice.
+    fn read_slice(mut buf: &[u8]) -> io::Result<Self> {
+        let payload = Self::read_payload(&mut buf)?;
+        match buf.is_empty() {
+            true => Ok(payload),
+            false => Err(io::ErrorKind::InvalidData.into()),
+        }
+    }
+
+    fn write_payload<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        if let Some(prefix) = Self::TYPE {
+            prefix.write(writer)?;
+        }
+
+        self.write(writer)
+    }
+
+    /// Encode with the type prefix. Like `wormhole-io`, this panics if the payload cannot be
+    /// encoded. Use [Codec::try_encode](crate::error::Codec::try_encode) to handle the error.
+    fn to_vec(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.written_size().saturating_add(N));
+        self.write_payload(&mut buf).unwrap();
+        buf
+    }
+}